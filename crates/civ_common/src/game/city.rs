@@ -4,7 +4,10 @@ use std::{
 };
 
 use super::unit::UnitType;
+use crate::{geo::WorldPoint, rules::TileYield, world::Resource};
 use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+use thiserror::Error;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -62,42 +65,277 @@ impl Sub for CityProductionTons {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum CityProduct {
     Unit(UnitType),
+    Building(BuildingType),
 }
 
 impl Display for CityProduct {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             CityProduct::Unit(unit_type) => f.write_str(&unit_type.to_string()),
+            CityProduct::Building(building) => f.write_str(&building.to_string()),
         }
     }
 }
 
+/// City improvement, built once and for all. Its effects are given by the ruleset.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter)]
+pub enum BuildingType {
+    Granary,
+    Barracks,
+    Walls,
+    Library,
+}
+
+impl Display for BuildingType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildingType::Granary => f.write_str("Granary"),
+            BuildingType::Barracks => f.write_str("Barracks"),
+            BuildingType::Walls => f.write_str("Walls"),
+            BuildingType::Library => f.write_str("Library"),
+        }
+    }
+}
+
+/// City production queue: first product is the one currently produced
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct CityProduction {
     stack: Vec<CityProduct>,
+    /// Tons stored toward current product when its production started (surplus of
+    /// previous products included)
+    #[serde(default)]
+    tons: CityProductionTons,
+    /// Completed products are queued again instead of being dropped
+    #[serde(default)]
+    repeat: bool,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CityProductionError {
+    #[error("Production queue can't be empty")]
+    Empty,
+    #[error("No product at position {0}")]
+    OutOfQueue(usize),
+    #[error("{0} can't be produced")]
+    Unavailable(CityProduct),
+    #[error("{0} is already built")]
+    AlreadyBuilt(BuildingType),
+    #[error("{0} is already queued")]
+    AlreadyQueued(BuildingType),
+    #[error("{0} is required but not in city work radius")]
+    MissingResource(Resource),
 }
 
 impl CityProduction {
     pub fn new(stack: Vec<CityProduct>) -> Self {
-        Self { stack }
+        Self {
+            stack,
+            tons: CityProductionTons(0),
+            repeat: false,
+        }
     }
 
     pub fn current(&self) -> &CityProduct {
         self.stack.first().expect("One item is mandatory")
     }
+
+    pub fn stack(&self) -> &[CityProduct] {
+        &self.stack
+    }
+
+    pub fn tons(&self) -> CityProductionTons {
+        self.tons
+    }
+
+    pub fn set_tons(&mut self, tons: CityProductionTons) {
+        self.tons = tons;
+    }
+
+    pub fn repeat(&self) -> bool {
+        self.repeat
+    }
+
+    pub fn set_repeat(&mut self, repeat: bool) {
+        self.repeat = repeat;
+    }
+
+    pub fn push(&mut self, product: CityProduct) {
+        self.stack.push(product);
+    }
+
+    pub fn remove(&mut self, index: usize) -> Result<CityProduct, CityProductionError> {
+        if index >= self.stack.len() {
+            return Err(CityProductionError::OutOfQueue(index));
+        }
+        if self.stack.len() == 1 {
+            return Err(CityProductionError::Empty);
+        }
+
+        Ok(self.stack.remove(index))
+    }
+
+    /// Move product at `from` position to `to` position
+    pub fn move_(&mut self, from: usize, to: usize) -> Result<(), CityProductionError> {
+        if from >= self.stack.len() {
+            return Err(CityProductionError::OutOfQueue(from));
+        }
+        if to >= self.stack.len() {
+            return Err(CityProductionError::OutOfQueue(to));
+        }
+
+        let product = self.stack.remove(from);
+        self.stack.insert(to, product);
+        Ok(())
+    }
+
+    /// Current product is done: it is queued again if repeating (units only, as a building
+    /// is built once), and `default` is produced when nothing else is queued. Queued
+    /// buildings the city has (`built`, or the done one) are dropped.
+    pub fn next(&mut self, default: &CityProduct, built: &[BuildingType]) -> CityProduct {
+        let done = self.stack.remove(0);
+
+        if self.repeat && matches!(done, CityProduct::Unit(_)) {
+            self.stack.push(done.clone());
+        }
+        self.stack.retain(|product| match product {
+            CityProduct::Unit(_) => true,
+            CityProduct::Building(building) => !built.contains(building) && product != &done,
+        });
+        if self.stack.is_empty() {
+            self.stack.push(default.clone());
+        }
+
+        done
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+/// Tiles worked by city citizens (city tile first) and their total yield
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
 pub struct CityExploitation {
-    tons: CityProductionTons,
+    tiles: Vec<WorldPoint>,
+    yield_: TileYield,
 }
 
 impl CityExploitation {
-    pub fn new(tons: CityProductionTons) -> Self {
-        Self { tons }
+    pub fn new(tiles: Vec<WorldPoint>, yield_: TileYield) -> Self {
+        Self { tiles, yield_ }
+    }
+
+    pub fn tiles(&self) -> &[WorldPoint] {
+        &self.tiles
+    }
+
+    pub fn yield_(&self) -> &TileYield {
+        &self.yield_
+    }
+
+    /// A city always produces at least one ton
+    pub fn production_tons(&self) -> CityProductionTons {
+        CityProductionTons(self.yield_.production.max(1))
+    }
+}
+
+/// City citizens count and food stored toward its next citizen
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct CityPopulation {
+    size: u64,
+    food: u64,
+}
+
+impl CityPopulation {
+    pub fn new(size: u64, food: u64) -> Self {
+        Self { size, food }
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn food(&self) -> u64 {
+        self.food
+    }
+}
+
+impl Default for CityPopulation {
+    fn default() -> Self {
+        Self { size: 1, food: 0 }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SETTLERS: CityProduct = CityProduct::Unit(UnitType::Settlers);
+    const WARRIORS: CityProduct = CityProduct::Unit(UnitType::Warriors);
+
+    #[test]
+    fn test_production_queue() {
+        // Given
+        let mut production = CityProduction::new(vec![SETTLERS]);
+
+        // When
+        production.push(WARRIORS);
+        production.push(SETTLERS);
+        production.move_(2, 1).unwrap();
+
+        // Then
+        assert_eq!(production.stack(), &[SETTLERS, SETTLERS, WARRIORS]);
+        assert_eq!(
+            production.move_(0, 3),
+            Err(CityProductionError::OutOfQueue(3))
+        );
+        assert_eq!(production.remove(1), Ok(SETTLERS));
+        assert_eq!(production.remove(1), Ok(WARRIORS));
+        assert_eq!(production.remove(0), Err(CityProductionError::Empty));
+    }
+
+    #[test]
+    fn test_production_next() {
+        // Given
+        let mut production = CityProduction::new(vec![SETTLERS, WARRIORS]);
+
+        // When
+        let done = production.next(&WARRIORS, &[]);
+
+        // Then
+        assert_eq!(done, SETTLERS);
+        assert_eq!(production.stack(), &[WARRIORS]);
+
+        // When (nothing else is queued)
+        production.next(&SETTLERS, &[]);
+
+        // Then (default is produced)
+        assert_eq!(production.stack(), &[SETTLERS]);
+
+        // When
+        production.set_repeat(true);
+        production.push(WARRIORS);
+        production.next(&WARRIORS, &[]);
+
+        // Then
+        assert_eq!(production.stack(), &[WARRIORS, SETTLERS]);
     }
 
-    pub fn production_tons(&self) -> &CityProductionTons {
-        &self.tons
+    #[test]
+    fn test_production_next_repeat_with_buildings() {
+        // Given
+        let granary = CityProduct::Building(BuildingType::Granary);
+        let walls = CityProduct::Building(BuildingType::Walls);
+        let mut production = CityProduction::new(vec![granary.clone(), WARRIORS, walls.clone()]);
+        production.set_repeat(true);
+
+        // When
+        let done = production.next(&SETTLERS, &[]);
+
+        // Then (built building is not queued again)
+        assert_eq!(done, granary);
+        assert_eq!(production.stack(), &[WARRIORS, walls.clone()]);
+
+        // When (walls got built meanwhile)
+        production.next(&SETTLERS, &[BuildingType::Walls]);
+
+        // Then
+        assert_eq!(production.stack(), &[WARRIORS]);
     }
 }
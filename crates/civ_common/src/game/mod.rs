@@ -11,6 +11,8 @@ use uuid::Uuid;
 
 pub mod city;
 pub mod slice;
+pub mod tech;
+pub mod treasury;
 pub mod unit;
 
 pub const GAME_FRAMES_PER_SECOND: u64 = 10;
@@ -1,23 +1,23 @@
 use serde::{Deserialize, Serialize};
 
-use crate::rules::RuleSetType;
+use crate::rules::file::FileRuleSet;
 
 use super::nation::flag::Flag;
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ServerResume {
-    /// Rules applied on server
-    rules: RuleSetType,
+    /// Rules applied on server, which clients must play with
+    rules: FileRuleSet,
     /// Current flags on server
     flags: Vec<Flag>,
 }
 
 impl ServerResume {
-    pub fn new(rules: RuleSetType, flags: Vec<Flag>) -> Self {
+    pub fn new(rules: FileRuleSet, flags: Vec<Flag>) -> Self {
         Self { rules, flags }
     }
 
-    pub fn rules(&self) -> &RuleSetType {
+    pub fn rules(&self) -> &FileRuleSet {
         &self.rules
     }
 
@@ -10,7 +10,9 @@ use crate::{
 };
 
 use super::{
-    city::{CityExploitation, CityId, CityProduction},
+    city::{
+        BuildingType, CityExploitation, CityId, CityPopulation, CityProduction, CityProductionTons,
+    },
     nation::flag::Flag,
     tasks::client::{city::production::ClientCityProductionTask, ClientTask},
     unit::{UnitCan, UnitId, UnitType},
@@ -154,6 +156,10 @@ impl GameSlice {
         &self.tiles
     }
 
+    pub fn tiles_mut(&mut self) -> &mut Slice<CtxTile<Tile>> {
+        &mut self.tiles
+    }
+
     pub fn center(&self) -> ImaginaryWorldPoint {
         self.imaginary_world_point_for_center_rel((0, 0))
     }
@@ -230,6 +236,34 @@ impl GameSlice {
     }
 }
 
+/// A tile which visibility changed, with the city and units now seen on it
+#[derive(Builder, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ClientTile {
+    point: WorldPoint,
+    tile: CtxTile<Tile>,
+    city: Option<ClientCity>,
+    #[builder(default)]
+    units: Vec<ClientUnit>,
+}
+
+impl ClientTile {
+    pub fn point(&self) -> &WorldPoint {
+        &self.point
+    }
+
+    pub fn tile(&self) -> &CtxTile<Tile> {
+        &self.tile
+    }
+
+    pub fn city(&self) -> Option<&ClientCity> {
+        self.city.as_ref()
+    }
+
+    pub fn units(&self) -> &[ClientUnit] {
+        &self.units
+    }
+}
+
 #[derive(Builder, Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct ClientCity {
     id: CityId,
@@ -238,6 +272,10 @@ pub struct ClientCity {
     geo: GeoContext,
     production: CityProduction,
     exploitation: CityExploitation,
+    #[builder(default)]
+    population: CityPopulation,
+    #[builder(default)]
+    buildings: Vec<BuildingType>,
     tasks: ClientCityTasks,
 }
 
@@ -269,6 +307,31 @@ impl ClientCity {
     pub fn tasks(&self) -> &ClientCityTasks {
         &self.tasks
     }
+
+    pub fn production(&self) -> &CityProduction {
+        &self.production
+    }
+
+    /// Tons stored toward current product at given frame
+    pub fn stored_tons(&self, frame: &GameFrame) -> CityProductionTons {
+        self.production.tons() + self.tasks.production.produced(frame).0
+    }
+
+    pub fn exploitation(&self) -> &CityExploitation {
+        &self.exploitation
+    }
+
+    pub fn population(&self) -> &CityPopulation {
+        &self.population
+    }
+
+    pub fn size(&self) -> u64 {
+        self.population.size()
+    }
+
+    pub fn buildings(&self) -> &[BuildingType] {
+        &self.buildings
+    }
 }
 
 #[derive(Builder, Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -294,6 +357,11 @@ pub struct ClientUnit {
     geo: GeoContext,
     task: Option<ClientTask>,
     can: Vec<UnitCan>,
+    #[builder(default)]
+    veteran: bool,
+    /// Hit points lost in combats
+    #[builder(default)]
+    damage: u64,
 }
 
 impl ClientUnit {
@@ -321,6 +389,14 @@ impl ClientUnit {
         &self.task
     }
 
+    pub fn veteran(&self) -> bool {
+        self.veteran
+    }
+
+    pub fn damage(&self) -> u64 {
+        self.damage
+    }
+
     pub fn can(&self) -> &[UnitCan] {
         &self.can
     }
@@ -0,0 +1,26 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::unit::AttackTarget;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ClientAttack {
+    target: AttackTarget,
+}
+
+impl ClientAttack {
+    pub fn new(target: AttackTarget) -> Self {
+        Self { target }
+    }
+
+    pub fn target(&self) -> &AttackTarget {
+        &self.target
+    }
+}
+
+impl Display for ClientAttack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!("Attack {}", self.target))
+    }
+}
@@ -1,16 +1,24 @@
 use serde::{Deserialize, Serialize};
 
-use crate::game::GameFrame;
+use crate::game::{city::CityProductionTons, GameFrame, PRODUCTION_FRAMES_PER_TONS};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct ClientCityProductionTask {
     start: GameFrame,
     end: GameFrame,
+    /// Production rate
+    tons: CityProductionTons,
 }
 
 impl ClientCityProductionTask {
-    pub fn new(start: GameFrame, end: GameFrame) -> Self {
-        Self { start, end }
+    pub fn new(start: GameFrame, end: GameFrame, tons: CityProductionTons) -> Self {
+        Self { start, end, tons }
+    }
+
+    /// Tons produced since task start
+    pub fn produced(&self, frame: &GameFrame) -> CityProductionTons {
+        let frames = frame.0.min(self.end.0).saturating_sub(self.start.0);
+        CityProductionTons(self.tons.0 * frames / PRODUCTION_FRAMES_PER_TONS)
     }
 
     pub fn progress(&self, frame: &GameFrame) -> f32 {
@@ -1,7 +1,11 @@
+pub mod attack;
 pub mod city;
+pub mod move_;
 pub mod settle;
 
+use attack::ClientAttack;
 use derive_more::Constructor;
+use move_::ClientMove;
 use serde::{Deserialize, Serialize};
 use settle::ClientSettle;
 
@@ -39,6 +43,8 @@ impl ClientTask {
                     (self.progress(frame).current * 100.0) as u8
                 )
             }
+            ClientTaskType::Move(task) => task.to_string(),
+            ClientTaskType::Attack(task) => task.to_string(),
         }
     }
 }
@@ -68,4 +74,6 @@ impl std::ops::DerefMut for ClientTaskProgress {
 pub enum ClientTaskType {
     Idle,
     Settle(ClientSettle),
+    Move(ClientMove),
+    Attack(ClientAttack),
 }
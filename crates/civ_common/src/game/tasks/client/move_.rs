@@ -0,0 +1,29 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::geo::WorldPoint;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ClientMove {
+    destination: WorldPoint,
+}
+
+impl ClientMove {
+    pub fn new(destination: WorldPoint) -> Self {
+        Self { destination }
+    }
+
+    pub fn destination(&self) -> &WorldPoint {
+        &self.destination
+    }
+}
+
+impl Display for ClientMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!(
+            "Move to {}.{}",
+            self.destination.x, self.destination.y
+        ))
+    }
+}
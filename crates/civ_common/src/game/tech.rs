@@ -0,0 +1,121 @@
+use std::fmt::Display;
+
+use derive_more::Constructor;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    city::{BuildingType, CityProduct},
+    unit::{UnitTaskType, UnitType},
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TechType {
+    Alphabet,
+    Writing,
+    Pottery,
+    Masonry,
+    BronzeWorking,
+    WarriorCode,
+}
+
+impl Display for TechType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TechType::Alphabet => f.write_str("Alphabet"),
+            TechType::Writing => f.write_str("Writing"),
+            TechType::Pottery => f.write_str("Pottery"),
+            TechType::Masonry => f.write_str("Masonry"),
+            TechType::BronzeWorking => f.write_str("Bronze working"),
+            TechType::WarriorCode => f.write_str("Warrior code"),
+        }
+    }
+}
+
+/// What a tech gives access to. Terrain improvements are unit tasks.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum TechUnlock {
+    Unit(UnitType),
+    Building(BuildingType),
+    Task(UnitTaskType),
+}
+
+impl From<&CityProduct> for TechUnlock {
+    fn from(value: &CityProduct) -> Self {
+        match value {
+            CityProduct::Unit(unit_type) => TechUnlock::Unit(*unit_type),
+            CityProduct::Building(building) => TechUnlock::Building(*building),
+        }
+    }
+}
+
+/// Tech tree node, as described by the ruleset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Constructor)]
+pub struct Tech {
+    type_: TechType,
+    prerequisites: Vec<TechType>,
+    /// Science needed to learn it
+    cost: u64,
+    unlocks: Vec<TechUnlock>,
+}
+
+impl Tech {
+    pub fn type_(&self) -> &TechType {
+        &self.type_
+    }
+
+    pub fn prerequisites(&self) -> &[TechType] {
+        &self.prerequisites
+    }
+
+    pub fn cost(&self) -> u64 {
+        self.cost
+    }
+
+    pub fn unlocks(&self) -> &[TechUnlock] {
+        &self.unlocks
+    }
+}
+
+/// Research of a player: known techs, and science stored for the current one
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Constructor)]
+pub struct Research {
+    known: Vec<TechType>,
+    current: Option<TechType>,
+    science: u64,
+}
+
+impl Research {
+    pub fn known(&self) -> &[TechType] {
+        &self.known
+    }
+
+    pub fn knows(&self, tech: &TechType) -> bool {
+        self.known.contains(tech)
+    }
+
+    pub fn current(&self) -> Option<&TechType> {
+        self.current.as_ref()
+    }
+
+    pub fn set_current(&mut self, current: Option<TechType>) {
+        self.current = current;
+    }
+
+    pub fn science(&self) -> u64 {
+        self.science
+    }
+
+    pub fn set_science(&mut self, science: u64) {
+        self.science = science;
+    }
+
+    /// Given tech becomes known, and is no longer researched
+    pub fn learn(&mut self, tech: TechType) {
+        if !self.knows(&tech) {
+            self.known.push(tech);
+        }
+        if self.current == Some(tech) {
+            self.current = None;
+        }
+    }
+}
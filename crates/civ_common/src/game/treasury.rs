@@ -0,0 +1,66 @@
+use derive_more::Constructor;
+use serde::{Deserialize, Serialize};
+
+/// Part of trade (in percent) given to gold when the player didn't choose one
+pub const DEFAULT_TAX_RATE: u8 = 50;
+
+/// Gold of a player, and the tax rate splitting its cities trade into gold and science
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Constructor)]
+pub struct Treasury {
+    gold: u64,
+    /// Part of trade (in percent) given to gold, the rest goes to science
+    tax_rate: u8,
+}
+
+impl Default for Treasury {
+    fn default() -> Self {
+        Self::new(0, DEFAULT_TAX_RATE)
+    }
+}
+
+impl Treasury {
+    pub fn gold(&self) -> u64 {
+        self.gold
+    }
+
+    pub fn set_gold(&mut self, gold: u64) {
+        self.gold = gold;
+    }
+
+    pub fn tax_rate(&self) -> u8 {
+        self.tax_rate
+    }
+
+    pub fn set_tax_rate(&mut self, tax_rate: u8) {
+        self.tax_rate = tax_rate;
+    }
+
+    /// Gold and science parts of given trade
+    pub fn split(&self, trade: u64) -> (u64, u64) {
+        let gold = trade * self.tax_rate as u64 / 100;
+        (gold, trade - gold)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(50, 10, (5, 5))]
+    #[case(50, 3, (1, 2))]
+    #[case(0, 10, (0, 10))]
+    #[case(100, 10, (10, 0))]
+    fn test_split(#[case] tax_rate: u8, #[case] trade: u64, #[case] expected: (u64, u64)) {
+        // GIVEN
+        let treasury = Treasury::new(0, tax_rate);
+
+        // WHEN
+        let split = treasury.split(trade);
+
+        // THEN
+        assert_eq!(split, expected);
+    }
+}
@@ -1,9 +1,10 @@
 use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
+use strum_macros::{EnumIter, EnumString};
 use uuid::Uuid;
 
-use super::city::CityProductionTons;
+use super::city::{CityId, CityProductionTons};
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct UnitId(pub Uuid);
@@ -32,7 +33,7 @@ impl From<Uuid> for UnitId {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, EnumIter, EnumString)]
 pub enum UnitType {
     Warriors,
     Settlers,
@@ -47,10 +48,28 @@ impl Display for UnitType {
     }
 }
 
+/// What a unit can attack: a unit on a neighbor tile, or a neighbor city (through its
+/// defenders, then by capturing it)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum AttackTarget {
+    Unit(UnitId),
+    City(CityId),
+}
+
+impl Display for AttackTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttackTarget::Unit(unit_id) => f.write_str(&format!("unit {}", unit_id)),
+            AttackTarget::City(city_id) => f.write_str(&format!("city {}", city_id)),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum TaskType {
     City(CityTaskType),
     Unit(UnitTaskType),
+    Player(PlayerTaskType),
     System(SystemTaskType),
     Testing,
 }
@@ -58,11 +77,20 @@ pub enum TaskType {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum UnitTaskType {
     Settle,
+    Move,
+    Attack,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum CityTaskType {
     Production(CityProductionTons),
+    Growth,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum PlayerTaskType {
+    Research,
+    Upkeep,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -74,7 +102,12 @@ impl Display for TaskType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             TaskType::Unit(UnitTaskType::Settle) => f.write_str("Settle"),
+            TaskType::Unit(UnitTaskType::Move) => f.write_str("Move"),
+            TaskType::Unit(UnitTaskType::Attack) => f.write_str("Attack"),
             TaskType::City(CityTaskType::Production(_)) => f.write_str("Production"),
+            TaskType::City(CityTaskType::Growth) => f.write_str("Growth"),
+            TaskType::Player(PlayerTaskType::Research) => f.write_str("Research"),
+            TaskType::Player(PlayerTaskType::Upkeep) => f.write_str("Upkeep"),
             TaskType::Testing => f.write_str("Testing"),
             TaskType::System(SystemTaskType::Snapshot) => f.write_str("Snapshot"),
         }
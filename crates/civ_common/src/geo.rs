@@ -2,6 +2,9 @@ use bon::Builder;
 use derive_more::Constructor;
 use glam::{U64Vec2, UVec2, Vec2};
 use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::hex::Cube;
 
 #[derive(
     Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default,
@@ -61,6 +64,56 @@ impl WorldPoint {
     }
 }
 
+/// Direction from a tile to one of its (hexagonal) neighbors, see [`crate::hex`]
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, EnumIter)]
+pub enum Direction {
+    East,
+    NorthEast,
+    NorthWest,
+    West,
+    SouthWest,
+    SouthEast,
+}
+
+impl Direction {
+    /// Axial coordinates delta
+    pub fn delta(&self) -> (i64, i64) {
+        match self {
+            Direction::East => (1, 0),
+            Direction::NorthEast => (1, -1),
+            Direction::NorthWest => (0, -1),
+            Direction::West => (-1, 0),
+            Direction::SouthWest => (-1, 1),
+            Direction::SouthEast => (0, 1),
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            Direction::East => Direction::West,
+            Direction::NorthEast => Direction::SouthWest,
+            Direction::NorthWest => Direction::SouthEast,
+            Direction::West => Direction::East,
+            Direction::SouthWest => Direction::NorthEast,
+            Direction::SouthEast => Direction::NorthWest,
+        }
+    }
+
+    /// Direction from `from` to `to` if they are neighbors
+    pub fn between(from: &WorldPoint, to: &WorldPoint) -> Option<Self> {
+        let delta = (to.x as i64 - from.x as i64, to.y as i64 - from.y as i64);
+        match delta {
+            (1, 0) => Some(Direction::East),
+            (1, -1) => Some(Direction::NorthEast),
+            (0, -1) => Some(Direction::NorthWest),
+            (-1, 0) => Some(Direction::West),
+            (-1, 1) => Some(Direction::SouthWest),
+            (0, 1) => Some(Direction::SouthEast),
+            _ => None,
+        }
+    }
+}
+
 impl From<(u64, u64)> for WorldPoint {
     fn from(value: (u64, u64)) -> Self {
         Self {
@@ -100,19 +153,14 @@ impl ImaginaryWorldPoint {
     }
 
     pub fn from_iso(size: &UVec2, point: &Vec2) -> Self {
-        // Shift the point up by half the sprite's height
-        // because sprite is displayed by using center anchor.
-        let point = Vec2::new(point.x, point.y + size.y as f32 / 2.0);
-
+        // Sprites are displayed by using center anchor, so tiles centers are on integer
+        // coordinates: pointed tile is the nearest hexagon.
         let (x, y) = (
             (point.x / size.x as f32 + point.y / size.y as f32),
             (point.y / size.y as f32 - point.x / size.x as f32),
         );
 
-        Self {
-            x: x as i64,
-            y: y as i64,
-        }
+        Cube::round(x as f64, y as f64).into()
     }
 
     pub fn relative_to(&self, pos: (i32, i32)) -> Option<Self> {
@@ -0,0 +1,229 @@
+//! Hexagonal grid geometry. World points are axial coordinates (`x` is `q`, `y` is `r`):
+//! the isometric projection used to draw the world displays them as pointy hexagons, where
+//! `(1, -1)` and `(-1, 1)` are left and right neighbors, while `(1, 1)` and `(-1, -1)`
+//! (top and bottom) are two steps away.
+use strum::IntoEnumIterator;
+
+use crate::geo::{Direction, ImaginaryWorldPoint, WorldPoint};
+
+/// Cube coordinates, where `q + r + s == 0`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cube {
+    pub q: i64,
+    pub r: i64,
+    pub s: i64,
+}
+
+impl Cube {
+    pub fn new(q: i64, r: i64) -> Self {
+        Self { q, r, s: -q - r }
+    }
+
+    /// Nearest hexagon of fractional axial coordinates
+    pub fn round(q: f64, r: f64) -> Self {
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+
+        Self::new(rq as i64, rr as i64)
+    }
+
+    pub fn neighbor(&self, direction: Direction) -> Self {
+        let (dq, dr) = direction.delta();
+        Self::new(self.q + dq, self.r + dr)
+    }
+
+    pub fn neighbors(&self) -> Vec<Self> {
+        Direction::iter().map(|d| self.neighbor(d)).collect()
+    }
+
+    /// Number of steps between two hexagons
+    pub fn distance(&self, other: &Self) -> u64 {
+        self.q
+            .abs_diff(other.q)
+            .max(self.r.abs_diff(other.r))
+            .max(self.s.abs_diff(other.s))
+    }
+
+    /// Hexagons exactly at `radius` steps
+    pub fn ring(&self, radius: u64) -> Vec<Self> {
+        if radius == 0 {
+            return vec![*self];
+        }
+
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+        let (dq, dr) = Direction::SouthWest.delta();
+        let mut current = Self::new(self.q + dq * radius as i64, self.r + dr * radius as i64);
+
+        for direction in Direction::iter() {
+            for _ in 0..radius {
+                ring.push(current);
+                current = current.neighbor(direction);
+            }
+        }
+
+        ring
+    }
+
+    /// Hexagons at `radius` steps or less, from the center to the outer ring
+    pub fn spiral(&self, radius: u64) -> Vec<Self> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    /// Hexagons crossed by a straight line to `to`, both included
+    pub fn line(&self, to: &Self) -> Vec<Self> {
+        let steps = self.distance(to);
+        if steps == 0 {
+            return vec![*self];
+        }
+
+        // Nudge to break ties consistently when line goes along hexagons edges
+        let (q, r) = (self.q as f64 + 1e-6, self.r as f64 + 1e-6);
+        (0..=steps)
+            .map(|i| {
+                let t = i as f64 / steps as f64;
+                Self::round(
+                    q + (to.q as f64 - self.q as f64) * t,
+                    r + (to.r as f64 - self.r as f64) * t,
+                )
+            })
+            .collect()
+    }
+
+    /// World point of this hexagon, if not in negative coordinates
+    pub fn world_point(&self) -> Option<WorldPoint> {
+        (self.q >= 0 && self.r >= 0).then(|| WorldPoint::new(self.q as u64, self.r as u64))
+    }
+}
+
+impl From<WorldPoint> for Cube {
+    fn from(value: WorldPoint) -> Self {
+        Self::new(value.x as i64, value.y as i64)
+    }
+}
+
+impl From<ImaginaryWorldPoint> for Cube {
+    fn from(value: ImaginaryWorldPoint) -> Self {
+        Self::new(value.x, value.y)
+    }
+}
+
+impl From<Cube> for ImaginaryWorldPoint {
+    fn from(value: Cube) -> Self {
+        Self::new(value.q, value.r)
+    }
+}
+
+fn world_points(cubes: Vec<Cube>) -> Vec<WorldPoint> {
+    cubes.iter().filter_map(Cube::world_point).collect()
+}
+
+/// Hexagonal helpers. Points in negative coordinates are excluded, but callers must still
+/// exclude points beyond the world width and height.
+impl WorldPoint {
+    pub fn neighbors(&self) -> Vec<WorldPoint> {
+        world_points(Cube::from(*self).neighbors())
+    }
+
+    pub fn distance(&self, other: &WorldPoint) -> u64 {
+        Cube::from(*self).distance(&Cube::from(*other))
+    }
+
+    pub fn ring(&self, radius: u64) -> Vec<WorldPoint> {
+        world_points(Cube::from(*self).ring(radius))
+    }
+
+    pub fn spiral(&self, radius: u64) -> Vec<WorldPoint> {
+        world_points(Cube::from(*self).spiral(radius))
+    }
+
+    pub fn line(&self, to: &WorldPoint) -> Vec<WorldPoint> {
+        world_points(Cube::from(*self).line(&Cube::from(*to)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn test_neighbors() {
+        let center = WorldPoint::new(5, 5);
+        let neighbors = center.neighbors();
+
+        assert_eq!(neighbors.len(), 6);
+        assert!(neighbors.iter().all(|n| center.distance(n) == 1));
+        assert!(!neighbors.contains(&WorldPoint::new(6, 6)));
+        assert!(!neighbors.contains(&WorldPoint::new(4, 4)));
+        assert!(neighbors.contains(&WorldPoint::new(6, 4)));
+        assert!(neighbors.contains(&WorldPoint::new(4, 6)));
+        // Negative coordinates are excluded
+        assert_eq!(WorldPoint::new(0, 0).neighbors().len(), 2);
+    }
+
+    #[rstest]
+    #[case((0, 0), (0, 0), 0)]
+    #[case((0, 0), (3, 0), 3)]
+    #[case((0, 0), (3, -3), 3)]
+    #[case((0, 0), (2, 2), 4)]
+    #[case((1, 2), (-2, 1), 4)]
+    fn test_distance(#[case] a: (i64, i64), #[case] b: (i64, i64), #[case] expected: u64) {
+        assert_eq!(Cube::new(a.0, a.1).distance(&Cube::new(b.0, b.1)), expected);
+    }
+
+    #[rstest]
+    #[case(0, 1)]
+    #[case(1, 6)]
+    #[case(3, 18)]
+    fn test_ring(#[case] radius: u64, #[case] expected_len: usize) {
+        let center = Cube::new(0, 0);
+        let ring = center.ring(radius);
+
+        assert_eq!(ring.len(), expected_len);
+        assert!(ring.iter().all(|c| center.distance(c) == radius));
+    }
+
+    #[test]
+    fn test_spiral() {
+        let center = Cube::new(0, 0);
+        let spiral = center.spiral(2);
+
+        assert_eq!(spiral.len(), 1 + 6 + 12);
+        assert_eq!(spiral[0], center);
+        assert_eq!(
+            spiral
+                .iter()
+                .collect::<std::collections::HashSet<_>>()
+                .len(),
+            spiral.len()
+        );
+    }
+
+    #[test]
+    fn test_line() {
+        let from = Cube::new(0, 0);
+        let to = Cube::new(4, -2);
+        let line = from.line(&to);
+
+        assert_eq!(line.len(), 5);
+        assert_eq!(line.first(), Some(&from));
+        assert_eq!(line.last(), Some(&to));
+        assert!(line.windows(2).all(|w| w[0].distance(&w[1]) == 1));
+    }
+
+    #[test]
+    fn test_round() {
+        assert_eq!(Cube::round(0.1, 0.2), Cube::new(0, 0));
+        assert_eq!(Cube::round(0.9, -0.1), Cube::new(1, 0));
+        // Top corner of (0, 0) is closer to (1, 0) or (0, 1) than to (1, 1)
+        assert_ne!(Cube::round(0.55, 0.55), Cube::new(1, 1));
+    }
+}
@@ -1,6 +1,8 @@
 pub mod game;
 pub mod geo;
+pub mod hex;
 pub mod network;
+pub mod path;
 pub mod rules;
 pub mod space;
 pub mod task;
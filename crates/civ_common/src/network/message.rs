@@ -3,18 +3,20 @@ use thiserror::Error;
 
 use crate::{
     game::{
-        city::{CityExploitation, CityId, CityProduction},
+        city::{CityId, CityProduct, CityProduction},
         nation::flag::Flag,
         server::ServerResume,
-        slice::{ClientCity, ClientUnit, GameSlice},
-        unit::UnitId,
+        slice::{ClientCity, ClientTile, ClientUnit, GameSlice},
+        tech::{Research, TechType},
+        treasury::Treasury,
+        unit::{AttackTarget, UnitId},
         GameFrame,
     },
     geo::WorldPoint,
     space::window::{Resolution, Window},
 };
 
-use super::Client;
+use super::{Client, ProtocolVersion};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum NotificationLevel {
@@ -31,10 +33,21 @@ pub enum ClientToServerMessage {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum ClientToServerNetworkMessage {
-    Hello(Client, Resolution),
+    /// Protocol version comes first (and this variant must stay the first one) so it can be
+    /// read whatever the rest of the message looks like, see [`hello_version`]
+    Hello(ProtocolVersion, Client, Resolution),
     Goodbye,
 }
 
+/// Protocol version of given encoded [`ClientToServerMessage`] if it is a `Hello`. Works
+/// with messages encoded by any protocol version, even the ones which can't be decoded.
+pub fn hello_version(data: &[u8]) -> Option<ProtocolVersion> {
+    // Bincode encodes enum variants as their u32 index followed by their fields
+    let (message, network, version): (u32, u32, ProtocolVersion) =
+        bincode::deserialize(data).ok()?;
+    (message == 0 && network == 0).then_some(version)
+}
+
 impl From<ClientToServerNetworkMessage> for ClientToServerMessage {
     fn from(value: ClientToServerNetworkMessage) -> Self {
         Self::Network(value)
@@ -49,7 +62,8 @@ pub enum ClientToServerGameMessage {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum ClientToServerEstablishmentMessage {
-    TakePlace(Flag, Resolution),
+    /// Flag to play, and fingerprint of the rules received in server resume
+    TakePlace(Flag, Resolution, u64),
 }
 
 impl From<ClientToServerEstablishmentMessage> for ClientToServerGameMessage {
@@ -70,6 +84,10 @@ pub enum ClientToServerInGameMessage {
     SetWindow(Window),
     Unit(UnitId, ClientToServerUnitMessage),
     City(CityId, ClientToServerCityMessage),
+    /// Tech the player wants to research
+    SetResearch(TechType),
+    /// Part of trade (in percent) the player wants to give to gold
+    SetTaxRate(u8),
 }
 
 impl From<ClientToServerInGameMessage> for ClientToServerGameMessage {
@@ -87,13 +105,24 @@ impl From<ClientToServerInGameMessage> for ClientToServerMessage {
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum ClientToServerUnitMessage {
     Settle(String), // CityName
+    MoveTo(WorldPoint),
+    Attack(AttackTarget),
     CancelCurrentTask,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum ClientToServerCityMessage {
     SetProduction(CityProduction),
-    SetExploitation(CityExploitation),
+    /// Queue a product at the end of production queue
+    PushProduct(CityProduct),
+    /// Remove product at given production queue position
+    RemoveProduct(usize),
+    /// Move product from a production queue position to another
+    MoveProduct(usize, usize),
+    /// Queue completed products again (or not)
+    SetRepeat(bool),
+    /// Tiles worked by city citizens, around the city tile (always worked)
+    SetWorkedTiles(Vec<WorldPoint>),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -105,8 +134,9 @@ pub enum ServerToClientMessage {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum ServerToClientEstablishmentMessage {
-    ServerResume(ServerResume, Option<Flag>), // None flag mean player not placed
+    ServerResume(Box<ServerResume>, Option<Flag>), // None flag mean player not placed
     TakePlaceRefused(TakePlaceRefusedReason),
+    HelloRefused(HelloRefusedReason),
 }
 
 impl From<ServerToClientEstablishmentMessage> for ServerToClientMessage {
@@ -127,10 +157,14 @@ pub enum ClientStateMessage {
     SetGameFrame(GameFrame),
     SetWindow(Window),
     SetGameSlice(GameSlice),
+    /// Tiles which visibility changed
+    SetTiles(Vec<ClientTile>),
     SetCity(ClientCity),
     RemoveCity(WorldPoint, CityId),
     SetUnit(ClientUnit),
     RemoveUnit(WorldPoint, UnitId),
+    SetResearch(Research),
+    SetTreasury(Treasury),
 }
 
 impl From<ClientStateMessage> for ServerToClientMessage {
@@ -143,4 +177,48 @@ impl From<ClientStateMessage> for ServerToClientMessage {
 pub enum TakePlaceRefusedReason {
     #[error("Flag {0} already taken")]
     FlagAlreadyTaken(Flag),
+    #[error("Server rules changed, reconnect to get them")]
+    IncompatibleRules,
+}
+
+#[derive(Error, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum HelloRefusedReason {
+    #[error("Server protocol version is {0} but client one is {1}, client must be updated")]
+    IncompatibleProtocol(ProtocolVersion, ProtocolVersion),
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use crate::network::PROTOCOL_VERSION;
+
+    use super::*;
+
+    #[rstest]
+    #[case(
+        bincode::serialize(&ClientToServerMessage::Network(ClientToServerNetworkMessage::Hello(
+            PROTOCOL_VERSION,
+            Client::default(),
+            Resolution::new(1, 1),
+        ))).unwrap(),
+        Some(PROTOCOL_VERSION)
+    )]
+    #[case(
+        bincode::serialize(&ClientToServerMessage::Network(ClientToServerNetworkMessage::Goodbye)).unwrap(),
+        None
+    )]
+    // Hello of another protocol version, with fields unknown by this one
+    #[case(
+        bincode::serialize(&(0u32, 0u32, ProtocolVersion(0), "unknown")).unwrap(),
+        Some(ProtocolVersion(0))
+    )]
+    #[case(vec![0], None)]
+    fn test_hello_version(#[case] data: Vec<u8>, #[case] expected: Option<ProtocolVersion>) {
+        // GIVEN/WHEN
+        let version = hello_version(&data);
+
+        // THEN
+        assert_eq!(version, expected);
+    }
 }
@@ -8,6 +8,19 @@ use crate::game::PlayerId;
 
 pub mod message;
 
+/// Version of the messages exchanged between clients and server. Must be incremented on
+/// each message change, so clients and server deployed separately refuse each other cleanly.
+pub const PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion(2);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProtocolVersion(pub u32);
+
+impl Display for ProtocolVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.to_string())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ClientId(pub Uuid);
 
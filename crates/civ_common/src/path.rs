@@ -0,0 +1,243 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use bon::Builder;
+use rustc_hash::FxHashMap;
+use thiserror::Error;
+
+use crate::geo::WorldPoint;
+
+/// Default maximum distance (in tiles) from start explored by a path search
+pub const DEFAULT_SEARCH_RADIUS: u64 = 64;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum PathError {
+    #[error("Point is outside the world")]
+    OutOfBounds,
+    #[error("Destination is too far (more than {0} tiles)")]
+    TooFar(u64),
+    #[error("No path found")]
+    NotFound,
+}
+
+/// A* search over the world (hexagonal) grid. Search is bounded to `radius` tiles around the start
+/// point to keep it cheap on large worlds.
+#[derive(Debug, Builder)]
+pub struct PathFinder {
+    width: u64,
+    height: u64,
+    #[builder(default = DEFAULT_SEARCH_RADIUS)]
+    radius: u64,
+    /// Lowest possible cost of one step, used to weight the heuristic (must not over-estimate)
+    #[builder(default = 1)]
+    min_cost: u64,
+}
+
+impl PathFinder {
+    /// Cheapest path from `from` to `to`, `from` excluded. `cost` gives the cost to step from
+    /// a tile to its neighbor, or `None` if this neighbor is impassable.
+    pub fn find(
+        &self,
+        from: &WorldPoint,
+        to: &WorldPoint,
+        cost: impl Fn(&WorldPoint, &WorldPoint) -> Option<u64>,
+    ) -> Result<Vec<WorldPoint>, PathError> {
+        if !self.contains(from) || !self.contains(to) {
+            return Err(PathError::OutOfBounds);
+        }
+
+        if from.distance(to) > self.radius {
+            return Err(PathError::TooFar(self.radius));
+        }
+
+        if from == to {
+            return Ok(vec![]);
+        }
+
+        let mut open = BinaryHeap::new();
+        let mut costs: FxHashMap<WorldPoint, u64> = FxHashMap::default();
+        let mut came_from: FxHashMap<WorldPoint, WorldPoint> = FxHashMap::default();
+
+        costs.insert(*from, 0);
+        open.push(Reverse((self.heuristic(from, to), 0, *from)));
+
+        while let Some(Reverse((_, point_cost, point))) = open.pop() {
+            if &point == to {
+                return Ok(rebuild(&came_from, from, to));
+            }
+
+            // Already reached with a cheaper cost
+            if costs.get(&point).is_some_and(|c| point_cost > *c) {
+                continue;
+            }
+
+            for next in self.neighbors(&point) {
+                if from.distance(&next) > self.radius {
+                    continue;
+                }
+
+                let Some(step_cost) = cost(&point, &next) else {
+                    continue;
+                };
+
+                let next_cost = point_cost + step_cost;
+                if costs.get(&next).is_none_or(|c| next_cost < *c) {
+                    costs.insert(next, next_cost);
+                    came_from.insert(next, point);
+                    open.push(Reverse((
+                        next_cost + self.heuristic(&next, to),
+                        next_cost,
+                        next,
+                    )));
+                }
+            }
+        }
+
+        Err(PathError::NotFound)
+    }
+
+    fn contains(&self, point: &WorldPoint) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    fn heuristic(&self, from: &WorldPoint, to: &WorldPoint) -> u64 {
+        from.distance(to) * self.min_cost
+    }
+
+    fn neighbors(&self, point: &WorldPoint) -> Vec<WorldPoint> {
+        point
+            .neighbors()
+            .into_iter()
+            .filter(|neighbor| self.contains(neighbor))
+            .collect()
+    }
+}
+
+fn rebuild(
+    came_from: &FxHashMap<WorldPoint, WorldPoint>,
+    from: &WorldPoint,
+    to: &WorldPoint,
+) -> Vec<WorldPoint> {
+    let mut path = vec![*to];
+    let mut current = to;
+
+    while let Some(previous) = came_from.get(current) {
+        if previous == from {
+            break;
+        }
+        path.push(*previous);
+        current = previous;
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn finder(width: u64, height: u64) -> PathFinder {
+        PathFinder::builder().width(width).height(height).build()
+    }
+
+    #[test]
+    fn test_straight_path() {
+        // Given
+        let finder = finder(5, 5);
+
+        // When
+        let path = finder.find(&WorldPoint::new(0, 0), &WorldPoint::new(3, 0), |_, _| {
+            Some(1)
+        });
+
+        // Then
+        assert_eq!(
+            path,
+            Ok(vec![
+                WorldPoint::new(1, 0),
+                WorldPoint::new(2, 0),
+                WorldPoint::new(3, 0),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_path_is_hexagonal() {
+        // Given
+        let finder = finder(5, 5);
+
+        // When
+        let path = finder
+            .find(&WorldPoint::new(0, 0), &WorldPoint::new(2, 2), |_, _| {
+                Some(1)
+            })
+            .unwrap();
+
+        // Then: (1, 1) is not a neighbor of (0, 0)
+        assert_eq!(path.len(), 4);
+        assert!(path.windows(2).all(|w| w[0].distance(&w[1]) == 1));
+    }
+
+    #[test]
+    fn test_path_avoid_impassable() {
+        // Given
+        let finder = finder(5, 5);
+        // Wall on x=2, except at y=4
+        let cost = |_: &WorldPoint, point: &WorldPoint| {
+            if point.x == 2 && point.y != 4 {
+                None
+            } else {
+                Some(1)
+            }
+        };
+
+        // When
+        let path = finder
+            .find(&WorldPoint::new(0, 0), &WorldPoint::new(4, 0), cost)
+            .unwrap();
+
+        // Then
+        assert!(path.contains(&WorldPoint::new(2, 4)));
+        assert_eq!(path.last(), Some(&WorldPoint::new(4, 0)));
+    }
+
+    #[test]
+    fn test_path_prefer_cheap_tiles() {
+        // Given
+        let finder = finder(5, 3);
+        // Middle row is expensive
+        let cost =
+            |_: &WorldPoint, point: &WorldPoint| if point.y == 1 { Some(10) } else { Some(1) };
+
+        // When
+        let path = finder
+            .find(&WorldPoint::new(0, 0), &WorldPoint::new(4, 0), cost)
+            .unwrap();
+
+        // Then
+        assert!(path.iter().all(|point| point.y == 0));
+    }
+
+    #[test]
+    fn test_path_errors() {
+        let finder = PathFinder::builder()
+            .width(100)
+            .height(100)
+            .radius(10)
+            .build();
+        let cost = |_: &WorldPoint, point: &WorldPoint| if point.x == 1 { None } else { Some(1) };
+
+        assert_eq!(
+            finder.find(&WorldPoint::new(0, 0), &WorldPoint::new(100, 0), cost),
+            Err(PathError::OutOfBounds)
+        );
+        assert_eq!(
+            finder.find(&WorldPoint::new(0, 0), &WorldPoint::new(20, 0), cost),
+            Err(PathError::TooFar(10))
+        );
+        assert_eq!(
+            finder.find(&WorldPoint::new(0, 0), &WorldPoint::new(5, 0), cost),
+            Err(PathError::NotFound)
+        );
+    }
+}
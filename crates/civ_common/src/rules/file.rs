@@ -0,0 +1,947 @@
+//! Ruleset described by a RON document, to tune the game without recompiling it
+use std::{collections::HashSet, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use thiserror::Error;
+
+use crate::{
+    game::{
+        city::{BuildingType, CityProduct, CityProductionTons},
+        tech::{Tech, TechType, TechUnlock},
+        unit::{TaskType, UnitTaskType, UnitType},
+        GameFrame,
+    },
+    world::{Elevation, Resource, TerrainType, Tile, Vegetation},
+};
+
+use super::{RuleSet, RuleSetType, TileYield};
+
+const STD1: &str = include_str!("std1.ron");
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum FileRuleSetError {
+    #[error("Can't read rules file: {0}")]
+    Io(String),
+    #[error("Line {0}, column {1}: {2}")]
+    Syntax(usize, usize, String),
+    #[error("Line {0}: {1}")]
+    Invalid(usize, String),
+}
+
+/// Ruleset loaded from a RON document. Durations are in game frames, bonuses in percent.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FileRuleSet {
+    type_: RuleSetType,
+    /// Unit tasks known by this ruleset
+    tasks: Vec<UnitTaskType>,
+    units: Vec<UnitRules>,
+    buildings: Vec<BuildingRules>,
+    terrains: Vec<TerrainRules>,
+    elevations: Vec<ElevationRules>,
+    vegetations: Vec<VegetationRules>,
+    resources: Vec<ResourceRules>,
+    /// Trade bonus of land tiles with a river
+    river_trade: u64,
+    /// Moving along a river is this times faster than on a flat tile
+    river_move_divisor: u64,
+    default_product: CityProduct,
+    growth_frames: u64,
+    citizen_food: u64,
+    /// Stored food needed by a city to grow is `(size + 1) * growth_food`
+    growth_food: u64,
+    city_sight: u64,
+    city_work_radius: u64,
+    combat_round_frames: u64,
+    techs: Vec<Tech>,
+    research_frames: u64,
+    /// Trade always produced by a city tile, in addition to its worked tiles
+    city_center_trade: u64,
+    upkeep_frames: u64,
+    city_free_upkeep: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UnitRules {
+    type_: UnitType,
+    tons: u64,
+    tasks: Vec<UnitTaskType>,
+    settle_frames: u64,
+    /// Frames to enter a flat tile without vegetation
+    move_frames: u64,
+    sight: u64,
+    attack: u64,
+    defence: u64,
+    hit_points: u64,
+    upkeep: u64,
+    #[serde(default)]
+    required_resources: Vec<Resource>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BuildingRules {
+    type_: BuildingType,
+    tons: u64,
+    upkeep: u64,
+    sale: u64,
+    effects: Vec<BuildingEffect>,
+    #[serde(default)]
+    required_resources: Vec<Resource>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum BuildingEffect {
+    /// City yield bonus, in percent
+    YieldBonus(TileYield),
+    /// Food kept when the city grows, in percent of the required food
+    KeptFood(u64),
+    /// Defence multiplier of units in the city, in percent
+    Defence(u64),
+    /// Units produced by the city are veterans
+    Veteran,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TerrainRules {
+    type_: TerrainType,
+    yield_: TileYield,
+    enterable: bool,
+    /// Players can start on it
+    startup: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ElevationRules {
+    elevation: Elevation,
+    /// Overrides terrain yield
+    yield_: Option<TileYield>,
+    move_factor: u64,
+    startup: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VegetationRules {
+    vegetation: Vegetation,
+    /// Overrides elevation and terrain yields
+    yield_: TileYield,
+    move_factor: u64,
+    startup: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ResourceRules {
+    resource: Resource,
+    yield_: TileYield,
+}
+
+impl FileRuleSet {
+    /// Built-in std1 ruleset, behaving as [`super::std1::Std1RuleSet`]
+    pub fn std1() -> Self {
+        Self::from_ron(STD1).expect("Built-in std1 ruleset must be valid")
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self, FileRuleSetError> {
+        let source = fs::read_to_string(path)
+            .map_err(|error| FileRuleSetError::Io(format!("{}: {}", path.display(), error)))?;
+        Self::from_ron(&source)
+    }
+
+    /// Stable hash of these rules, to ensure a client plays with the server ones
+    pub fn fingerprint(&self) -> u64 {
+        // FNV-1a, as std hashers output may change between Rust versions
+        bincode::serialize(self)
+            .expect("Consider rules always serializable")
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    pub fn from_ron(source: &str) -> Result<Self, FileRuleSetError> {
+        let rules: Self = ron::from_str(source).map_err(|error| {
+            FileRuleSetError::Syntax(
+                error.position.line,
+                error.position.col,
+                error.code.to_string(),
+            )
+        })?;
+        rules.validate(source)?;
+        Ok(rules)
+    }
+
+    /// Ensure each unit, building, terrain, elevation, vegetation and resource is described
+    /// once, techs prerequisites are in the tech tree (without cycle) and durations and
+    /// divisors are not zero
+    fn validate(&self, source: &str) -> Result<(), FileRuleSetError> {
+        check_entries(
+            source,
+            "units",
+            &self.units,
+            |rules| rules.type_,
+            UnitType::iter(),
+        )?;
+        check_entries(
+            source,
+            "buildings",
+            &self.buildings,
+            |rules| rules.type_,
+            BuildingType::iter(),
+        )?;
+        check_entries(
+            source,
+            "terrains",
+            &self.terrains,
+            |rules| rules.type_,
+            TerrainType::iter(),
+        )?;
+        check_entries(
+            source,
+            "elevations",
+            &self.elevations,
+            |rules| rules.elevation,
+            Elevation::iter(),
+        )?;
+        check_entries(
+            source,
+            "vegetations",
+            &self.vegetations,
+            |rules| rules.vegetation,
+            Vegetation::iter(),
+        )?;
+        check_entries(
+            source,
+            "resources",
+            &self.resources,
+            |rules| rules.resource,
+            Resource::iter(),
+        )?;
+        check_entries(
+            source,
+            "techs",
+            &self.techs,
+            |tech| *tech.type_(),
+            self.techs.iter().map(|tech| *tech.type_()),
+        )?;
+
+        for tech in &self.techs {
+            if let Some(missing) = tech
+                .prerequisites()
+                .iter()
+                .find(|prerequisite| self.techs.iter().all(|tech| tech.type_() != *prerequisite))
+            {
+                let entry = format!("type_: {:?}", tech.type_());
+                return Err(FileRuleSetError::Invalid(
+                    line_or_section(
+                        source,
+                        "techs",
+                        line_of_field(source, "techs", &entry, "prerequisites"),
+                    ),
+                    format!(
+                        "{:?} prerequisite {:?} is not in techs",
+                        tech.type_(),
+                        missing
+                    ),
+                ));
+            }
+        }
+
+        if let Some(cyclic) = cyclic_tech(&self.techs) {
+            return Err(FileRuleSetError::Invalid(
+                line_or_section(
+                    source,
+                    "techs",
+                    line_of(source, "techs", &format!("type_: {:?}", cyclic), 0),
+                ),
+                format!("{:?} requires itself through its prerequisites", cyclic),
+            ));
+        }
+
+        for unit in &self.units {
+            let entry = format!("type_: {:?}", unit.type_);
+            for (field, value, needed) in [
+                ("tons", unit.tons, true),
+                (
+                    "settle_frames",
+                    unit.settle_frames,
+                    unit.tasks.contains(&UnitTaskType::Settle),
+                ),
+                (
+                    "move_frames",
+                    unit.move_frames,
+                    unit.tasks.contains(&UnitTaskType::Move),
+                ),
+            ] {
+                if needed && value == 0 {
+                    return Err(FileRuleSetError::Invalid(
+                        line_or_section(
+                            source,
+                            "units",
+                            line_of_field(source, "units", &entry, field),
+                        ),
+                        format!("{:?} {} must not be 0", unit.type_, field),
+                    ));
+                }
+            }
+        }
+
+        for building in &self.buildings {
+            if building.tons == 0 {
+                return Err(FileRuleSetError::Invalid(
+                    line_or_section(
+                        source,
+                        "buildings",
+                        line_of_field(
+                            source,
+                            "buildings",
+                            &format!("type_: {:?}", building.type_),
+                            "tons",
+                        ),
+                    ),
+                    format!("{:?} tons must not be 0", building.type_),
+                ));
+            }
+        }
+
+        // Cities fall back to it when their queue is empty, but a building is built only once
+        if let CityProduct::Building(building) = &self.default_product {
+            return Err(FileRuleSetError::Invalid(
+                line_or_section(source, "default_product", None),
+                format!("default_product must be a unit, not {:?}", building),
+            ));
+        }
+
+        for (key, value) in [
+            ("river_move_divisor", self.river_move_divisor),
+            ("growth_frames", self.growth_frames),
+            ("combat_round_frames", self.combat_round_frames),
+            ("research_frames", self.research_frames),
+            ("upkeep_frames", self.upkeep_frames),
+        ] {
+            if value == 0 {
+                return Err(FileRuleSetError::Invalid(
+                    line_or_section(source, key, None),
+                    format!("{} must not be 0", key),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn unit(&self, type_: &UnitType) -> &UnitRules {
+        self.units
+            .iter()
+            .find(|rules| &rules.type_ == type_)
+            .expect("Units validated at load")
+    }
+
+    fn building(&self, type_: &BuildingType) -> &BuildingRules {
+        self.buildings
+            .iter()
+            .find(|rules| &rules.type_ == type_)
+            .expect("Buildings validated at load")
+    }
+
+    fn terrain(&self, type_: &TerrainType) -> &TerrainRules {
+        self.terrains
+            .iter()
+            .find(|rules| &rules.type_ == type_)
+            .expect("Terrains validated at load")
+    }
+
+    fn elevation(&self, elevation: &Elevation) -> &ElevationRules {
+        self.elevations
+            .iter()
+            .find(|rules| &rules.elevation == elevation)
+            .expect("Elevations validated at load")
+    }
+
+    fn vegetation(&self, vegetation: &Vegetation) -> &VegetationRules {
+        self.vegetations
+            .iter()
+            .find(|rules| &rules.vegetation == vegetation)
+            .expect("Vegetations validated at load")
+    }
+
+    fn effects<'a>(
+        &'a self,
+        buildings: &'a [BuildingType],
+    ) -> impl Iterator<Item = &'a BuildingEffect> + 'a {
+        buildings
+            .iter()
+            .flat_map(|building| self.building(building).effects.iter())
+    }
+}
+
+/// Ensure `entries` (of given `section`) describe each one of `expected` exactly once
+fn check_entries<T, K: PartialEq + std::fmt::Debug>(
+    source: &str,
+    section: &str,
+    entries: &[T],
+    key: impl Fn(&T) -> K,
+    expected: impl Iterator<Item = K>,
+) -> Result<(), FileRuleSetError> {
+    for expected in expected {
+        let count = entries
+            .iter()
+            .filter(|entry| key(entry) == expected)
+            .count();
+        match count {
+            0 => {
+                return Err(FileRuleSetError::Invalid(
+                    line_or_section(source, section, None),
+                    format!("{} misses {:?}", section, expected),
+                ))
+            }
+            1 => {}
+            _ => {
+                return Err(FileRuleSetError::Invalid(
+                    line_or_section(
+                        source,
+                        section,
+                        line_of(source, section, &format!("{:?}", expected), 1),
+                    ),
+                    format!("{} describes {:?} more than once", section, expected),
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// First tech found among its own (direct or indirect) prerequisites
+fn cyclic_tech(techs: &[Tech]) -> Option<TechType> {
+    techs.iter().map(Tech::type_).copied().find(|type_| {
+        let mut seen = HashSet::new();
+        let mut stack = vec![*type_];
+        while let Some(current) = stack.pop() {
+            let Some(tech) = techs.iter().find(|tech| tech.type_() == &current) else {
+                continue;
+            };
+            for prerequisite in tech.prerequisites() {
+                if prerequisite == type_ {
+                    return true;
+                }
+                if seen.insert(*prerequisite) {
+                    stack.push(*prerequisite);
+                }
+            }
+        }
+        false
+    })
+}
+
+/// Line (starting at 1) of the `nth` occurrence of `needle` after `section` key (of the
+/// key itself when `needle` is empty), to point modders to the offending entry
+fn line_of(source: &str, section: &str, needle: &str, nth: usize) -> Option<usize> {
+    // Key must not be the end of another one (as `resources` in `required_resources`)
+    let key = format!("{}:", section);
+    let (start, _) = source.match_indices(&key).find(|(position, _)| {
+        source[..*position]
+            .chars()
+            .next_back()
+            .is_none_or(|char| !char.is_alphanumeric() && char != '_')
+    })?;
+    let position = if needle.is_empty() {
+        start
+    } else {
+        start + source[start..].match_indices(needle).nth(nth)?.0
+    };
+
+    Some(source[..position].matches('\n').count() + 1)
+}
+
+/// Line of given field of the entry of given section (as `tons` of `type_: Warriors` in
+/// `units`), or of the entry itself when the field is not written
+fn line_of_field(source: &str, section: &str, entry: &str, field: &str) -> Option<usize> {
+    let line = line_of(source, section, entry, 0)?;
+    let start = source
+        .split_inclusive('\n')
+        .take(line - 1)
+        .map(str::len)
+        .sum::<usize>();
+    let line = source[start..]
+        .find(&format!("{}:", field))
+        .map(|position| source[..start + position].matches('\n').count() + 1)
+        .unwrap_or(line);
+
+    Some(line)
+}
+
+/// Given line, or the one of `section` key when not found (first line when missing too)
+fn line_or_section(source: &str, section: &str, line: Option<usize>) -> usize {
+    line.or_else(|| line_of(source, section, "", 0))
+        .unwrap_or(1)
+}
+
+impl RuleSet for FileRuleSet {
+    fn type_(&self) -> RuleSetType {
+        self.type_
+    }
+
+    fn file(&self) -> FileRuleSet {
+        self.clone()
+    }
+
+    fn tasks(&self) -> Vec<TaskType> {
+        self.tasks.iter().cloned().map(TaskType::Unit).collect()
+    }
+
+    fn unit_can(&self, type_: &UnitType, techs: &[TechType]) -> Vec<TaskType> {
+        self.unit(type_)
+            .tasks
+            .iter()
+            .filter(|task| self.unlocked(&TechUnlock::Task((*task).clone()), techs))
+            .cloned()
+            .map(TaskType::Unit)
+            .collect()
+    }
+
+    fn settle_duration(&self, unit_type: &UnitType) -> GameFrame {
+        GameFrame(self.unit(unit_type).settle_frames)
+    }
+
+    fn can_settle(&self, unit_type: &UnitType) -> bool {
+        self.unit(unit_type).tasks.contains(&UnitTaskType::Settle)
+    }
+
+    fn can_move(&self, unit_type: &UnitType) -> bool {
+        self.unit(unit_type).tasks.contains(&UnitTaskType::Move)
+    }
+
+    fn can_enter(&self, _unit_type: &UnitType, tile: &Tile) -> bool {
+        self.terrain(&tile.type_()).enterable
+    }
+
+    fn move_duration(&self, unit_type: &UnitType, tile: &Tile) -> GameFrame {
+        let elevation = self.elevation(&tile.elevation()).move_factor;
+        let vegetation = tile
+            .vegetation()
+            .map(|vegetation| self.vegetation(&vegetation).move_factor)
+            .unwrap_or(1);
+
+        GameFrame(self.unit(unit_type).move_frames * elevation.max(vegetation))
+    }
+
+    fn river_move_duration(&self, unit_type: &UnitType) -> GameFrame {
+        GameFrame(self.unit(unit_type).move_frames / self.river_move_divisor)
+    }
+
+    fn tile_yield(&self, tile: &Tile) -> TileYield {
+        let mut yield_ = match tile.vegetation() {
+            Some(vegetation) => self.vegetation(&vegetation).yield_,
+            None => self
+                .elevation(&tile.elevation())
+                .yield_
+                .unwrap_or(self.terrain(&tile.type_()).yield_),
+        };
+
+        if tile.has_river() && !tile.is_water() {
+            yield_ += TileYield::new(0, 0, self.river_trade);
+        }
+
+        if let Some(resource) = tile.resource() {
+            yield_ += self.resource_yield(&resource);
+        }
+
+        yield_
+    }
+
+    fn resource_yield(&self, resource: &Resource) -> TileYield {
+        self.resources
+            .iter()
+            .find(|rules| &rules.resource == resource)
+            .map(|rules| rules.yield_)
+            .unwrap_or_default()
+    }
+
+    fn required_tons(&self, product: &CityProduct) -> CityProductionTons {
+        CityProductionTons(match product {
+            CityProduct::Unit(unit_type) => self.unit(unit_type).tons,
+            CityProduct::Building(building) => self.building(building).tons,
+        })
+    }
+
+    fn default_product(&self) -> CityProduct {
+        self.default_product.clone()
+    }
+
+    fn buildings(&self) -> Vec<BuildingType> {
+        self.buildings.iter().map(|rules| rules.type_).collect()
+    }
+
+    fn city_yield(&self, yield_: TileYield, buildings: &[BuildingType]) -> TileYield {
+        let bonus = self
+            .effects(buildings)
+            .filter_map(|effect| match effect {
+                BuildingEffect::YieldBonus(bonus) => Some(*bonus),
+                _ => None,
+            })
+            .fold(TileYield::default(), |total, bonus| total + bonus);
+
+        TileYield::new(
+            yield_.food + yield_.food * bonus.food / 100,
+            yield_.production + yield_.production * bonus.production / 100,
+            yield_.trade + yield_.trade * bonus.trade / 100,
+        )
+    }
+
+    fn growth_kept_food(&self, required: u64, buildings: &[BuildingType]) -> u64 {
+        let kept = self
+            .effects(buildings)
+            .filter_map(|effect| match effect {
+                BuildingEffect::KeptFood(kept) => Some(*kept),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+
+        required * kept / 100
+    }
+
+    fn city_defence(&self, buildings: &[BuildingType]) -> u64 {
+        self.effects(buildings)
+            .filter_map(|effect| match effect {
+                BuildingEffect::Defence(defence) => Some(*defence),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(100)
+    }
+
+    fn veteran_units(&self, buildings: &[BuildingType]) -> bool {
+        self.effects(buildings)
+            .any(|effect| effect == &BuildingEffect::Veteran)
+    }
+
+    fn required_resources(&self, product: &CityProduct) -> Vec<Resource> {
+        match product {
+            CityProduct::Unit(unit_type) => self.unit(unit_type).required_resources.clone(),
+            CityProduct::Building(building) => self.building(building).required_resources.clone(),
+        }
+    }
+
+    fn can_be_startup(&self, tile: &Tile) -> bool {
+        self.terrain(&tile.type_()).startup
+            && self.elevation(&tile.elevation()).startup
+            && tile
+                .vegetation()
+                .map(|vegetation| self.vegetation(&vegetation).startup)
+                .unwrap_or(true)
+    }
+
+    fn growth_duration(&self) -> GameFrame {
+        GameFrame(self.growth_frames)
+    }
+
+    fn citizen_food(&self) -> u64 {
+        self.citizen_food
+    }
+
+    fn growth_food(&self, size: u64) -> u64 {
+        (size + 1) * self.growth_food
+    }
+
+    fn unit_sight(&self, unit_type: &UnitType) -> u64 {
+        self.unit(unit_type).sight
+    }
+
+    fn city_sight(&self) -> u64 {
+        self.city_sight
+    }
+
+    fn city_work_radius(&self) -> u64 {
+        self.city_work_radius
+    }
+
+    fn unit_attack(&self, unit_type: &UnitType) -> u64 {
+        self.unit(unit_type).attack
+    }
+
+    fn unit_defence(&self, unit_type: &UnitType) -> u64 {
+        self.unit(unit_type).defence
+    }
+
+    fn unit_hit_points(&self, unit_type: &UnitType) -> u64 {
+        self.unit(unit_type).hit_points
+    }
+
+    fn combat_round_duration(&self) -> GameFrame {
+        GameFrame(self.combat_round_frames)
+    }
+
+    fn techs(&self) -> Vec<Tech> {
+        self.techs.clone()
+    }
+
+    fn research_duration(&self) -> GameFrame {
+        GameFrame(self.research_frames)
+    }
+
+    fn city_trade(&self, yield_: &TileYield) -> u64 {
+        yield_.trade + self.city_center_trade
+    }
+
+    fn upkeep_duration(&self) -> GameFrame {
+        GameFrame(self.upkeep_frames)
+    }
+
+    fn unit_upkeep(&self, unit_type: &UnitType) -> u64 {
+        self.unit(unit_type).upkeep
+    }
+
+    fn building_upkeep(&self, building: &BuildingType) -> u64 {
+        self.building(building).upkeep
+    }
+
+    fn building_sale(&self, building: &BuildingType) -> u64 {
+        self.building(building).sale
+    }
+
+    fn city_free_upkeep(&self) -> u64 {
+        self.city_free_upkeep
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use crate::{geo::Direction, rules::std1::Std1RuleSet, world::RiverEdges};
+
+    use super::*;
+
+    fn tiles() -> Vec<Tile> {
+        let mut tiles = vec![];
+        for type_ in TerrainType::iter() {
+            for elevation in Elevation::iter() {
+                for vegetation in std::iter::once(None).chain(Vegetation::iter().map(Some)) {
+                    for resource in std::iter::once(None).chain(Resource::iter().map(Some)) {
+                        let tile = Tile::builder()
+                            .type_(type_)
+                            .elevation(elevation)
+                            .maybe_vegetation(vegetation)
+                            .maybe_resource(resource)
+                            .build();
+                        tiles.push(tile.clone());
+                        tiles.push(tile.with_rivers(RiverEdges::default().with(Direction::East)));
+                    }
+                }
+            }
+        }
+        tiles
+    }
+
+    #[test]
+    fn test_std1_behaves_as_hardcoded_std1() {
+        // GIVEN
+        let file = FileRuleSet::std1();
+        let std1 = Std1RuleSet;
+        let techs = [vec![], vec![TechType::Alphabet, TechType::Writing]];
+        let all_buildings = BuildingType::iter().collect::<Vec<_>>();
+        let buildings = std::iter::once(vec![])
+            .chain(BuildingType::iter().map(|building| vec![building]))
+            .chain(std::iter::once(all_buildings))
+            .collect::<Vec<_>>();
+        let yield_ = TileYield::new(7, 5, 9);
+
+        // WHEN/THEN
+        assert_eq!(file.type_(), std1.type_());
+        assert_eq!(file.tasks(), std1.tasks());
+        assert_eq!(file.default_product(), std1.default_product());
+        assert_eq!(file.buildings(), std1.buildings());
+        assert_eq!(file.growth_duration(), std1.growth_duration());
+        assert_eq!(file.citizen_food(), std1.citizen_food());
+        assert_eq!(file.city_sight(), std1.city_sight());
+        assert_eq!(file.city_work_radius(), std1.city_work_radius());
+        assert_eq!(file.combat_round_duration(), std1.combat_round_duration());
+        assert_eq!(file.techs(), std1.techs());
+        assert_eq!(file.research_duration(), std1.research_duration());
+        assert_eq!(file.city_trade(&yield_), std1.city_trade(&yield_));
+        assert_eq!(file.upkeep_duration(), std1.upkeep_duration());
+        assert_eq!(file.city_free_upkeep(), std1.city_free_upkeep());
+        for size in 0..10 {
+            assert_eq!(file.growth_food(size), std1.growth_food(size));
+        }
+        for buildings in &buildings {
+            assert_eq!(
+                file.city_yield(yield_, buildings),
+                std1.city_yield(yield_, buildings)
+            );
+            assert_eq!(
+                file.growth_kept_food(31, buildings),
+                std1.growth_kept_food(31, buildings)
+            );
+            assert_eq!(file.city_defence(buildings), std1.city_defence(buildings));
+            assert_eq!(file.veteran_units(buildings), std1.veteran_units(buildings));
+        }
+        for building in BuildingType::iter() {
+            let product = CityProduct::Building(building);
+            assert_eq!(file.required_tons(&product), std1.required_tons(&product));
+            assert_eq!(
+                file.required_resources(&product),
+                std1.required_resources(&product)
+            );
+            assert_eq!(
+                file.building_upkeep(&building),
+                std1.building_upkeep(&building)
+            );
+            assert_eq!(file.building_sale(&building), std1.building_sale(&building));
+        }
+        for resource in Resource::iter() {
+            assert_eq!(
+                file.resource_yield(&resource),
+                std1.resource_yield(&resource)
+            );
+        }
+        for unit_type in UnitType::iter() {
+            let product = CityProduct::Unit(unit_type);
+            assert_eq!(file.required_tons(&product), std1.required_tons(&product));
+            assert_eq!(
+                file.required_resources(&product),
+                std1.required_resources(&product)
+            );
+            for techs in &techs {
+                assert_eq!(
+                    file.unit_can(&unit_type, techs),
+                    std1.unit_can(&unit_type, techs)
+                );
+            }
+            assert_eq!(
+                file.settle_duration(&unit_type),
+                std1.settle_duration(&unit_type)
+            );
+            assert_eq!(file.can_settle(&unit_type), std1.can_settle(&unit_type));
+            assert_eq!(file.can_move(&unit_type), std1.can_move(&unit_type));
+            assert_eq!(
+                file.river_move_duration(&unit_type),
+                std1.river_move_duration(&unit_type)
+            );
+            assert_eq!(file.unit_sight(&unit_type), std1.unit_sight(&unit_type));
+            assert_eq!(file.unit_attack(&unit_type), std1.unit_attack(&unit_type));
+            assert_eq!(file.unit_defence(&unit_type), std1.unit_defence(&unit_type));
+            assert_eq!(
+                file.unit_hit_points(&unit_type),
+                std1.unit_hit_points(&unit_type)
+            );
+            assert_eq!(file.unit_upkeep(&unit_type), std1.unit_upkeep(&unit_type));
+            for tile in tiles() {
+                assert_eq!(
+                    file.can_enter(&unit_type, &tile),
+                    std1.can_enter(&unit_type, &tile)
+                );
+                assert_eq!(
+                    file.move_duration(&unit_type, &tile),
+                    std1.move_duration(&unit_type, &tile)
+                );
+            }
+        }
+        for tile in tiles() {
+            assert_eq!(file.tile_yield(&tile), std1.tile_yield(&tile), "{:?}", tile);
+            assert_eq!(file.can_be_startup(&tile), std1.can_be_startup(&tile));
+        }
+    }
+
+    #[rstest]
+    #[case("(\n    type_: Std1,\n    tasks: [Settle, Fly],\n", 3)]
+    #[case("(\n    type_: Std2,\n", 2)]
+    fn test_syntax_error_line(#[case] source: &str, #[case] expected: usize) {
+        // WHEN
+        let result = FileRuleSet::from_ron(source);
+
+        // THEN
+        assert!(
+            matches!(result, Err(FileRuleSetError::Syntax(line, _, _)) if line == expected),
+            "{:?}",
+            result
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "(type_: Alphabet, prerequisites: [], cost: 10, unlocks: []),",
+        "",
+        "prerequisites: [Alphabet]"
+    )]
+    #[case("            hit_points: 10,\n            upkeep: 0,\n        ),", "            hit_points: 10,\n            upkeep: 0,\n        ),\n        (type_: Warriors, tons: 1, tasks: [], settle_frames: 0, move_frames: 1, sight: 1, attack: 1, defence: 1, hit_points: 1, upkeep: 0),", "(type_: Warriors, tons: 1")]
+    #[case("growth_frames: 600,", "growth_frames: 0,", "growth_frames: 0")]
+    #[case("tons: 8,", "tons: 0,", "tons: 0")]
+    #[case("tons: 30,", "tons: 0,", "tons: 0")]
+    #[case("move_frames: 40,", "move_frames: 0,", "move_frames: 0")]
+    #[case("settle_frames: 100,", "settle_frames:0,", "settle_frames:0")]
+    #[case(
+        "default_product: Unit(Warriors),",
+        "default_product: Building(Granary),",
+        "default_product: Building"
+    )]
+    #[case("research_frames: 600,", "research_frames: 0,", "research_frames: 0")]
+    #[case("upkeep_frames: 600,", "upkeep_frames: 0,", "upkeep_frames: 0")]
+    #[case(
+        "combat_round_frames: 10,",
+        "combat_round_frames: 0,",
+        "combat_round_frames: 0"
+    )]
+    #[case(
+        "(type_: Alphabet, prerequisites: [],",
+        "(type_: Alphabet, prerequisites: [Writing],",
+        "(type_: Alphabet"
+    )]
+    #[case(
+        "(type_: Pottery, prerequisites: [],",
+        "(type_: Pottery, prerequisites: [Pottery],",
+        "(type_: Pottery"
+    )]
+    fn test_invalid_line(#[case] from: &str, #[case] to: &str, #[case] offending: &str) {
+        // GIVEN
+        let source = STD1.replacen(from, to, 1);
+        let expected = source
+            .lines()
+            .position(|line| line.contains(offending))
+            .unwrap()
+            + 1;
+
+        // WHEN
+        let result = FileRuleSet::from_ron(&source);
+
+        // THEN
+        assert!(
+            matches!(result, Err(FileRuleSetError::Invalid(line, _)) if line == expected),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_unknown_prerequisite_line() {
+        // GIVEN
+        let source = STD1
+            .replacen(
+                "(type_: Alphabet, prerequisites: [], cost: 10, unlocks: []),",
+                "",
+                1,
+            )
+            .replacen(
+                "(type_: Writing, prerequisites: [Alphabet],",
+                "(type_: Writing,\n            prerequisites: [Alphabet],",
+                1,
+            );
+        let expected = source
+            .lines()
+            .position(|line| line.contains("prerequisites: [Alphabet]"))
+            .unwrap()
+            + 1;
+
+        // WHEN
+        let result = FileRuleSet::from_ron(&source);
+
+        // THEN
+        assert_eq!(
+            result,
+            Err(FileRuleSetError::Invalid(
+                expected,
+                "Writing prerequisite Alphabet is not in techs".to_string()
+            ))
+        );
+    }
+}
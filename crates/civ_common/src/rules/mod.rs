@@ -1,35 +1,151 @@
+use std::ops::{Add, AddAssign};
+
+use derive_more::Constructor;
 use dyn_clone::DynClone;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     game::{
-        city::{CityProduct, CityProductionTons},
+        city::{BuildingType, CityProduct, CityProductionTons},
+        tech::{Tech, TechType, TechUnlock},
         unit::{TaskType, UnitType},
         GameFrame,
     },
-    world::Tile,
+    world::{Resource, Tile},
 };
 
+pub mod file;
 pub mod std1;
 
+use file::FileRuleSet;
+
 pub type RuleSetBox = Box<dyn RuleSet + Send + Sync>;
 
 pub trait RuleSet: DynClone {
     fn type_(&self) -> RuleSetType;
+    /// Serializable form of this ruleset, sent to clients so they play with the same rules
+    fn file(&self) -> FileRuleSet;
     fn tasks(&self) -> Vec<TaskType>;
-    fn unit_can(&self, type_: &UnitType) -> Vec<TaskType>;
+    /// Tasks a unit of given type can do, once given techs known
+    fn unit_can(&self, type_: &UnitType, techs: &[TechType]) -> Vec<TaskType>;
     fn settle_duration(&self, unit_type: &UnitType) -> GameFrame;
     fn can_settle(&self, unit: &UnitType) -> bool;
+    fn can_move(&self, unit: &UnitType) -> bool;
+    fn can_enter(&self, unit_type: &UnitType, tile: &Tile) -> bool;
+    /// Frames needed by given unit type to enter given tile
+    fn move_duration(&self, unit_type: &UnitType, tile: &Tile) -> GameFrame;
+    /// Frames needed by given unit type to move along a river, between two tiles it connects
+    fn river_move_duration(&self, unit_type: &UnitType) -> GameFrame;
+    /// Yield of given tile, including its river and resource bonuses
+    fn tile_yield(&self, tile: &Tile) -> TileYield;
+    /// Yield bonus given by a resource to its tile
+    fn resource_yield(&self, resource: &Resource) -> TileYield;
     fn required_tons(&self, product: &CityProduct) -> CityProductionTons;
+    /// Product of cities which production queue is empty
+    fn default_product(&self) -> CityProduct;
+    /// Buildings cities can build
+    fn buildings(&self) -> Vec<BuildingType>;
+    /// Yield of a city worked tiles once its buildings bonuses applied
+    fn city_yield(&self, yield_: TileYield, buildings: &[BuildingType]) -> TileYield;
+    /// Food kept by a city when it gets a new citizen which required given food
+    fn growth_kept_food(&self, required: u64, buildings: &[BuildingType]) -> u64;
+    /// City defence multiplier (in percent) given by its buildings
+    fn city_defence(&self, buildings: &[BuildingType]) -> u64;
+    /// Units produced by a city with given buildings are veterans
+    fn veteran_units(&self, buildings: &[BuildingType]) -> bool;
+    /// Resources a city must have access to for producing given product
+    fn required_resources(&self, product: &CityProduct) -> Vec<Resource>;
     fn can_be_startup(&self, tile: &Tile) -> bool;
+    /// Frames of a city growth cycle, at the end of which its food surplus is stored
+    fn growth_duration(&self) -> GameFrame;
+    /// Food eaten by each citizen during a growth cycle
+    fn citizen_food(&self) -> u64;
+    /// Stored food needed by a city of given size to get a new citizen
+    fn growth_food(&self, size: u64) -> u64;
+    /// Radius (in tiles) around a unit of given type which is visible by its player
+    fn unit_sight(&self, unit_type: &UnitType) -> u64;
+    /// Radius (in tiles) around a city which is visible by its player
+    fn city_sight(&self) -> u64;
+    /// Radius (in tiles) around a city which its citizens can work
+    fn city_work_radius(&self) -> u64;
+    /// Attack strength of given unit type (units without attack strength can't attack)
+    fn unit_attack(&self, unit_type: &UnitType) -> u64;
+    /// Defence strength of given unit type
+    fn unit_defence(&self, unit_type: &UnitType) -> u64;
+    /// Damage a unit of given type can take before dying
+    fn unit_hit_points(&self, unit_type: &UnitType) -> u64;
+    /// Frames of a combat round, at the end of which its loser takes one damage
+    fn combat_round_duration(&self) -> GameFrame;
+    /// Tech tree
+    fn techs(&self) -> Vec<Tech>;
+    /// Frames of a research cycle, at the end of which cities science is stored
+    fn research_duration(&self) -> GameFrame;
+    /// Trade produced by a city during a research or upkeep cycle, split by the player tax
+    /// rate into science and gold
+    fn city_trade(&self, yield_: &TileYield) -> u64;
+    /// Frames of an upkeep cycle, at the end of which gold is collected and upkeep paid
+    fn upkeep_duration(&self) -> GameFrame;
+    /// Gold paid each upkeep cycle for a unit of given type
+    fn unit_upkeep(&self, unit_type: &UnitType) -> u64;
+    /// Gold paid each upkeep cycle for given building
+    fn building_upkeep(&self, building: &BuildingType) -> u64;
+    /// Gold given back when given building is sold
+    fn building_sale(&self, building: &BuildingType) -> u64;
+    /// Upkeep each city supports without gold
+    fn city_free_upkeep(&self) -> u64;
+
+    fn tech(&self, type_: &TechType) -> Option<Tech> {
+        self.techs().into_iter().find(|tech| tech.type_() == type_)
+    }
+
+    /// Given techs know all techs which unlock this (things no tech unlocks are always
+    /// unlocked)
+    fn unlocked(&self, unlock: &TechUnlock, techs: &[TechType]) -> bool {
+        self.techs()
+            .iter()
+            .filter(|tech| tech.unlocks().contains(unlock))
+            .all(|tech| techs.contains(tech.type_()))
+    }
+
+    /// Given product can be produced once given techs known
+    fn can_produce(&self, product: &CityProduct, techs: &[TechType]) -> bool {
+        self.unlocked(&product.into(), techs)
+    }
 }
 
 dyn_clone::clone_trait_object!(RuleSet);
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Constructor)]
+pub struct TileYield {
+    pub food: u64,
+    pub production: u64,
+    pub trade: u64,
+}
+
+impl Add for TileYield {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            food: self.food + rhs.food,
+            production: self.production + rhs.production,
+            trade: self.trade + rhs.trade,
+        }
+    }
+}
+
+impl AddAssign for TileYield {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum RuleSetType {
     Testing,
     Std1,
+    /// Loaded from a ruleset file which is not a built-in one
+    Custom,
 }
 
 impl From<RuleSetBox> for RuleSetType {
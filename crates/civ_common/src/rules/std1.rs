@@ -1,13 +1,14 @@
 use crate::{
     game::{
-        city::{CityProduct, CityProductionTons},
+        city::{BuildingType, CityProduct, CityProductionTons},
+        tech::{Tech, TechType, TechUnlock},
         unit::{TaskType, UnitTaskType, UnitType},
         GameFrame, GAME_FRAMES_PER_SECOND,
     },
-    world::{TerrainType, Tile},
+    world::{Elevation, Resource, TerrainType, Tile, Vegetation},
 };
 
-use super::{RuleSet, RuleSetType};
+use super::{file::FileRuleSet, RuleSet, RuleSetType, TileYield};
 
 #[derive(Clone)]
 pub struct Std1RuleSet;
@@ -17,15 +18,37 @@ impl RuleSet for Std1RuleSet {
         RuleSetType::Std1
     }
 
+    fn file(&self) -> FileRuleSet {
+        FileRuleSet::std1()
+    }
+
     fn tasks(&self) -> Vec<TaskType> {
-        vec![TaskType::Unit(UnitTaskType::Settle)]
+        vec![
+            TaskType::Unit(UnitTaskType::Settle),
+            TaskType::Unit(UnitTaskType::Move),
+            TaskType::Unit(UnitTaskType::Attack),
+        ]
     }
 
-    fn unit_can(&self, type_: &UnitType) -> Vec<TaskType> {
-        match type_ {
-            UnitType::Settlers => vec![TaskType::Unit(UnitTaskType::Settle)],
-            UnitType::Warriors => vec![],
-        }
+    fn unit_can(&self, type_: &UnitType, techs: &[TechType]) -> Vec<TaskType> {
+        let tasks = match type_ {
+            UnitType::Settlers => vec![
+                TaskType::Unit(UnitTaskType::Settle),
+                TaskType::Unit(UnitTaskType::Move),
+            ],
+            UnitType::Warriors => vec![
+                TaskType::Unit(UnitTaskType::Move),
+                TaskType::Unit(UnitTaskType::Attack),
+            ],
+        };
+
+        tasks
+            .into_iter()
+            .filter(|task| match task {
+                TaskType::Unit(task) => self.unlocked(&TechUnlock::Task(task.clone()), techs),
+                _ => true,
+            })
+            .collect()
     }
 
     fn settle_duration(&self, unit_type: &UnitType) -> GameFrame {
@@ -42,20 +65,294 @@ impl RuleSet for Std1RuleSet {
         }
     }
 
+    fn can_move(&self, unit_type: &UnitType) -> bool {
+        match unit_type {
+            UnitType::Settlers | UnitType::Warriors => true,
+        }
+    }
+
+    fn can_enter(&self, _unit_type: &UnitType, tile: &Tile) -> bool {
+        !tile.is_water()
+    }
+
+    fn move_duration(&self, unit_type: &UnitType, tile: &Tile) -> GameFrame {
+        let unit = unit_move_duration(unit_type);
+        let elevation = match tile.elevation() {
+            Elevation::Flat => 1,
+            Elevation::Hills => 2,
+            Elevation::Mountains => 3,
+        };
+        let vegetation = match tile.vegetation() {
+            None => 1,
+            Some(Vegetation::Forest | Vegetation::Jungle | Vegetation::Swamp) => 2,
+        };
+
+        GameFrame(unit * elevation.max(vegetation))
+    }
+
+    fn river_move_duration(&self, unit_type: &UnitType) -> GameFrame {
+        GameFrame(unit_move_duration(unit_type) / 3)
+    }
+
+    fn tile_yield(&self, tile: &Tile) -> TileYield {
+        let mut yield_ = terrain_yield(tile);
+
+        // Rivers bring trade to any land tile
+        if tile.has_river() && !tile.is_water() {
+            yield_ += TileYield::new(0, 0, 1);
+        }
+
+        if let Some(resource) = tile.resource() {
+            yield_ += self.resource_yield(&resource);
+        }
+
+        yield_
+    }
+
+    fn resource_yield(&self, resource: &Resource) -> TileYield {
+        match resource {
+            Resource::Wheat => TileYield::new(2, 0, 0),
+            Resource::Fruit => TileYield::new(3, 0, 1),
+            Resource::Fish => TileYield::new(2, 0, 0),
+            Resource::Horses => TileYield::new(0, 1, 0),
+            Resource::Iron => TileYield::new(0, 3, 0),
+            Resource::Coal => TileYield::new(0, 2, 0),
+            Resource::Gold => TileYield::new(0, 0, 6),
+            Resource::Oil => TileYield::new(0, 3, 0),
+        }
+    }
+
     fn required_tons(&self, product: &CityProduct) -> CityProductionTons {
         match product {
             CityProduct::Unit(unit_type) => match unit_type {
                 UnitType::Settlers => CityProductionTons(40),
                 UnitType::Warriors => CityProductionTons(8),
             },
+            CityProduct::Building(building) => match building {
+                BuildingType::Barracks => CityProductionTons(30),
+                BuildingType::Granary | BuildingType::Walls => CityProductionTons(60),
+                BuildingType::Library => CityProductionTons(90),
+            },
+        }
+    }
+
+    fn default_product(&self) -> CityProduct {
+        CityProduct::Unit(UnitType::Warriors)
+    }
+
+    fn buildings(&self) -> Vec<BuildingType> {
+        vec![
+            BuildingType::Granary,
+            BuildingType::Barracks,
+            BuildingType::Walls,
+            BuildingType::Library,
+        ]
+    }
+
+    fn city_yield(&self, yield_: TileYield, buildings: &[BuildingType]) -> TileYield {
+        let mut yield_ = yield_;
+
+        if buildings.contains(&BuildingType::Library) {
+            yield_.trade += yield_.trade / 2;
+        }
+
+        yield_
+    }
+
+    fn growth_kept_food(&self, required: u64, buildings: &[BuildingType]) -> u64 {
+        if buildings.contains(&BuildingType::Granary) {
+            required / 2
+        } else {
+            0
+        }
+    }
+
+    fn city_defence(&self, buildings: &[BuildingType]) -> u64 {
+        if buildings.contains(&BuildingType::Walls) {
+            300
+        } else {
+            100
+        }
+    }
+
+    fn veteran_units(&self, buildings: &[BuildingType]) -> bool {
+        buildings.contains(&BuildingType::Barracks)
+    }
+
+    fn required_resources(&self, product: &CityProduct) -> Vec<Resource> {
+        match product {
+            CityProduct::Unit(UnitType::Settlers | UnitType::Warriors) => vec![],
+            CityProduct::Building(BuildingType::Barracks) => vec![Resource::Iron],
+            CityProduct::Building(
+                BuildingType::Granary | BuildingType::Walls | BuildingType::Library,
+            ) => vec![],
         }
     }
 
     fn can_be_startup(&self, tile: &Tile) -> bool {
+        if tile.elevation() != Elevation::Flat || tile.vegetation().is_some() {
+            return false;
+        }
+
         match tile.type_() {
             TerrainType::GrassLand | TerrainType::Plain => true,
+            TerrainType::Desert
+            | TerrainType::Tundra
+            | TerrainType::Coast
+            | TerrainType::Ocean
+            | TerrainType::Lake => false,
         }
     }
+
+    fn growth_duration(&self) -> GameFrame {
+        GameFrame(GAME_FRAMES_PER_SECOND * 60)
+    }
+
+    fn citizen_food(&self) -> u64 {
+        2
+    }
+
+    fn growth_food(&self, size: u64) -> u64 {
+        (size + 1) * 10
+    }
+
+    fn unit_sight(&self, unit_type: &UnitType) -> u64 {
+        match unit_type {
+            UnitType::Settlers | UnitType::Warriors => 1,
+        }
+    }
+
+    fn city_sight(&self) -> u64 {
+        2
+    }
+
+    fn city_work_radius(&self) -> u64 {
+        2
+    }
+
+    fn unit_attack(&self, unit_type: &UnitType) -> u64 {
+        match unit_type {
+            UnitType::Settlers => 0,
+            UnitType::Warriors => 1,
+        }
+    }
+
+    fn unit_defence(&self, unit_type: &UnitType) -> u64 {
+        match unit_type {
+            UnitType::Settlers | UnitType::Warriors => 1,
+        }
+    }
+
+    fn unit_hit_points(&self, unit_type: &UnitType) -> u64 {
+        match unit_type {
+            UnitType::Settlers | UnitType::Warriors => 10,
+        }
+    }
+
+    fn combat_round_duration(&self) -> GameFrame {
+        GameFrame(GAME_FRAMES_PER_SECOND)
+    }
+
+    fn techs(&self) -> Vec<Tech> {
+        vec![
+            Tech::new(TechType::Alphabet, vec![], 10, vec![]),
+            Tech::new(
+                TechType::Writing,
+                vec![TechType::Alphabet],
+                20,
+                vec![TechUnlock::Building(BuildingType::Library)],
+            ),
+            Tech::new(
+                TechType::Pottery,
+                vec![],
+                10,
+                vec![TechUnlock::Building(BuildingType::Granary)],
+            ),
+            Tech::new(
+                TechType::Masonry,
+                vec![],
+                10,
+                vec![TechUnlock::Building(BuildingType::Walls)],
+            ),
+            Tech::new(TechType::BronzeWorking, vec![], 10, vec![]),
+            Tech::new(
+                TechType::WarriorCode,
+                vec![],
+                10,
+                vec![TechUnlock::Building(BuildingType::Barracks)],
+            ),
+        ]
+    }
+
+    fn research_duration(&self) -> GameFrame {
+        GameFrame(GAME_FRAMES_PER_SECOND * 60)
+    }
+
+    fn city_trade(&self, yield_: &TileYield) -> u64 {
+        // City center always produces a bit of trade
+        yield_.trade + 1
+    }
+
+    fn upkeep_duration(&self) -> GameFrame {
+        GameFrame(GAME_FRAMES_PER_SECOND * 60)
+    }
+
+    fn unit_upkeep(&self, unit_type: &UnitType) -> u64 {
+        match unit_type {
+            UnitType::Settlers => 0,
+            UnitType::Warriors => 1,
+        }
+    }
+
+    fn building_upkeep(&self, building: &BuildingType) -> u64 {
+        match building {
+            BuildingType::Granary
+            | BuildingType::Barracks
+            | BuildingType::Walls
+            | BuildingType::Library => 1,
+        }
+    }
+
+    fn building_sale(&self, building: &BuildingType) -> u64 {
+        self.required_tons(&CityProduct::Building(*building)).0
+    }
+
+    fn city_free_upkeep(&self) -> u64 {
+        2
+    }
+}
+
+/// Vegetation, then elevation, override terrain yield
+fn terrain_yield(tile: &Tile) -> TileYield {
+    if let Some(vegetation) = tile.vegetation() {
+        return match vegetation {
+            Vegetation::Forest => TileYield::new(1, 2, 0),
+            Vegetation::Jungle | Vegetation::Swamp => TileYield::new(1, 0, 0),
+        };
+    }
+
+    match tile.elevation() {
+        Elevation::Hills => return TileYield::new(1, 0, 0),
+        Elevation::Mountains => return TileYield::new(0, 1, 0),
+        Elevation::Flat => {}
+    }
+
+    match tile.type_() {
+        TerrainType::GrassLand => TileYield::new(2, 0, 0),
+        TerrainType::Plain => TileYield::new(1, 1, 0),
+        TerrainType::Desert => TileYield::new(0, 1, 0),
+        TerrainType::Tundra => TileYield::new(1, 0, 0),
+        TerrainType::Coast | TerrainType::Ocean => TileYield::new(1, 0, 2),
+        TerrainType::Lake => TileYield::new(2, 0, 2),
+    }
+}
+
+/// Duration to move on a flat tile without vegetation
+fn unit_move_duration(unit_type: &UnitType) -> u64 {
+    match unit_type {
+        UnitType::Settlers => GAME_FRAMES_PER_SECOND * 4,
+        UnitType::Warriors => GAME_FRAMES_PER_SECOND * 3,
+    }
 }
 
 impl From<Std1RuleSet> for RuleSetType {
@@ -63,3 +360,76 @@ impl From<Std1RuleSet> for RuleSetType {
         RuleSetType::Std1
     }
 }
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use crate::{geo::Direction, world::RiverEdges};
+
+    use super::*;
+
+    #[rstest]
+    #[case(Tile::new(TerrainType::GrassLand), true)]
+    #[case(Tile::new(TerrainType::Desert), false)]
+    #[case(Tile::new(TerrainType::Ocean), false)]
+    #[case(Tile::builder().type_(TerrainType::Plain).elevation(Elevation::Hills).build(), false)]
+    #[case(Tile::builder().type_(TerrainType::Plain).vegetation(Vegetation::Forest).build(), false)]
+    fn test_can_be_startup(#[case] tile: Tile, #[case] expected: bool) {
+        assert_eq!(Std1RuleSet.can_be_startup(&tile), expected);
+    }
+
+    #[rstest]
+    #[case(Tile::new(TerrainType::Plain), TileYield::new(1, 1, 0))]
+    #[case(Tile::builder().type_(TerrainType::Plain).elevation(Elevation::Mountains).build(), TileYield::new(0, 1, 0))]
+    #[case(Tile::builder().type_(TerrainType::GrassLand).vegetation(Vegetation::Forest).build(), TileYield::new(1, 2, 0))]
+    #[case(Tile::new(TerrainType::Plain).with_rivers(RiverEdges::default().with(Direction::East)), TileYield::new(1, 1, 1))]
+    #[case(Tile::new(TerrainType::Lake), TileYield::new(2, 0, 2))]
+    #[case(Tile::builder().type_(TerrainType::Plain).elevation(Elevation::Hills).resource(Resource::Iron).build(), TileYield::new(1, 3, 0))]
+    fn test_tile_yield(#[case] tile: Tile, #[case] expected: TileYield) {
+        assert_eq!(Std1RuleSet.tile_yield(&tile), expected);
+    }
+
+    #[test]
+    fn test_move_duration() {
+        let flat = Tile::new(TerrainType::Plain);
+        let hills = Tile::builder()
+            .type_(TerrainType::Plain)
+            .elevation(Elevation::Hills)
+            .build();
+
+        assert!(
+            Std1RuleSet.move_duration(&UnitType::Warriors, &hills)
+                > Std1RuleSet.move_duration(&UnitType::Warriors, &flat)
+        );
+        assert!(
+            Std1RuleSet.river_move_duration(&UnitType::Warriors)
+                < Std1RuleSet.move_duration(&UnitType::Warriors, &flat)
+        );
+        assert!(!Std1RuleSet.can_enter(&UnitType::Warriors, &Tile::new(TerrainType::Ocean)));
+    }
+
+    #[rstest]
+    #[case(CityProduct::Unit(UnitType::Warriors), vec![], true)]
+    #[case(CityProduct::Building(BuildingType::Granary), vec![], false)]
+    #[case(CityProduct::Building(BuildingType::Granary), vec![TechType::Pottery], true)]
+    #[case(CityProduct::Building(BuildingType::Library), vec![TechType::Alphabet], false)]
+    fn test_can_produce(
+        #[case] product: CityProduct,
+        #[case] techs: Vec<TechType>,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(Std1RuleSet.can_produce(&product, &techs), expected);
+    }
+
+    #[test]
+    fn test_techs_prerequisites_are_techs() {
+        let techs = Std1RuleSet.techs();
+
+        for tech in &techs {
+            for prerequisite in tech.prerequisites() {
+                assert!(techs.iter().any(|t| t.type_() == prerequisite));
+            }
+        }
+    }
+}
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 pub mod window;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Constructor)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Constructor)]
 pub struct D2Size {
     width: usize,
     height: usize,
@@ -1,6 +1,14 @@
 use thiserror::Error;
 
-use crate::game::unit::UnitType;
+use crate::{
+    game::{
+        city::CityProductionError,
+        tech::TechType,
+        unit::{TaskType, UnitType},
+    },
+    geo::WorldPoint,
+    path::PathError,
+};
 
 #[derive(Error, Debug)]
 pub enum CreateTaskError {
@@ -14,6 +22,20 @@ pub enum CreateTaskError {
 pub enum GamePlayReason {
     #[error("Cant settle: {0}")]
     CantSettle(CantSettleReason),
+    #[error("Cant move: {0}")]
+    CantMove(CantMoveReason),
+    #[error("Cant attack: {0}")]
+    CantAttack(CantAttackReason),
+    #[error("Cant exploit: {0}")]
+    CantExploit(CantExploitReason),
+    #[error("Cant change production: {0}")]
+    CantChangeProduction(CityProductionError),
+    #[error("Cant research: {0}")]
+    CantResearch(CantResearchReason),
+    #[error("Tax rate {0} is not a percentage")]
+    InvalidTaxRate(u8),
+    #[error("{0} requires an unknown tech")]
+    TaskLocked(TaskType),
     #[error("City no longer exist")]
     CityNoLongerExist,
     #[error("Unit no longer exist")]
@@ -27,3 +49,53 @@ pub enum CantSettleReason {
     #[error("{0} can't settle")]
     WrongUnitType(UnitType),
 }
+
+#[derive(Error, Debug)]
+pub enum CantMoveReason {
+    #[error("{0} can't move")]
+    WrongUnitType(UnitType),
+    #[error("Unit is already at destination")]
+    AlreadyThere,
+    #[error("Destination is outside the world")]
+    OutsideWorld,
+    #[error("Tile is occupied by another player, attack it instead")]
+    Occupied,
+    #[error("{0}")]
+    Path(#[from] PathError),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CantAttackReason {
+    #[error("{0} can't attack")]
+    WrongUnitType(UnitType),
+    #[error("Target is not on a neighbor tile")]
+    TooFar,
+    #[error("Target is owned by the same player")]
+    SameFlag,
+    #[error("Target no longer exist")]
+    TargetNoLongerExist,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CantResearchReason {
+    #[error("{0} is not in the tech tree")]
+    NotInTree(TechType),
+    #[error("{0} is already known")]
+    AlreadyKnown(TechType),
+    #[error("{0} must be known first")]
+    MissingPrerequisite(TechType),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CantExploitReason {
+    #[error("City has only {0} citizens")]
+    NotEnoughCitizens(u64),
+    #[error("Tile {0:?} is out of city work radius")]
+    OutOfRadius(WorldPoint),
+    #[error("Tile {0:?} is outside the world")]
+    OutsideWorld(WorldPoint),
+    #[error("Tile {0:?} is worked twice")]
+    AlreadyWorked(WorldPoint),
+    #[error("Tile {0:?} is worked by another city")]
+    WorkedByOtherCity(WorldPoint),
+}
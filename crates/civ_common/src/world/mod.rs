@@ -1,38 +1,177 @@
 use bon::Builder;
 use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+use crate::geo::Direction;
 
 pub mod slice;
 pub mod tile;
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
 pub enum TerrainType {
     GrassLand,
     Plain,
+    Desert,
+    Tundra,
+    Coast,
+    Ocean,
+    Lake,
+}
+
+impl TerrainType {
+    pub fn is_water(&self) -> bool {
+        matches!(
+            self,
+            TerrainType::Coast | TerrainType::Ocean | TerrainType::Lake
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default, EnumIter)]
+pub enum Elevation {
+    #[default]
+    Flat,
+    Hills,
+    Mountains,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum Vegetation {
+    Forest,
+    Jungle,
+    Swamp,
+}
+
+/// Bonus (food, production, trade) or strategic (required by some products) tile resource
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Display)]
+pub enum Resource {
+    Wheat,
+    Fruit,
+    Fish,
+    Horses,
+    Iron,
+    Coal,
+    Gold,
+    Oil,
+}
+
+/// Directions where a river flows from a tile to its neighbors
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default, Hash)]
+pub struct RiverEdges(u8);
+
+impl RiverEdges {
+    pub fn with(self, direction: Direction) -> Self {
+        Self(self.0 | Self::bit(direction))
+    }
+
+    pub fn contains(&self, direction: Direction) -> bool {
+        self.0 & Self::bit(direction) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn directions(&self) -> Vec<Direction> {
+        Direction::iter().filter(|d| self.contains(*d)).collect()
+    }
+
+    fn bit(direction: Direction) -> u8 {
+        1 << direction as u8
+    }
 }
 
 pub trait TileDetail {
     fn type_(&self) -> TerrainType;
+    fn elevation(&self) -> Elevation;
+    fn vegetation(&self) -> Option<Vegetation>;
+    fn rivers(&self) -> RiverEdges;
+    fn resource(&self) -> Option<Resource>;
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Builder)]
 pub struct Tile {
     type_: TerrainType,
+    #[builder(default)]
+    elevation: Elevation,
+    vegetation: Option<Vegetation>,
+    #[builder(default)]
+    rivers: RiverEdges,
+    resource: Option<Resource>,
 }
 
 impl Tile {
+    /// Flat tile without vegetation
     pub fn new(type_: TerrainType) -> Self {
-        Self { type_ }
+        Self {
+            type_,
+            elevation: Elevation::Flat,
+            vegetation: None,
+            rivers: RiverEdges::default(),
+            resource: None,
+        }
+    }
+
+    pub fn with_rivers(mut self, rivers: RiverEdges) -> Self {
+        self.rivers = rivers;
+        self
+    }
+
+    pub fn with_resource(mut self, resource: Option<Resource>) -> Self {
+        self.resource = resource;
+        self
     }
 
     pub fn type_(&self) -> TerrainType {
         self.type_
     }
+
+    pub fn elevation(&self) -> Elevation {
+        self.elevation
+    }
+
+    pub fn vegetation(&self) -> Option<Vegetation> {
+        self.vegetation
+    }
+
+    pub fn rivers(&self) -> RiverEdges {
+        self.rivers
+    }
+
+    pub fn resource(&self) -> Option<Resource> {
+        self.resource
+    }
+
+    pub fn has_river(&self) -> bool {
+        !self.rivers.is_empty()
+    }
+
+    pub fn is_water(&self) -> bool {
+        self.type_.is_water()
+    }
 }
 
 impl TileDetail for Tile {
     fn type_(&self) -> TerrainType {
         self.type_
     }
+
+    fn elevation(&self) -> Elevation {
+        self.elevation
+    }
+
+    fn vegetation(&self) -> Option<Vegetation> {
+        self.vegetation
+    }
+
+    fn rivers(&self) -> RiverEdges {
+        self.rivers
+    }
+
+    fn resource(&self) -> Option<Resource> {
+        self.resource
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -47,12 +186,19 @@ pub struct World {
     pub chunk_size: u64,
     pub width: u64,
     pub height: u64,
+    /// Seed used to generate this world (same seed produces same world)
+    #[builder(default)]
+    pub seed: u64,
 }
 
 // FIXME BS NOW: not necessary anymore ? (Because Option<WorldTile> now)
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CtxTile<T> {
     Outside,
+    /// Never explored by the player
+    Unknown,
+    /// Explored by the player, but not currently visible
+    Explored(T),
     Visible(T),
 }
 
@@ -60,6 +206,8 @@ impl From<CtxTile<&Tile>> for CtxTile<Tile> {
     fn from(value: CtxTile<&Tile>) -> Self {
         match value {
             CtxTile::Outside => CtxTile::Outside,
+            CtxTile::Unknown => CtxTile::Unknown,
+            CtxTile::Explored(tile) => CtxTile::Explored(tile.clone()),
             CtxTile::Visible(tile) => CtxTile::Visible(tile.clone()),
         }
     }
@@ -0,0 +1,266 @@
+"""Source of terrain2.png: sprites of the terrains missing in terrain1.png (Amplio tileset).
+
+Each sprite is drawn over (and clipped to) a background tile of terrain1.png, so both
+tilesets share the same isometric shape and grid. Run from this directory:
+
+    python3 terrain2.py
+"""
+
+import math
+import struct
+import zlib
+
+SOURCE = "terrain1.png"
+TARGET = "terrain2.png"
+# Same grid than terrain1.png (see `assets/tile.rs`)
+TILE_WIDTH, TILE_HEIGHT = 93, 48
+PADDING_X, PADDING_Y = 4, 2
+OFFSET_X, OFFSET_Y = 2, 1
+SOURCE_COLUMNS = 10
+# Anti-aliasing: sub pixels per pixel side
+SAMPLES = 4
+
+
+def read_png(path):
+    data = open(path, "rb").read()
+    position, chunks = 8, []
+    while position < len(data):
+        (length,) = struct.unpack(">I", data[position : position + 4])
+        type_ = data[position + 4 : position + 8]
+        chunks.append((type_, data[position + 8 : position + 8 + length]))
+        position += 12 + length
+    width, height, depth, color = struct.unpack(">IIBB", chunks[0][1][:10])
+    assert (depth, color) == (8, 6), "RGBA 8 bits expected"
+    raw = zlib.decompress(b"".join(c for t, c in chunks if t == b"IDAT"))
+    stride, rows, previous, position = width * 4, [], bytearray(width * 4), 0
+    for _ in range(height):
+        filter_, line = raw[position], bytearray(raw[position + 1 : position + 1 + stride])
+        position += 1 + stride
+        for x in range(stride):
+            a = line[x - 4] if x >= 4 else 0
+            b = previous[x]
+            c = previous[x - 4] if x >= 4 else 0
+            if filter_ == 1:
+                line[x] = (line[x] + a) & 255
+            elif filter_ == 2:
+                line[x] = (line[x] + b) & 255
+            elif filter_ == 3:
+                line[x] = (line[x] + (a + b) // 2) & 255
+            elif filter_ == 4:
+                pa, pb, pc = abs(b - c), abs(a - c), abs(a + b - 2 * c)
+                line[x] = (line[x] + (a if pa <= pb and pa <= pc else b if pb <= pc else c)) & 255
+        rows.append(line)
+        previous = line
+    return width, height, rows
+
+
+def write_png(path, width, height, rows):
+    def chunk(type_, content):
+        crc = zlib.crc32(type_ + content) & 0xFFFFFFFF
+        return struct.pack(">I", len(content)) + type_ + content + struct.pack(">I", crc)
+
+    header = struct.pack(">IIBBBBB", width, height, 8, 6, 0, 0, 0)
+    raw = b"".join(b"\x00" + bytes(row) for row in rows)
+    with open(path, "wb") as file:
+        file.write(b"\x89PNG\r\n\x1a\n")
+        file.write(chunk(b"IHDR", header))
+        file.write(chunk(b"IDAT", zlib.compress(raw, 9)))
+        file.write(chunk(b"IEND", b""))
+
+
+def noise(x, y, seed):
+    """Deterministic value in [0, 1) for an integer point"""
+    value = (x * 374761393 + y * 668265263 + seed * 2147483647) & 0xFFFFFFFF
+    value = ((value ^ (value >> 13)) * 1274126177) & 0xFFFFFFFF
+    return (value ^ (value >> 16)) / 2**32
+
+
+def shade(color, factor):
+    return tuple(max(0, min(255, round(channel * factor))) for channel in color)
+
+
+def mix(color, other, ratio):
+    return tuple(round(a * (1 - ratio) + b * ratio) for a, b in zip(color, other))
+
+
+class Sprite:
+    """Tile sprite: a background cell of terrain1.png, its alpha being the tile shape"""
+
+    def __init__(self, source, index):
+        _, _, rows = source
+        x0 = OFFSET_X + (index % SOURCE_COLUMNS) * (TILE_WIDTH + PADDING_X)
+        y0 = OFFSET_Y + (index // SOURCE_COLUMNS) * (TILE_HEIGHT + PADDING_Y)
+        self.pixels = [
+            [list(rows[y0 + y][(x0 + x) * 4 : (x0 + x) * 4 + 4]) for x in range(TILE_WIDTH)]
+            for y in range(TILE_HEIGHT)
+        ]
+        self.mask = [[pixel[3] for pixel in row] for row in self.pixels]
+
+    def blend(self, x, y, color, coverage):
+        """Paint `color` over pixel, with `coverage` in [0, 1], inside the tile shape only"""
+        if not (0 <= x < TILE_WIDTH and 0 <= y < TILE_HEIGHT) or coverage <= 0:
+            return
+        coverage *= self.mask[y][x] / 255
+        pixel = self.pixels[y][x]
+        for channel in range(3):
+            pixel[channel] = round(pixel[channel] * (1 - coverage) + color[channel] * coverage)
+
+    def fill(self, inside, color_at, bounds=None):
+        """Paint shape `inside(x, y)` (sub pixel coordinates), colored by `color_at(x, y)`"""
+        left, top, right, bottom = bounds or (0, 0, TILE_WIDTH, TILE_HEIGHT)
+        for y in range(max(0, math.floor(top)), min(TILE_HEIGHT, math.ceil(bottom) + 1)):
+            for x in range(max(0, math.floor(left)), min(TILE_WIDTH, math.ceil(right) + 1)):
+                hits = 0
+                for sy in range(SAMPLES):
+                    for sx in range(SAMPLES):
+                        if inside(x + (sx + 0.5) / SAMPLES, y + (sy + 0.5) / SAMPLES):
+                            hits += 1
+                if hits:
+                    self.blend(x, y, color_at(x + 0.5, y + 0.5), hits / SAMPLES**2)
+
+    def ellipse(self, cx, cy, rx, ry, color_at):
+        self.fill(
+            lambda x, y: ((x - cx) / rx) ** 2 + ((y - cy) / ry) ** 2 <= 1,
+            color_at,
+            (cx - rx, cy - ry, cx + rx, cy + ry),
+        )
+
+    def polygon(self, points, color_at):
+        def inside(x, y):
+            result = False
+            for (x1, y1), (x2, y2) in zip(points, points[1:] + points[:1]):
+                if (y1 > y) != (y2 > y) and x < x1 + (y - y1) * (x2 - x1) / (y2 - y1):
+                    result = not result
+            return result
+
+        xs, ys = [p[0] for p in points], [p[1] for p in points]
+        self.fill(inside, color_at, (min(xs), min(ys), max(xs), max(ys)))
+
+    def rows(self):
+        return [bytes(channel for pixel in row for channel in pixel) for row in self.pixels]
+
+
+def inside_diamond(x, y, margin):
+    """Point is in the isometric tile, at `margin` (in tile height fraction) from its edges"""
+    dx = abs(x - TILE_WIDTH / 2) / (TILE_WIDTH / 2)
+    dy = abs(y - TILE_HEIGHT / 2) / (TILE_HEIGHT / 2)
+    return dx + dy <= 1 - margin
+
+
+def water(source, deep, shallow, seed, waves):
+    """Water gradient (deeper at the bottom) with noise and some light wave strokes"""
+    sprite = Sprite(source, 20)
+    for y in range(TILE_HEIGHT):
+        for x in range(TILE_WIDTH):
+            ratio = 1 - y / TILE_HEIGHT
+            color = mix(deep, shallow, ratio * 0.6)
+            sprite.blend(x, y, shade(color, 0.94 + noise(x, y, seed) * 0.12), 1)
+    crest = shade(shallow, 1.25)
+    for index in range(waves):
+        cx = 12 + noise(index, 1, seed) * (TILE_WIDTH - 24)
+        cy = 8 + noise(index, 2, seed) * (TILE_HEIGHT - 16)
+        if not inside_diamond(cx, cy, 0.2):
+            continue
+        length = 4 + noise(index, 3, seed) * 5
+        sprite.fill(
+            lambda x, y: abs(y - cy + 0.6 * math.sin((x - cx) / length * math.pi)) < 0.45
+            and abs(x - cx) < length,
+            lambda x, y: crest,
+            (cx - length, cy - 2, cx + length, cy + 2),
+        )
+    return sprite
+
+
+def forest(source):
+    """Trees (conifers and broadleaves), back to front, over the forest background"""
+    sprite = Sprite(source, 30)
+    trees = []
+    for row in range(-3, 4):
+        for column in range(-5, 6):
+            x = TILE_WIDTH / 2 + column * 9 + (row % 2) * 4.5 + (noise(row, column, 3) - 0.5) * 4
+            y = TILE_HEIGHT / 2 + row * 6 + (noise(row, column, 4) - 0.5) * 3
+            if inside_diamond(x, y, 0.25):
+                trees.append((y, x, noise(row, column, 5) < 0.5, 0.85 + noise(row, column, 6) * 0.3))
+    dark, light = (34, 82, 28), (74, 138, 48)
+    for y, x, conifer, size in sorted(trees):
+        sprite.ellipse(x + 2, y + 0.5, 4 * size, 1.6 * size, lambda *_: (40, 70, 25))
+        sprite.polygon([(x - 0.6, y), (x + 0.6, y), (x + 0.6, y - 3), (x - 0.6, y - 3)],
+                       lambda *_: (92, 60, 34))
+        if conifer:
+            height, width = 11 * size, 4 * size
+            sprite.polygon(
+                [(x, y - 2 - height), (x + width, y - 2), (x - width, y - 2)],
+                lambda px, py, x=x: mix(light, dark, min(1, max(0, (px - x + 2) / 5))),
+            )
+        else:
+            radius = 4 * size
+            sprite.ellipse(
+                x, y - 2 - radius, radius, radius * 1.05,
+                lambda px, py, x=x, y=y: mix(
+                    light, dark, min(1, max(0, ((px - x) + (py - y + 2 + radius)) / (2 * radius) + 0.35))
+                ),
+            )
+    return sprite
+
+
+def hills(source):
+    """Rounded hills, lit from the upper left, over the hills background"""
+    sprite = Sprite(source, 40)
+    lit, shadow = (150, 158, 82), (86, 98, 44)
+    for cx, cy, rx, ry in [(30, 24, 17, 11), (62, 22, 19, 12), (46, 34, 18, 10)]:
+        sprite.ellipse(cx + 3, cy + 1, rx, ry * 0.5, lambda *_: (70, 90, 40))
+
+        def color_at(x, y, cx=cx, cy=cy, rx=rx, ry=ry):
+            # Dome normal dot light direction
+            nx, ny = (x - cx) / rx, (y - cy) / ry
+            light = max(0, min(1, 0.55 - 0.45 * nx - 0.35 * ny))
+            return shade(mix(shadow, lit, light), 0.96 + noise(int(x), int(y), 7) * 0.08)
+
+        sprite.fill(
+            lambda x, y, cx=cx, cy=cy, rx=rx, ry=ry: y <= cy + ry * 0.35
+            and ((x - cx) / rx) ** 2 + ((y - cy) / ry) ** 2 <= 1,
+            color_at,
+            (cx - rx, cy - ry, cx + rx, cy + ry),
+        )
+    return sprite
+
+
+def mountains(source):
+    """Rocky peaks with snow caps, lit from the left, over the mountains background"""
+    sprite = Sprite(source, 50)
+    lit, shadow, snow = (150, 140, 128), (88, 82, 78), (240, 242, 246)
+    for cx, base, height, half in [(34, 28, 22, 17), (60, 27, 20, 16), (47, 38, 24, 19)]:
+        top = base - height
+
+        def color_at(x, y, cx=cx, top=top, height=height):
+            color = lit if x < cx else shadow
+            if y < top + height * 0.3 + (noise(int(x), 0, 8) - 0.5) * 3:
+                color = snow if x < cx else shade(snow, 0.78)
+            return shade(color, 0.93 + noise(int(x), int(y), 9) * 0.14)
+
+        sprite.polygon([(cx, top), (cx + half, base), (cx - half, base)], color_at)
+    return sprite
+
+
+def main():
+    source = read_png(SOURCE)
+    sprites = [
+        water(source, (38, 104, 150), (88, 172, 196), 1, 6),  # Coast
+        water(source, (18, 48, 110), (40, 88, 152), 2, 8),  # Ocean
+        water(source, (46, 110, 168), (92, 160, 206), 3, 3),  # Lake
+        forest(source),
+        hills(source),
+        mountains(source),
+    ]
+    width = OFFSET_X * 2 + len(sprites) * (TILE_WIDTH + PADDING_X) - PADDING_X
+    height = OFFSET_Y * 2 + TILE_HEIGHT
+    rows = [bytearray(width * 4) for _ in range(height)]
+    for index, sprite in enumerate(sprites):
+        x0 = OFFSET_X + index * (TILE_WIDTH + PADDING_X)
+        for y, row in enumerate(sprite.rows()):
+            rows[OFFSET_Y + y][x0 * 4 : (x0 + TILE_WIDTH) * 4] = row
+    write_png(TARGET, width, height, rows)
+
+
+if __name__ == "__main__":
+    main()
@@ -45,6 +45,7 @@ pub struct Setup<W: Generator> {
     world_width: usize,
     world_height: usize,
     chunk_size: usize,
+    world_seed: Option<u64>,
     world_generator: W,
     window_start: ImaginaryWorldPoint,
     window_end: ImaginaryWorldPoint,
@@ -74,6 +75,7 @@ impl<W: Generator> Setup<W> {
             self.world_width,
             self.world_height,
             self.chunk_size,
+            self.world_seed,
         );
         let world_size = D2Size::new(self.world_width, self.world_height);
         let client = Client::default();
@@ -153,7 +155,7 @@ impl<W: Generator> Setup<W> {
         };
 
         let mut app = App::new();
-        let context = Context::new();
+        let context = Context::default();
         app.add_plugins((
             DefaultPlugins
                 .set(window_plugin())
@@ -1,10 +1,22 @@
 use crate::map::AtlasIndex;
 
 // terrain1.png
-pub const TILE_GRASSLAND: AtlasIndex = AtlasIndex(20);
+pub const TILE_DESERT: AtlasIndex = AtlasIndex(0);
 pub const TILE_PLAIN: AtlasIndex = AtlasIndex(10);
+pub const TILE_GRASSLAND: AtlasIndex = AtlasIndex(20);
+pub const TILE_TUNDRA: AtlasIndex = AtlasIndex(60);
+pub const TILE_SWAMP: AtlasIndex = AtlasIndex(80);
+pub const TILE_JUNGLE: AtlasIndex = AtlasIndex(90);
 pub const TILE_BLACK: AtlasIndex = AtlasIndex(152);
 
+// terrain2.png
+pub const TILE_COAST: AtlasIndex = AtlasIndex(0);
+pub const TILE_OCEAN: AtlasIndex = AtlasIndex(1);
+pub const TILE_LAKE: AtlasIndex = AtlasIndex(2);
+pub const TILE_FOREST: AtlasIndex = AtlasIndex(3);
+pub const TILE_HILLS: AtlasIndex = AtlasIndex(4);
+pub const TILE_MOUNTAINS: AtlasIndex = AtlasIndex(5);
+
 // units.png
 pub const UNIT_SETTLER: AtlasIndex = AtlasIndex(24);
 
@@ -13,3 +25,5 @@ pub const CITY_JUNGLE: AtlasIndex = AtlasIndex(12);
 
 // TODO
 pub const ACTION_SETTLE: AtlasIndex = AtlasIndex(12);
+pub const ACTION_MOVE: AtlasIndex = AtlasIndex(12);
+pub const ACTION_ATTACK: AtlasIndex = AtlasIndex(12);
@@ -8,6 +8,11 @@ pub const TILES_ATLAS_ROWS: u32 = 16;
 pub const TILES_ATLAS_PADDING: Option<UVec2> = Some(UVec2::new(4, 2));
 pub const TILES_ATLAS_OFFSET: Option<UVec2> = Some(UVec2::new(2, 1));
 
+// Terrains missing in terrain1.png, generated by terrain2.py on the same grid
+pub const TERRAINS_ATLAS_PATH: &str = "img/terrain2.png";
+pub const TERRAINS_ATLAS_COLUMNS: u32 = 6;
+pub const TERRAINS_ATLAS_ROWS: u32 = 1;
+
 pub fn tiles_texture_atlas_layout() -> TextureAtlasLayout {
     TextureAtlasLayout::from_grid(
         TILE_SIZE,
@@ -17,3 +22,13 @@ pub fn tiles_texture_atlas_layout() -> TextureAtlasLayout {
         TILES_ATLAS_OFFSET,
     )
 }
+
+pub fn terrains_texture_atlas_layout() -> TextureAtlasLayout {
+    TextureAtlasLayout::from_grid(
+        TILE_SIZE,
+        TERRAINS_ATLAS_COLUMNS,
+        TERRAINS_ATLAS_ROWS,
+        TILES_ATLAS_PADDING,
+        TILES_ATLAS_OFFSET,
+    )
+}
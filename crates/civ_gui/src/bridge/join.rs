@@ -2,7 +2,7 @@ use bevy::prelude::*;
 use common::{
     network::{
         message::{ClientToServerMessage, ClientToServerNetworkMessage},
-        Client,
+        Client, PROTOCOL_VERSION,
     },
     space::window::Resolution,
 };
@@ -18,6 +18,7 @@ pub fn join(trigger: On<JoinEvent>, mut commands: Commands, client_id: Res<Clien
     );
     commands.trigger(SendMessageToServerEvent(ClientToServerMessage::Network(
         ClientToServerNetworkMessage::Hello(
+            PROTOCOL_VERSION,
             Client::new(client_id, player_id),
             // FIXME BS NOW: now now now
             Resolution::new(10, 10),
@@ -56,7 +56,14 @@ pub fn connect(
                 NetEvent::Connected(_endpoint, _ok) => handler.signals().send(Signal::Connected),
                 NetEvent::Accepted(_, _) => unreachable!(), // Only generated by listening
                 NetEvent::Message(_endpoint, data) => {
-                    let message: ServerToClientMessage = bincode::deserialize(data).unwrap();
+                    let message: ServerToClientMessage = match bincode::deserialize(data) {
+                        Ok(message) => message,
+                        Err(error) => {
+                            // Probably a server of another protocol version
+                            error!("Unreadable message from server: {}", error);
+                            return;
+                        }
+                    };
                     from_server_sender_
                         .send_blocking(BridgeMessage::Server(message))
                         .unwrap();
@@ -15,6 +15,7 @@ use common::game::nation::flag::Flag;
 use common::game::GameFrame;
 use common::network::message::ClientToServerEstablishmentMessage;
 use common::network::Client;
+use common::rules::file::FileRuleSet;
 use common::utils::Progress;
 use derive_more::Constructor;
 use uuid::Uuid;
@@ -56,6 +57,7 @@ impl SingleConfiguration {
             server: ServerConfig::new(
                 Some(snapshot),
                 GameFrame(120000), // TODO
+                3,                 // TODO
                 "".to_string(),
                 "".to_string(),
             ),
@@ -237,10 +239,12 @@ pub fn join_embedded_server(
     let window = windows.single()?;
     let cam_transform = cameras.single()?;
     let resolution = (window, cam_transform).resolution();
+    // Embedded server is started with built-in rules
+    let fingerprint = FileRuleSet::std1().fingerprint();
 
     to_server!(
         commands,
-        ClientToServerEstablishmentMessage::TakePlace(conf.flag(), resolution)
+        ClientToServerEstablishmentMessage::TakePlace(conf.flag(), resolution, fingerprint)
     );
 
     Ok(())
@@ -1,11 +1,16 @@
 use bevy::{prelude::*, window::PrimaryWindow};
 use common::network::message::ClientToServerEstablishmentMessage;
 
-use crate::{menu::join::TakePlaceEvent, to_server, utils::gui::window::IntoResolution};
+use crate::{
+    menu::{join::TakePlaceEvent, state::MenuStateResource},
+    to_server,
+    utils::gui::window::IntoResolution,
+};
 
 pub fn take_place(
     trigger: On<TakePlaceEvent>,
     mut commands: Commands,
+    state: Res<MenuStateResource>,
     windows: Query<&Window, With<PrimaryWindow>>,
     cameras: Query<&GlobalTransform, With<Camera>>,
 ) {
@@ -15,11 +20,16 @@ pub fn take_place(
         return;
     };
     let resolution = (window, cam_transform).resolution();
+    // Server checks we play with the rules it sent
+    let Some(resume) = &state.join.resume else {
+        return;
+    };
+    let fingerprint = resume.rules().fingerprint();
 
     info!("Taking place as {} ...", &flag);
 
     to_server!(
         commands,
-        ClientToServerEstablishmentMessage::TakePlace(flag, resolution)
+        ClientToServerEstablishmentMessage::TakePlace(flag, resolution, fingerprint)
     );
 }
@@ -78,7 +78,14 @@ async fn listen_to_server(mut tx: Sink, to_server_receiver: Receiver<ClientToSer
 async fn listen_from_server(mut rx: Stream, from_server_sender: Sender<BridgeMessage>) {
     while let Some(msg) = rx.next().await {
         if let Ok(WsMessage::Binary(bytes)) = msg {
-            let message: ServerToClientMessage = bincode::deserialize(&bytes).unwrap();
+            let message: ServerToClientMessage = match bincode::deserialize(&bytes) {
+                Ok(message) => message,
+                Err(error) => {
+                    // Probably a server of another protocol version
+                    error!("Unreadable message from server: {}", error);
+                    continue;
+                }
+            };
             from_server_sender
                 .send(BridgeMessage::Server(message))
                 .await
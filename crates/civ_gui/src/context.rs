@@ -1,13 +1,32 @@
 use bevy::prelude::*;
 
-use common::network::ServerAddress;
+use common::{
+    network::ServerAddress,
+    rules::{file::FileRuleSet, RuleSetBox},
+};
 use derive_more::Constructor;
 
 #[derive(Resource, Deref, Constructor)]
 pub struct ContextResource(pub Context);
 
-#[derive(Debug, Clone, Constructor)]
-pub struct Context;
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    /// Rules received from the server
+    rules: Option<FileRuleSet>,
+}
+
+impl Context {
+    #[allow(unused)]
+    pub fn rule_set(&self) -> Option<RuleSetBox> {
+        self.rules
+            .clone()
+            .map(|rules| Box::new(rules) as RuleSetBox)
+    }
+
+    pub fn set_rules(&mut self, rules: FileRuleSet) {
+        self.rules = Some(rules);
+    }
+}
 
 pub enum EntryPoint {
     Root,
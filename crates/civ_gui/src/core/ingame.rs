@@ -8,6 +8,7 @@ use common::network::message::{
 use crate::{
     assets::tile::TILE_SIZE,
     bridge::MessageReceivedFromServerEvent,
+    context::ContextResource,
     core::{establishment::react_server_resume_message, state::react_state_message},
     ingame::{GameFrameResource, GameSliceResource, GameWindowResource},
     menu::state::MenuStateResource,
@@ -22,6 +23,7 @@ use super::GameWindowUpdated;
 pub fn react_server_message(
     trigger: On<MessageReceivedFromServerEvent>,
     mut commands: Commands,
+    mut context: ResMut<ContextResource>,
     mut state: ResMut<MenuStateResource>,
     mut frame: ResMut<GameFrameResource>,
     mut game_slice: ResMut<GameSliceResource>,
@@ -40,10 +42,17 @@ pub fn react_server_message(
     match &trigger.event().0 {
         ServerToClientMessage::Establishment(message) => match message {
             ServerToClientEstablishmentMessage::ServerResume(resume, flag) => {
+                context.0.set_rules(resume.rules().clone());
                 react_server_resume_message(resume, flag, &mut state, &mut next_state)
             }
-            ServerToClientEstablishmentMessage::TakePlaceRefused(_reason) => {
-                todo!()
+            ServerToClientEstablishmentMessage::TakePlaceRefused(reason) => {
+                error!("Server refused to take place: {}", reason);
+                state.taking_place = false;
+                state.join.refused = Some(reason.to_string());
+            }
+            // FIXME (gui display this error)
+            ServerToClientEstablishmentMessage::HelloRefused(reason) => {
+                error!("Server refused connection: {}", reason)
             }
         },
         ServerToClientMessage::InGame(message) => match message {
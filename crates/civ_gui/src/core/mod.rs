@@ -5,6 +5,8 @@ use common::{
     game::{
         city::CityId,
         slice::{ClientCity, ClientUnit},
+        tech::Research,
+        treasury::Treasury,
         unit::UnitId,
     },
     geo::WorldPoint,
@@ -65,3 +67,9 @@ pub struct CityRemoved(pub CityId, pub WorldPoint);
 // TODO: move
 #[derive(Event)]
 pub struct GameSlicePropagated;
+// TODO: move
+#[derive(Event)]
+pub struct ResearchUpdated(pub Research);
+// TODO: move
+#[derive(Event)]
+pub struct TreasuryUpdated(pub Treasury);
@@ -3,7 +3,9 @@ use common::network::message::ClientStateMessage;
 
 use common::space::{CityVec2dIndex, UnitVec2dIndex};
 
-use crate::core::{CityRemoved, CityUpdated, UnitRemoved, UnitUpdated};
+use crate::core::{
+    CityRemoved, CityUpdated, ResearchUpdated, TreasuryUpdated, UnitRemoved, UnitUpdated,
+};
 use crate::ingame::{GameFrameResource, GameFrameUpdated, GameSliceResource, GameWindowResource};
 
 use super::{GameSliceUpdated, GameWindowUpdated};
@@ -38,6 +40,47 @@ pub fn react_state_message_(
             slice.0 = Some(game_slice_.clone());
             Some(Box::new(|c| c.trigger(GameSliceUpdated)))
         }
+        ClientStateMessage::SetTiles(tiles) => {
+            if let Some(ref mut slice) = &mut (slice.0) {
+                for tile in tiles {
+                    let point = tile.point();
+                    slice.tiles_mut().set(point, tile.tile().clone());
+
+                    if let Some(city) = slice.cities().get(point).cloned().flatten() {
+                        slice.cities_map_mut().remove(city.id());
+                    }
+                    if let Some(index) = slice.cities_mut().set(point, tile.city().cloned()) {
+                        if let Some(city) = tile.city() {
+                            slice
+                                .cities_map_mut()
+                                .insert(*city.id(), CityVec2dIndex(index));
+                        }
+                    }
+
+                    let previous_units = slice
+                        .units()
+                        .get(point)
+                        .into_iter()
+                        .flatten()
+                        .flatten()
+                        .map(|unit| *unit.id())
+                        .collect::<Vec<_>>();
+                    for unit_id in &previous_units {
+                        slice.units_map_mut().remove(unit_id);
+                    }
+                    let units = (!tile.units().is_empty()).then(|| tile.units().to_vec());
+                    if let Some(index) = slice.units_mut().set(point, units) {
+                        for (i, unit) in tile.units().iter().enumerate() {
+                            slice
+                                .units_map_mut()
+                                .insert(*unit.id(), UnitVec2dIndex(index, i));
+                        }
+                    }
+                }
+            }
+
+            Some(Box::new(|c| c.trigger(GameSliceUpdated)))
+        }
         ClientStateMessage::SetWindow(window_) => {
             window.0 = Some(*window_);
             Some(Box::new(|c| c.trigger(GameWindowUpdated)))
@@ -117,6 +160,14 @@ pub fn react_state_message_(
             let point = *point;
             Some(Box::new(move |c| c.trigger(UnitRemoved(unit_id, point))))
         }
+        ClientStateMessage::SetResearch(research) => {
+            let research = research.clone();
+            Some(Box::new(move |c| c.trigger(ResearchUpdated(research))))
+        }
+        ClientStateMessage::SetTreasury(treasury) => {
+            let treasury = treasury.clone();
+            Some(Box::new(move |c| c.trigger(TreasuryUpdated(treasury))))
+        }
     }
 }
 
@@ -146,10 +197,11 @@ mod test {
             .name("MyCity".to_string())
             .geo(GeoContext::new(point))
             .production(CityProduction::new(vec![]))
-            .exploitation(CityExploitation::new(CityProductionTons(0)))
+            .exploitation(CityExploitation::default())
             .tasks(ClientCityTasks::new(ClientCityProductionTask::new(
                 GameFrame(0),
                 GameFrame(0),
+                CityProductionTons(1),
             )))
             .build()
     }
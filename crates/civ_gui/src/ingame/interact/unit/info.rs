@@ -19,6 +19,7 @@ pub struct SetupUnitInfo(pub UnitId);
 pub struct UnitInfo {
     unit_id: UnitId,
     task: Option<ClientTask>,
+    damage: u64,
 }
 
 #[derive(Debug, Resource, Default)]
@@ -55,6 +56,7 @@ impl DrawUiComponent for UnitInfo {
             .factor(EGUI_DISPLAY_FACTOR)
             .ui(|ui| {
                 ui.vertical_centered(|ui| {
+                    ui.label(format!("Damage: {}", self.damage));
                     ui.horizontal_wrapped(|ui| {
                         ui.label("Current task:");
                         ui.label(
@@ -83,6 +85,6 @@ impl DrawUiComponent for UnitInfo {
 
 impl From<ClientUnit> for UnitInfo {
     fn from(unit: ClientUnit) -> Self {
-        Self::new(*unit.id(), unit.task().clone())
+        Self::new(*unit.id(), unit.task().clone(), unit.damage())
     }
 }
@@ -49,7 +49,33 @@ impl DrawUiComponent for CityMenu {
             .factor(EGUI_DISPLAY_FACTOR)
             .ui(|ui| {
                 ui.vertical_centered(|ui| {
+                    ui.label(format!(
+                        "Size: {} (food: {})",
+                        self.city.size(),
+                        self.city.population().food()
+                    ));
+                    let yield_ = self.city.exploitation().yield_();
+                    ui.label(format!(
+                        "Food: {} Production: {} Trade: {} ({} tiles worked)",
+                        yield_.food,
+                        yield_.production,
+                        yield_.trade,
+                        self.city.exploitation().tiles().len()
+                    ));
                     ui.label(format!("Production: {}", self.city.production_str(&frame)));
+                    ui.label(format!("Stored: {} tons", self.city.stored_tons(&frame).0));
+                    for (i, product) in self.city.production().stack().iter().enumerate().skip(1) {
+                        ui.label(format!("{}. {}", i, product));
+                    }
+                    if self.city.production().repeat() {
+                        ui.label("(repeat)");
+                    }
+                    if !self.city.buildings().is_empty() {
+                        ui.separator();
+                        for building in self.city.buildings() {
+                            ui.label(building.to_string());
+                        }
+                    }
                 });
             })
             .call();
@@ -37,7 +37,7 @@ impl_ui_component_resource!(TileInfoMenuResource, TileInfoMenu, SetupTileInfoMen
 #[derive(Debug, Geo)]
 pub struct TileInfoMenu {
     geo: GeoContext,
-    _tile: Tile,
+    tile: Tile,
     _city: Option<ClientCity>,
     _units: Option<Vec<ClientUnit>>,
 }
@@ -50,16 +50,33 @@ impl DrawUiComponent for TileInfoMenu {
         _commands: &mut Commands,
         _frame: GameFrame,
     ) -> bool {
-        let close = false;
+        let mut close = false;
+        let point = self.geo.point();
+        let tile = &self.tile;
 
         fixed_window()
             .ctx(ctx)
             .window(window)
-            .title("TODO")
+            .title(&format!("Tile {}.{}", point.x, point.y))
             .factor(EGUI_DISPLAY_FACTOR)
             .ui(|ui| {
                 ui.vertical_centered(|ui| {
-                    ui.label("TODO");
+                    ui.label(format!("Terrain: {:?}", tile.type_()));
+                    ui.label(format!("Elevation: {:?}", tile.elevation()));
+                    if let Some(vegetation) = tile.vegetation() {
+                        ui.label(format!("Vegetation: {:?}", vegetation));
+                    }
+                    if tile.has_river() {
+                        ui.label("River");
+                    }
+                    ui.label(format!(
+                        "Resource: {}",
+                        tile.resource()
+                            .map(|resource| resource.to_string())
+                            .unwrap_or("None".to_string())
+                    ));
+
+                    close = ui.button("Close").clicked();
                 });
             })
             .call();
@@ -72,10 +89,10 @@ impl TryFrom<(GeoContext, &CtxTile<Tile>)> for TileInfoMenu {
     type Error = TryTileInfoMenuFromCtxTileError;
 
     fn try_from(value: (GeoContext, &CtxTile<Tile>)) -> Result<Self, Self::Error> {
-        if let CtxTile::Visible(tile) = value.1 {
+        if let CtxTile::Visible(tile) | CtxTile::Explored(tile) = value.1 {
             return Ok(Self {
                 geo: value.0,
-                _tile: tile.clone(),
+                tile: tile.clone(),
                 _city: None,
                 _units: None,
             });
@@ -9,6 +9,9 @@ use input::menu::on_try_menu;
 use input::select::on_try_select;
 use input::{on_click, update_last_known_cursor_position};
 use interact::unit::settle::on_setup_settle;
+use player::{
+    draw_player, on_research_updated, on_treasury_updated, ResearchResource, TreasuryResource,
+};
 use selected::{on_select_updated, SelectedResource};
 
 use crate::ingame::animation::{fade_animations, sprite_sheet_animations};
@@ -26,6 +29,7 @@ use crate::{add_city_component, add_tile_component, add_unit_component};
 pub mod input;
 pub mod interact;
 pub mod menu;
+pub mod player;
 pub mod selected;
 
 pub const EGUI_DISPLAY_FACTOR: f32 = 1.5;
@@ -41,6 +45,8 @@ impl Plugin for InGamePlugin {
             .init_resource::<GameFrameResource>()
             .init_resource::<LastKnownCursorPositionResource>()
             .init_resource::<SelectedResource>()
+            .init_resource::<ResearchResource>()
+            .init_resource::<TreasuryResource>()
             .insert_resource(
                 self.game_slice
                     .as_ref()
@@ -59,6 +65,10 @@ impl Plugin for InGamePlugin {
                 Update,
                 (sprite_sheet_animations,).run_if(in_state(AppState::InGame)),
             )
+            .add_systems(
+                EguiPrimaryContextPass,
+                (draw_player,).run_if(in_state(AppState::InGame)),
+            )
             .add_observer(on_click)
             .add_observer(on_try_select)
             .add_observer(on_try_menu)
@@ -66,6 +76,8 @@ impl Plugin for InGamePlugin {
             .add_observer(on_setup_settle)
             .add_observer(on_select_updated)
             .add_observer(update_progresses)
+            .add_observer(on_research_updated)
+            .add_observer(on_treasury_updated)
             .add_observer(select_on_game_slice_propagated);
 
         add_city_component!(app, CityMenuResource);
@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use common::game::{tech::Research, treasury::Treasury};
+
+use crate::core::{ResearchUpdated, TreasuryUpdated};
+
+/// Research of the player, as last sent by the server
+#[derive(Debug, Resource, Default, Deref)]
+pub struct ResearchResource(pub Option<Research>);
+
+/// Treasury of the player, as last sent by the server
+#[derive(Debug, Resource, Default, Deref)]
+pub struct TreasuryResource(pub Option<Treasury>);
+
+pub fn on_research_updated(trigger: On<ResearchUpdated>, mut research: ResMut<ResearchResource>) {
+    research.0 = Some(trigger.event().0.clone());
+}
+
+pub fn on_treasury_updated(trigger: On<TreasuryUpdated>, mut treasury: ResMut<TreasuryResource>) {
+    treasury.0 = Some(trigger.event().0.clone());
+}
+
+/// Player bar, at the bottom of the screen
+pub fn draw_player(
+    mut contexts: EguiContexts,
+    research: Res<ResearchResource>,
+    treasury: Res<TreasuryResource>,
+) -> Result {
+    egui::TopBottomPanel::bottom("player").show(contexts.ctx_mut()?, |ui| {
+        ui.horizontal(|ui| {
+            if let Some(treasury) = &treasury.0 {
+                ui.label(format!(
+                    "Gold: {} (tax rate {}%)",
+                    treasury.gold(),
+                    treasury.tax_rate()
+                ));
+            }
+            if let Some(research) = &research.0 {
+                let current = research
+                    .current()
+                    .map(|tech| tech.to_string())
+                    .unwrap_or("None".to_string());
+                ui.label(format!(
+                    "Research: {} ({} science, {} known)",
+                    current,
+                    research.science(),
+                    research.known().len()
+                ));
+            }
+        });
+    });
+
+    Ok(())
+}
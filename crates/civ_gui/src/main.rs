@@ -30,7 +30,7 @@ mod window;
 
 #[wasm_bindgen(start)]
 fn entrypoint() -> Result<(), JsValue> {
-    let context = Context::new();
+    let context = Context::default();
     let mut app = App::new();
 
     app.add_plugins((
@@ -13,7 +13,10 @@ use zoom::map_zoom;
 use crate::{
     assets::{
         select::{select_texture_atlas_layout, SELECT_ATLAS_PATH},
-        tile::{tiles_texture_atlas_layout, TILES_ATLAS_PATH},
+        tile::{
+            terrains_texture_atlas_layout, tiles_texture_atlas_layout, TERRAINS_ATLAS_PATH,
+            TILES_ATLAS_PATH,
+        },
         unit::{units_texture_atlas_layout, UNITS_ATLAS_PATH},
     },
     ingame::input::update_last_known_cursor_position,
@@ -81,6 +84,8 @@ impl Deref for AtlasIndex {
 pub struct AtlasesResource {
     pub tiles_atlas: Handle<TextureAtlasLayout>,
     pub tiles_texture: Handle<Image>,
+    pub terrains_atlas: Handle<TextureAtlasLayout>,
+    pub terrains_texture: Handle<Image>,
     pub units_atlas: Handle<TextureAtlasLayout>,
     pub units_texture: Handle<Image>,
     pub select_atlas: Handle<TextureAtlasLayout>,
@@ -93,16 +98,20 @@ fn init_atlases(
     assets: Res<AssetServer>,
 ) {
     let tiles_atlas = atlas.add(tiles_texture_atlas_layout());
+    let terrains_atlas = atlas.add(terrains_texture_atlas_layout());
     let units_atlas = atlas.add(units_texture_atlas_layout());
     let select_atlas = atlas.add(select_texture_atlas_layout());
 
     let tiles_texture = assets.load(TILES_ATLAS_PATH);
+    let terrains_texture = assets.load(TERRAINS_ATLAS_PATH);
     let units_texture = assets.load(UNITS_ATLAS_PATH);
     let select_texture = assets.load(SELECT_ATLAS_PATH);
 
     commands.insert_resource(AtlasesResource::new(
         tiles_atlas,
         tiles_texture,
+        terrains_atlas,
+        terrains_texture,
         units_atlas,
         units_texture,
         select_atlas,
@@ -29,6 +29,8 @@ pub struct JoinState {
     pub resume: Option<ServerResume>,
     pub flag: Option<Flag>,
     pub keep_connected: bool,
+    /// Why the server refused to let the player take place
+    pub refused: Option<String>,
 }
 
 impl JoinState {
@@ -42,6 +44,7 @@ impl JoinState {
             flag: Default::default(),
             // FIXME BS NOW: when switch on Join screen, must be updated with Preferences
             keep_connected: Default::default(),
+            refused: Default::default(),
         }
     }
 }
@@ -102,10 +105,15 @@ pub fn draw(ui: &mut Ui, state: &mut JoinState, mut commands: Commands) {
                         });
 
                     if ui.button("Join").clicked() {
+                        state.refused = None;
                         commands.trigger(TakePlaceEvent(state.flag.unwrap()));
                     }
                 });
             }
+
+            if let Some(refused) = &state.refused {
+                ui.colored_label(egui::Color32::RED, refused);
+            }
         }
     });
 }
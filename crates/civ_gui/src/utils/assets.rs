@@ -6,7 +6,7 @@ use common::{
         GameFrame,
     },
     geo::WorldPoint,
-    world::{CtxTile, TerrainType, Tile},
+    world::{CtxTile, Elevation, TerrainType, Tile, Vegetation},
 };
 use derive_more::Constructor;
 
@@ -20,11 +20,36 @@ use crate::{
 pub const TILE_Z: f32 = 0.0;
 pub const CITY_Z: f32 = 1.0;
 pub const UNIT_Z: f32 = 2.0;
+/// Tint of explored tiles which are not currently visible
+const EXPLORED_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
 
-fn terrain_type_index(terrain: &TerrainType) -> AtlasIndex {
+/// Atlas containing a tile sprite: terrain1.png, or terrain2.png for terrains it lacks
+enum TilesAtlas {
+    Tiles,
+    Terrains,
+}
+
+fn terrain_type_index(terrain: &TerrainType) -> (TilesAtlas, AtlasIndex) {
     match terrain {
-        TerrainType::GrassLand => atlas::TILE_GRASSLAND,
-        TerrainType::Plain => atlas::TILE_PLAIN,
+        TerrainType::GrassLand => (TilesAtlas::Tiles, atlas::TILE_GRASSLAND),
+        TerrainType::Plain => (TilesAtlas::Tiles, atlas::TILE_PLAIN),
+        TerrainType::Desert => (TilesAtlas::Tiles, atlas::TILE_DESERT),
+        TerrainType::Tundra => (TilesAtlas::Tiles, atlas::TILE_TUNDRA),
+        TerrainType::Coast => (TilesAtlas::Terrains, atlas::TILE_COAST),
+        TerrainType::Ocean => (TilesAtlas::Terrains, atlas::TILE_OCEAN),
+        TerrainType::Lake => (TilesAtlas::Terrains, atlas::TILE_LAKE),
+    }
+}
+
+/// Vegetation, then elevation, are drawn instead of terrain type
+fn tile_index(tile: &Tile) -> (TilesAtlas, AtlasIndex) {
+    match (tile.vegetation(), tile.elevation()) {
+        (Some(Vegetation::Forest), _) => (TilesAtlas::Terrains, atlas::TILE_FOREST),
+        (Some(Vegetation::Jungle), _) => (TilesAtlas::Tiles, atlas::TILE_JUNGLE),
+        (Some(Vegetation::Swamp), _) => (TilesAtlas::Tiles, atlas::TILE_SWAMP),
+        (None, Elevation::Mountains) => (TilesAtlas::Terrains, atlas::TILE_MOUNTAINS),
+        (None, Elevation::Hills) => (TilesAtlas::Terrains, atlas::TILE_HILLS),
+        (None, Elevation::Flat) => terrain_type_index(&tile.type_()),
     }
 }
 
@@ -126,19 +151,27 @@ impl IntoBundle for CtxTile<Tile> {
 
     fn bundle(&self, ctx: &DrawHexContext, z: f32) -> HexTileBundle {
         let point = ctx.point().iso(TILE_SIZE);
-        let atlas_index = match self {
-            CtxTile::Outside => atlas::TILE_BLACK,
-            CtxTile::Visible(tile) => terrain_type_index(&tile.type_()),
+        let ((tiles_atlas, atlas_index), color) = match self {
+            CtxTile::Outside | CtxTile::Unknown => {
+                ((TilesAtlas::Tiles, atlas::TILE_BLACK), Color::WHITE)
+            }
+            CtxTile::Explored(tile) => (tile_index(tile), EXPLORED_COLOR),
+            CtxTile::Visible(tile) => (tile_index(tile), Color::WHITE),
+        };
+        let (image, layout) = match tiles_atlas {
+            TilesAtlas::Tiles => (&ctx.atlases.tiles_texture, &ctx.atlases.tiles_atlas),
+            TilesAtlas::Terrains => (&ctx.atlases.terrains_texture, &ctx.atlases.terrains_atlas),
         };
 
         HexTileBundle::new(
             HexTile,
             Sprite {
-                image: ctx.atlases.tiles_texture.clone(),
+                image: image.clone(),
                 texture_atlas: Some(TextureAtlas {
                     index: *atlas_index,
-                    layout: ctx.atlases.tiles_atlas.clone(),
+                    layout: layout.clone(),
                 }),
+                color,
                 // anchor: Anchor::BottomCenter,
                 ..default()
             },
@@ -260,6 +293,8 @@ impl IntoBundle for ClientTask {
         let atlas_index = match self.type_() {
             ClientTaskType::Idle => todo!(),
             ClientTaskType::Settle(_) => atlas::ACTION_SETTLE,
+            ClientTaskType::Move(_) => atlas::ACTION_MOVE,
+            ClientTaskType::Attack(_) => atlas::ACTION_ATTACK,
         };
 
         ClientTaskBundle::new(
@@ -15,7 +15,7 @@ use civ_server::{
 use common::{
     network::{
         message::{ClientToServerMessage, ClientToServerNetworkMessage, ServerToClientMessage},
-        Client, ClientId,
+        Client, ClientId, PROTOCOL_VERSION,
     },
     rules::std1::Std1RuleSet,
     space::{window::Resolution, D2Size},
@@ -27,7 +27,11 @@ fn build_messages(count: usize) -> Vec<ClientToServerMessage> {
 
     for _ in 0..count {
         messages.push(ClientToServerMessage::Network(
-            ClientToServerNetworkMessage::Hello(Client::default(), Resolution::new(127, 128)),
+            ClientToServerNetworkMessage::Hello(
+                PROTOCOL_VERSION,
+                Client::default(),
+                Resolution::new(127, 128),
+            ),
         ))
     }
 
@@ -64,7 +68,7 @@ fn send_messages(
 ) {
     for message in messages {
         let client = match &message {
-            ClientToServerMessage::Network(ClientToServerNetworkMessage::Hello(client, _)) => {
+            ClientToServerMessage::Network(ClientToServerNetworkMessage::Hello(_, client, _)) => {
                 *client
             }
             _ => unreachable!(),
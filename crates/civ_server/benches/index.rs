@@ -49,7 +49,7 @@ fn inject_cities(index: &mut Index, cities: &Vec2d<Box<City>>, units: &Vec2d<Vec
         index.apply(
             &vec![Effect::State(StateEffect::City(
                 *city.id(),
-                CityEffect::New(*city.clone()),
+                CityEffect::New(city.clone()),
             ))],
             cities,
             units,
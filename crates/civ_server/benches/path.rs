@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use civ_server::{game::path::unit_path, state::State, world::reader::WorldReader};
+use common::{
+    game::{nation::flag::Flag, unit::UnitType},
+    geo::WorldPoint,
+    path::PathFinder,
+    rules::{std1::Std1RuleSet, RuleSetBox},
+    space::D2Size,
+    world::{TerrainType, Tile},
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn bench_unit_path(c: &mut Criterion) {
+    let rules: RuleSetBox = Box::new(Std1RuleSet);
+    let world = WorldReader::new(
+        PathBuf::new(),
+        1000,
+        1000,
+        vec![Tile::new(TerrainType::GrassLand); 1_000_000],
+    );
+    let state = State::empty(D2Size::new(1000, 1000));
+    let from = WorldPoint::new(450, 450);
+    let to = WorldPoint::new(500, 480);
+
+    c.bench_function("unit_path_1000x1000", |b| {
+        b.iter(|| {
+            unit_path(
+                black_box(&rules),
+                black_box(&world),
+                black_box(&state),
+                black_box(&Flag::Abkhazia),
+                black_box(&UnitType::Warriors),
+                black_box(&from),
+                black_box(&to),
+            )
+        })
+    });
+}
+
+fn bench_path_around_wall(c: &mut Criterion) {
+    // Virtual 10k x 10k world with a wall to bypass (tiles are computed, not stored)
+    let finder = PathFinder::builder().width(10_000).height(10_000).build();
+    let from = WorldPoint::new(5_000, 5_000);
+    let to = WorldPoint::new(5_040, 5_000);
+    let cost = |_: &WorldPoint, point: &WorldPoint| {
+        if point.x == 5_020 && point.y > 4_975 && point.y < 5_025 {
+            None
+        } else {
+            Some(1)
+        }
+    };
+
+    c.bench_function("path_around_wall_10000x10000", |b| {
+        b.iter(|| finder.find(black_box(&from), black_box(&to), cost))
+    });
+}
+
+criterion_group!(benches, bench_unit_path, bench_path_around_wall);
+criterion_main!(benches);
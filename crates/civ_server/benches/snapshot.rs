@@ -1,5 +1,3 @@
-use std::path::PathBuf;
-
 use civ_server::{
     snapshot::Snapshot,
     state::{clients::Clients, State},
@@ -36,7 +34,7 @@ fn snapshot_state(state: &State) -> Snapshot {
 }
 
 fn dump_state(snapshot: &Snapshot) {
-    snapshot.dump(&PathBuf::from("/dev/null")).unwrap();
+    snapshot.encode(0).unwrap();
 }
 
 pub fn bench_dump_snapshot(c: &mut Criterion) {
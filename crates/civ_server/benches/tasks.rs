@@ -11,7 +11,7 @@ use civ_server::{
     runner::{worker::setup_task_workers, Runner, RunnerContext},
     state::State,
     task::{TaskContext, TaskId},
-    test::task::{fibonacci, FibonacciTask},
+    test::task::{fibonacci, FibonacciTask, IdleTask},
     world::reader::WorldReader,
 };
 use common::{
@@ -26,8 +26,23 @@ use common::{
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 fn build_runner(tasks_count: usize, complexity: u64) -> Runner {
+    build_runner_with_idle(tasks_count, 0, complexity)
+}
+
+/// Runner with given fibonacci tasks and tasks doing nothing until far future (as most
+/// city tasks)
+fn build_runner_with_idle(tasks_count: usize, idle_count: usize, complexity: u64) -> Runner {
     let context = Context::new(Box::new(Std1RuleSet), ServerConfig::default());
     let mut state = State::empty(D2Size::new(1, 1));
+    for _ in 0..idle_count {
+        state.tasks_mut().push(Box::new(IdleTask::new(
+            TaskContext::builder()
+                .id(TaskId::default())
+                .start(GameFrame(0))
+                .end(GameFrame(1_000_000_000))
+                .build(),
+        )));
+    }
     for _ in 0..tasks_count {
         state.tasks_mut().push(Box::new(FibonacciTask::new(
             TaskContext::builder()
@@ -100,6 +115,20 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         )
     });
 
+    // runner_with_idle_tasks (must not depend on idle tasks count)
+    c.bench_function("runner_with_idle_tasks 8🎯 100k💤 1🧠 1k➰", |b| {
+        b.iter_with_setup(
+            || build_runner_with_idle(8, 100_000, 1),
+            |mut runner| run(black_box(&mut runner), black_box(8), black_box(1_000)),
+        )
+    });
+    c.bench_function("runner_with_idle_tasks 8🎯 1M💤 1🧠 1k➰", |b| {
+        b.iter_with_setup(
+            || build_runner_with_idle(8, 1_000_000, 1),
+            |mut runner| run(black_box(&mut runner), black_box(8), black_box(1_000)),
+        )
+    });
+
     c.bench_function("runner_with_fibonacci_tasks 8🎯 1M🧠 1k➰", |b| {
         b.iter_with_setup(
             || build_runner(8, 1_000_000),
@@ -0,0 +1,205 @@
+//! Inspect and edit game snapshots, while the server using them is stopped
+use std::{fs, path::PathBuf};
+
+use civ_server::{
+    journal::{journal_path, Journal},
+    snapshot::{
+        edit::{Edit, EditError},
+        rotation::Rotation,
+        summary::Summary,
+        Snapshot, SnapshotError,
+    },
+    state::State,
+    world::reader::{WorldReader, WorldReaderError},
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use common::{
+    game::{city::CityId, nation::flag::Flag, unit::UnitType, PlayerId},
+    geo::WorldPoint,
+    rules::{
+        file::{FileRuleSet, FileRuleSetError},
+        std1::Std1RuleSet,
+        RuleSetBox,
+    },
+};
+use log::{info, warn};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Snapshot path the server is started with: its newest snapshot is opened and its
+    /// journal replayed
+    snapshot: PathBuf,
+    /// Ruleset (RON) file the game is played with, instead of the built-in std1 ruleset
+    #[arg(long)]
+    rules: Option<PathBuf>,
+    /// Count of snapshots to keep (as the server one), older ones are removed
+    #[arg(long, default_value = "3")]
+    snapshot_keep: usize,
+    #[clap(subcommand)]
+    subcommand: SubCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum SubCommand {
+    /// Print frame, players, flags, city and unit counts and tasks by type
+    Stats,
+    /// Print (or write to given file) the whole snapshot content
+    Export {
+        #[arg(long, short, value_enum, default_value = "json")]
+        format: Format,
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Give a new unit to the player playing given flag
+    GiveUnit {
+        flag: Flag,
+        #[arg(value_name = "TYPE")]
+        type_: UnitType,
+        x: u64,
+        y: u64,
+        /// Write edited snapshot there instead of as the newest one of the game
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Move a city (and its exploited tiles, when they can still be worked)
+    MoveCity {
+        id: Uuid,
+        x: u64,
+        y: u64,
+        /// World path the server is started with, to choose the tiles worked by the city
+        #[arg(long)]
+        world: PathBuf,
+        /// Write edited snapshot there instead of as the newest one of the game
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Remove a player, with its cities, units and tasks
+    RemovePlayer {
+        id: Uuid,
+        /// Write edited snapshot there instead of as the newest one of the game
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Ron,
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("Snapshot error: {0}")]
+    Snapshot(#[from] SnapshotError),
+    #[error("No snapshot found for {0}")]
+    NoSnapshot(PathBuf),
+    #[error("Edit error: {0}")]
+    Edit(#[from] EditError),
+    #[error("Ruleset error: {0}")]
+    Rules(#[from] FileRuleSetError),
+    #[error("Export error: {0}")]
+    Export(String),
+    #[error("World error: {0}")]
+    World(#[from] WorldReaderError),
+}
+
+fn main() -> Result<(), Error> {
+    let env = env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, "info");
+    let args = Args::parse();
+    env_logger::init_from_env(env);
+
+    let rules: RuleSetBox = match &args.rules {
+        Some(path) => Box::new(FileRuleSet::from_path(path)?),
+        None => Box::new(Std1RuleSet),
+    };
+    let rotation = Rotation::new(args.snapshot.clone(), args.snapshot_keep);
+    let Some((header, snapshot)) = rotation.load()? else {
+        return Err(Error::NoSnapshot(args.snapshot));
+    };
+
+    // Open the game as the server would restart it: with its journal replayed
+    let journal_path = journal_path(&args.snapshot);
+    let mut journal = Journal::read(&journal_path)?;
+    let sequence = snapshot.journal_sequence();
+    if !journal.follows(sequence) {
+        warn!("Journal doesn't follow the loaded snapshot, ignore it");
+        journal = Journal::default();
+    }
+    let mut state = State::from(snapshot);
+    if header.is_none() {
+        state.see_around(&rules);
+    }
+    let sequence = journal.replay(&mut state, &rules, sequence);
+    let mut snapshot = Snapshot::from(&state);
+    // Replayed entries are in the snapshot: the journal restarts after them
+    snapshot.set_journal_sequence(sequence);
+
+    let (edit, output, world) = match args.subcommand {
+        SubCommand::Stats => {
+            print!("{}", Summary::from(&snapshot));
+            return Ok(());
+        }
+        SubCommand::Export { format, output } => {
+            let content = match format {
+                Format::Json => serde_json::to_string_pretty(&snapshot)
+                    .map_err(|e| Error::Export(e.to_string()))?,
+                Format::Ron => ron::ser::to_string_pretty(&snapshot, Default::default())
+                    .map_err(|e| Error::Export(e.to_string()))?,
+            };
+            match output {
+                Some(output) => {
+                    fs::write(output, content).map_err(|e| Error::Export(e.to_string()))?
+                }
+                None => println!("{}", content),
+            };
+            return Ok(());
+        }
+        SubCommand::GiveUnit {
+            flag,
+            type_,
+            x,
+            y,
+            output,
+        } => (
+            Edit::GiveUnit(flag, type_, WorldPoint::new(x, y)),
+            output,
+            None,
+        ),
+        SubCommand::MoveCity {
+            id,
+            x,
+            y,
+            world,
+            output,
+        } => (
+            Edit::MoveCity(CityId(id), WorldPoint::new(x, y)),
+            output,
+            Some(WorldReader::from(world, &None)?),
+        ),
+        SubCommand::RemovePlayer { id, output } => (Edit::RemovePlayer(PlayerId(id)), output, None),
+    };
+
+    edit.apply(&mut state, &rules, world.as_ref())?;
+    let mut snapshot = Snapshot::from(&state);
+    snapshot.set_journal_sequence(sequence);
+
+    // Keep the rules the game was played with
+    let fingerprint = header
+        .map(|header| header.rules())
+        .unwrap_or_else(|| rules.file().fingerprint());
+    match output {
+        Some(output) => snapshot.dump(&output, fingerprint)?,
+        None => {
+            let path = rotation.save(&snapshot, fingerprint)?;
+            info!("Snapshot to {}", path.display());
+            // Once the snapshot is written, as replayed entries would be skipped anyway
+            fs::write(&journal_path, []).map_err(|e| SnapshotError::Io(e.kind()))?;
+        }
+    }
+
+    Ok(())
+}
@@ -10,14 +10,20 @@ pub struct Clients {
 
 impl Clients {
     pub fn insert(&mut self, client: Client, endpoint: Endpoint) {
-        self.endpoints.insert(*client.client_id(), endpoint);
+        // Client can reconnect before its previous connection is detected as closed
+        if let Some(previous) = self.endpoints.insert(*client.client_id(), endpoint) {
+            if previous != endpoint {
+                self.clients.remove(&previous);
+            }
+        }
         self.clients.insert(endpoint, client);
     }
 
-    pub fn remove(&mut self, endpoint: &Endpoint) {
-        if let Some(client) = self.clients.remove(endpoint) {
-            self.endpoints.remove(client.client_id());
-        }
+    /// Forget given endpoint, returning the client it was connected to
+    pub fn remove(&mut self, endpoint: &Endpoint) -> Option<Client> {
+        let client = self.clients.remove(endpoint)?;
+        self.endpoints.remove(client.client_id());
+        Some(client)
     }
 
     pub fn client_for_endpoint(&self, endpoint: &Endpoint) -> Option<&Client> {
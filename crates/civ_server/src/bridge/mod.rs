@@ -16,6 +16,8 @@ pub mod network;
 
 const SEND_INTERVAL: Duration = Duration::from_millis(25);
 const CHECK_STOP_INTERVAL: Duration = Duration::from_millis(250);
+/// Delay before disconnecting a refused client, so it receives why it is refused
+const REFUSED_REMOVE_DELAY: Duration = Duration::from_millis(500);
 
 pub type FromClientsChannels = (
     Sender<(Client, ClientToServerMessage)>,
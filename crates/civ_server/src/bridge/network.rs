@@ -2,16 +2,17 @@ use super::clients::Clients;
 use super::{Bridge, BridgeBuildError, BridgeBuilder, FromClientsChannels, ToClientsChannels};
 use async_std::channel::{unbounded, Receiver, Sender};
 use common::network::message::{
-    ClientToServerMessage, ClientToServerNetworkMessage, ServerToClientMessage,
+    hello_version, ClientToServerMessage, ClientToServerNetworkMessage, HelloRefusedReason,
+    ServerToClientEstablishmentMessage, ServerToClientMessage,
 };
-use common::network::{Client, ClientId};
-use log::{debug, info};
-use message_io::network::{NetEvent, Transport};
-use message_io::node::{self};
+use common::network::{Client, ClientId, ProtocolVersion, PROTOCOL_VERSION};
+use log::{debug, error, info, warn};
+use message_io::network::{Endpoint, NetEvent, Transport};
+use message_io::node::{self, NodeHandler};
 use std::io;
 use std::sync::{Arc, RwLock};
 
-use crate::bridge::{CHECK_STOP_INTERVAL, SEND_INTERVAL};
+use crate::bridge::{CHECK_STOP_INTERVAL, REFUSED_REMOVE_DELAY, SEND_INTERVAL};
 use crate::config::ServerConfig;
 use crate::context::Context;
 use crate::state::State;
@@ -51,6 +52,8 @@ impl BridgeBuilder<NetworkBridge> for NetworkBridgeBuilder {
 enum Signal {
     SendServerToClientsMessages,
     CheckStopRequired,
+    /// Disconnect given endpoint
+    Remove(Endpoint),
 }
 
 pub struct NetworkBridge {
@@ -84,6 +87,18 @@ impl NetworkBridge {
             clients: Clients::default(),
         })
     }
+
+    /// Forget given endpoint and inform runner its client is gone
+    fn forget(&mut self, endpoint: &Endpoint) {
+        if let Some(client) = self.clients.remove(endpoint) {
+            if let Err(error) = self.from_clients_sender.send_blocking((
+                client,
+                ClientToServerMessage::Network(ClientToServerNetworkMessage::Goodbye),
+            )) {
+                error!("Can't inform runner of client goodbye: {}", error);
+            }
+        }
+    }
 }
 
 impl Bridge for NetworkBridge {
@@ -113,10 +128,29 @@ impl Bridge for NetworkBridge {
                 NetEvent::Connected(_, _) => unreachable!(), // There is no connect() calls.
                 NetEvent::Accepted(_, _) => {}
                 NetEvent::Message(endpoint, input_data) => {
-                    let message: ClientToServerMessage = bincode::deserialize(input_data).unwrap();
+                    let message: ClientToServerMessage = match bincode::deserialize(input_data) {
+                        Ok(message) => message,
+                        Err(error) => {
+                            warn!("Disconnect client sending unreadable message: {}", error);
+                            self.forget(&endpoint);
+                            // Probably a client of another protocol version
+                            match hello_version(input_data) {
+                                Some(version) => refuse_hello(&handler, endpoint, version),
+                                None => {
+                                    handler.network().remove(endpoint.resource_id());
+                                }
+                            }
+                            return;
+                        }
+                    };
                     match &message {
                         ClientToServerMessage::Network(message_) => match &message_ {
-                            ClientToServerNetworkMessage::Hello(client, _) => {
+                            ClientToServerNetworkMessage::Hello(version, _, _)
+                                if version != &PROTOCOL_VERSION =>
+                            {
+                                refuse_hello(&handler, endpoint, *version);
+                            }
+                            ClientToServerNetworkMessage::Hello(_, client, _) => {
                                 debug!(
                                     "Client hello ({}, {})",
                                     client.client_id(),
@@ -129,11 +163,15 @@ impl Bridge for NetworkBridge {
                             }
                             ClientToServerNetworkMessage::Goodbye => {
                                 debug!("Client goodbye");
-                                self.clients.remove(&endpoint);
+                                self.forget(&endpoint);
                             }
                         },
                         ClientToServerMessage::Game(_message) => {
-                            let client = self.clients.client_for_endpoint(&endpoint).unwrap();
+                            let Some(client) = self.clients.client_for_endpoint(&endpoint) else {
+                                warn!("Disconnect client sending game message before hello");
+                                handler.network().remove(endpoint.resource_id());
+                                return;
+                            };
                             self.from_clients_sender
                                 .send_blocking((*client, message.clone()))
                                 .unwrap();
@@ -142,7 +180,7 @@ impl Bridge for NetworkBridge {
                 }
                 NetEvent::Disconnected(endpoint) => {
                     debug!("Client disconnected");
-                    self.clients.remove(&endpoint);
+                    self.forget(&endpoint);
                 }
             },
             node::NodeEvent::Signal(signal) => {
@@ -162,10 +200,12 @@ impl Bridge for NetworkBridge {
                         if self.context.stop_is_required() {
                             handler.stop();
                         }
-                        handler.signals().send_with_timer(
-                            Signal::SendServerToClientsMessages,
-                            CHECK_STOP_INTERVAL,
-                        );
+                        handler
+                            .signals()
+                            .send_with_timer(Signal::CheckStopRequired, CHECK_STOP_INTERVAL);
+                    }
+                    Signal::Remove(endpoint) => {
+                        handler.network().remove(endpoint.resource_id());
                     }
                 };
             }
@@ -174,3 +214,169 @@ impl Bridge for NetworkBridge {
         info!("Network server finished running");
     }
 }
+
+/// Inform client its protocol version is not supported, then disconnect it (once the
+/// message had time to be sent)
+fn refuse_hello(handler: &NodeHandler<Signal>, endpoint: Endpoint, version: ProtocolVersion) {
+    info!(
+        "Refuse client of protocol version {} (server is {})",
+        version, PROTOCOL_VERSION
+    );
+    let message =
+        ServerToClientMessage::Establishment(ServerToClientEstablishmentMessage::HelloRefused(
+            HelloRefusedReason::IncompatibleProtocol(PROTOCOL_VERSION, version),
+        ));
+    let data = bincode::serialize(&message).unwrap();
+    handler.network().send(endpoint, &data);
+    handler
+        .signals()
+        .send_with_timer(Signal::Remove(endpoint), REFUSED_REMOVE_DELAY);
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, net::TcpListener, sync::mpsc, thread, time::Duration};
+
+    use common::{
+        game::GameFrame,
+        network::message::ClientToServerNetworkMessage,
+        rules::std1::Std1RuleSet,
+        space::{window::Resolution, D2Size},
+    };
+
+    use crate::{start, Args};
+
+    use super::*;
+
+    /// Address with a free port
+    fn free_address() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_refused_client_receives_why_before_disconnection() {
+        // GIVEN
+        let address = free_address();
+        let config = ServerConfig::new(None, GameFrame(0), 1, address.clone(), free_address());
+        let context = Context::new(Box::new(Std1RuleSet), config.clone());
+        let state = Arc::new(RwLock::new(State::empty(D2Size::new(1, 1))));
+        let (mut bridge, _from_clients, _to_clients) = NetworkBridgeBuilder
+            .build(context.clone(), state, &config)
+            .unwrap();
+        thread::spawn(move || bridge.run());
+        thread::sleep(CHECK_STOP_INTERVAL);
+        let (sender, receiver) = mpsc::channel();
+
+        // WHEN
+        let (handler, listener) = node::split::<()>();
+        handler
+            .network()
+            .connect(Transport::FramedTcp, address)
+            .unwrap();
+        thread::spawn(move || {
+            listener.for_each(move |event| {
+                if let node::NodeEvent::Network(event) = event {
+                    match event {
+                        NetEvent::Connected(endpoint, _) => {
+                            let hello = ClientToServerMessage::Network(
+                                ClientToServerNetworkMessage::Hello(
+                                    ProtocolVersion(PROTOCOL_VERSION.0 + 1),
+                                    Client::default(),
+                                    Resolution::new(1, 1),
+                                ),
+                            );
+                            let data = bincode::serialize(&hello).unwrap();
+                            handler.network().send(endpoint, &data);
+                        }
+                        NetEvent::Message(_, data) => {
+                            let message: ServerToClientMessage =
+                                bincode::deserialize(data).unwrap();
+                            sender.send(Some(message)).unwrap();
+                        }
+                        NetEvent::Disconnected(_) => {
+                            sender.send(None).unwrap();
+                            handler.stop();
+                        }
+                        NetEvent::Accepted(_, _) => {}
+                    }
+                }
+            })
+        });
+
+        // THEN
+        let timeout = Duration::from_secs(10);
+        assert_eq!(
+            receiver.recv_timeout(timeout),
+            Ok(Some(ServerToClientMessage::Establishment(
+                ServerToClientEstablishmentMessage::HelloRefused(
+                    HelloRefusedReason::IncompatibleProtocol(
+                        PROTOCOL_VERSION,
+                        ProtocolVersion(PROTOCOL_VERSION.0 + 1)
+                    )
+                )
+            )))
+        );
+        assert_eq!(receiver.recv_timeout(timeout), Ok(None));
+        context.require_stop();
+    }
+
+    /// Network bridge of a server which is required to stop once running
+    struct StoppingBridgeBuilder;
+
+    impl BridgeBuilder<NetworkBridge> for StoppingBridgeBuilder {
+        fn build(
+            &self,
+            context: Context,
+            state: Arc<RwLock<State>>,
+            config: &ServerConfig,
+        ) -> Result<
+            (
+                NetworkBridge,
+                Receiver<(Client, ClientToServerMessage)>,
+                Sender<(ClientId, ServerToClientMessage)>,
+            ),
+            BridgeBuildError,
+        > {
+            let context_ = context.clone();
+            thread::spawn(move || {
+                // After some stop checks, like an interrupt signal would do
+                thread::sleep(CHECK_STOP_INTERVAL * 3);
+                context_.require_stop();
+            });
+            NetworkBridgeBuilder.build(context, state, config)
+        }
+    }
+
+    #[test]
+    fn test_start_returns_when_stop_required() {
+        // GIVEN
+        let dir = std::env::temp_dir().join(format!("civ_network_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("world.ron"),
+            "(chunk_size: 1, width: 0, height: 0, seed: 0)",
+        )
+        .unwrap();
+        let args = Args::builder()
+            .world(dir.clone())
+            .snapshot_interval(1000)
+            .tcp_listen_address("127.0.0.1:0".to_string())
+            .ws_listen_address("127.0.0.1:0".to_string())
+            .build();
+        let (sender, receiver) = mpsc::channel();
+
+        // WHEN
+        thread::spawn(move || {
+            let result = start()
+                .args(args)
+                .bridge_builder(&StoppingBridgeBuilder)
+                .call();
+            sender.send(result.is_ok()).unwrap();
+        });
+
+        // THEN
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(10)), Ok(true));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -7,6 +7,8 @@ use crate::Args;
 pub struct ServerConfig {
     snapshot: Option<PathBuf>,
     snapshot_interval: GameFrame,
+    /// Count of snapshots kept (older ones are removed)
+    snapshot_keep: usize,
     tcp_listen_address: String,
     ws_listen_address: String,
 }
@@ -16,6 +18,7 @@ impl Default for ServerConfig {
         Self {
             snapshot: Default::default(),
             snapshot_interval: GameFrame(120000),
+            snapshot_keep: 3,
             tcp_listen_address: "127.0.0.1:9876".to_string(),
             ws_listen_address: "127.0.0.1:9877".to_string(),
         }
@@ -26,12 +29,14 @@ impl ServerConfig {
     pub fn new(
         snapshot: Option<PathBuf>,
         snapshot_interval: GameFrame,
+        snapshot_keep: usize,
         tcp_listen_address: String,
         ws_listen_address: String,
     ) -> Self {
         Self {
             snapshot,
             snapshot_interval,
+            snapshot_keep,
             tcp_listen_address,
             ws_listen_address,
         }
@@ -45,6 +50,10 @@ impl ServerConfig {
         &self.snapshot_interval
     }
 
+    pub fn snapshot_keep(&self) -> usize {
+        self.snapshot_keep
+    }
+
     pub fn tcp_listen_address(&self) -> &str {
         &self.tcp_listen_address
     }
@@ -59,6 +68,7 @@ impl From<Args> for ServerConfig {
         Self {
             snapshot: value.snapshot,
             snapshot_interval: GameFrame(value.snapshot_interval),
+            snapshot_keep: value.snapshot_keep,
             tcp_listen_address: value.tcp_listen_address,
             ws_listen_address: value.ws_listen_address,
         }
@@ -1,14 +1,19 @@
-use common::game::city::CityId;
+use std::path::PathBuf;
+
+use common::game::city::{BuildingType, CityExploitation, CityId, CityPopulation, CityProduction};
 use common::game::nation::flag::Flag;
+use common::game::tech::TechType;
 use common::game::unit::UnitId;
 use common::game::PlayerId;
 use common::network::message::ServerToClientMessage;
 use common::network::{Client, ClientId};
 use common::space::window::Window;
+use serde::{Deserialize, Serialize};
 
+use crate::game::task::{growth::CityGrowthTask, production::CityProductionTask};
 use crate::game::{city::City, unit::Unit};
 
-use crate::task::{Concern, TaskBox, TaskId};
+use crate::task::{task_box_serde, task_boxes_serde, Concern, TaskBox, TaskId};
 
 #[derive(Debug, Clone)]
 pub enum Effect {
@@ -17,13 +22,16 @@ pub enum Effect {
     // FIXME BS NOW: not simply "SendToClients" ?
     /// Effect which only product reflects
     Shines(Vec<(ServerToClientMessage, Vec<ClientId>)>),
+    /// Full snapshot of the state (once effects applied) must be written to given path
+    Snapshot(PathBuf),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum StateEffect {
     IncrementGameFrame,
     Clients(ClientsEffect),
     Client(Client, ClientEffect),
+    Player(PlayerId, PlayerEffect),
     Tasks(TasksEffect),
     Task(TaskId, TaskEffect),
     City(CityId, CityEffect),
@@ -37,43 +45,67 @@ impl From<StateEffect> for Effect {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ClientsEffect {
-    // FIXME BS NOW: when disconnected, remove
     Insert(ClientId, PlayerId),
+    /// Client disconnected: its player state is kept for when it reconnects
+    Remove(ClientId),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PlayerEffect {
+    /// Tech chosen as the current research
+    SetResearching(TechType),
+    /// Science stored for the current research, which can make it known
+    StoreScience(u64),
+    SetTaxRate(u8),
+    AddGold(u64),
+    /// Gold spent, down to zero
+    RemoveGold(u64),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TaskEffect {
-    Push(TaskBox),
-    Finished(TaskBox),
+    Push(#[serde(with = "task_box_serde")] TaskBox),
+    Finished(#[serde(with = "task_box_serde")] TaskBox),
     Remove(TaskId, Concern),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TasksEffect {
     Remove(Vec<(TaskId, Concern)>),
-    Add(Vec<TaskBox>),
+    Add(#[serde(with = "task_boxes_serde")] Vec<TaskBox>),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ClientEffect {
     PlayerTookPlace(Flag, Window),
     SetWindow(Window),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CityEffect {
-    New(City),
-    Replace(City),
-    Remove(City),
-}
-
-#[derive(Debug, Clone)]
+    New(Box<City>),
+    Replace(Box<City>),
+    Remove(Box<City>),
+    // Field changes, so several tasks can change the same city during a tick
+    SetPopulation(CityPopulation),
+    SetProduction(CityProduction),
+    SetExploitation(CityExploitation),
+    AddBuilding(BuildingType),
+    RemoveBuilding(BuildingType),
+    SetProductionTask(CityProductionTask),
+    SetGrowthTask(CityGrowthTask),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum UnitEffect {
     New(Unit),
     Replace(Unit),
     Remove(Unit),
+    /// Hit points lost, added once other effects of the tick are applied so several
+    /// combats can damage the same unit
+    Damage(u64),
 }
 
 pub fn new_unit(unit: Unit) -> Effect {
@@ -88,12 +120,110 @@ pub fn remove_unit(unit: Unit) -> Effect {
     Effect::State(StateEffect::Unit(*unit.id(), UnitEffect::Remove(unit)))
 }
 
+pub fn damage_unit(unit_id: UnitId, damage: u64) -> Effect {
+    Effect::State(StateEffect::Unit(unit_id, UnitEffect::Damage(damage)))
+}
+
+/// Remove given unit, with its current task
+pub fn disband_unit(unit: Unit) -> Vec<Effect> {
+    let mut effects = vec![];
+    if let Some(task) = unit.task() {
+        effects.push(remove_task(task.clone().into()));
+    }
+    effects.push(remove_unit(unit));
+    effects
+}
+
+pub fn set_researching(player_id: PlayerId, tech: TechType) -> Effect {
+    Effect::State(StateEffect::Player(
+        player_id,
+        PlayerEffect::SetResearching(tech),
+    ))
+}
+
+pub fn store_science(player_id: PlayerId, science: u64) -> Effect {
+    Effect::State(StateEffect::Player(
+        player_id,
+        PlayerEffect::StoreScience(science),
+    ))
+}
+
+pub fn set_tax_rate(player_id: PlayerId, tax_rate: u8) -> Effect {
+    Effect::State(StateEffect::Player(
+        player_id,
+        PlayerEffect::SetTaxRate(tax_rate),
+    ))
+}
+
+pub fn add_gold(player_id: PlayerId, gold: u64) -> Effect {
+    Effect::State(StateEffect::Player(player_id, PlayerEffect::AddGold(gold)))
+}
+
+pub fn remove_gold(player_id: PlayerId, gold: u64) -> Effect {
+    Effect::State(StateEffect::Player(
+        player_id,
+        PlayerEffect::RemoveGold(gold),
+    ))
+}
+
 pub fn new_city(city: City) -> Effect {
-    Effect::State(StateEffect::City(*city.id(), CityEffect::New(city)))
+    Effect::State(StateEffect::City(
+        *city.id(),
+        CityEffect::New(Box::new(city)),
+    ))
 }
 
 pub fn replace_city(city: City) -> Effect {
-    Effect::State(StateEffect::City(*city.id(), CityEffect::Replace(city)))
+    Effect::State(StateEffect::City(
+        *city.id(),
+        CityEffect::Replace(Box::new(city)),
+    ))
+}
+
+pub fn set_city_population(city_id: CityId, population: CityPopulation) -> Effect {
+    Effect::State(StateEffect::City(
+        city_id,
+        CityEffect::SetPopulation(population),
+    ))
+}
+
+pub fn set_city_production(city_id: CityId, production: CityProduction) -> Effect {
+    Effect::State(StateEffect::City(
+        city_id,
+        CityEffect::SetProduction(production),
+    ))
+}
+
+pub fn set_city_exploitation(city_id: CityId, exploitation: CityExploitation) -> Effect {
+    Effect::State(StateEffect::City(
+        city_id,
+        CityEffect::SetExploitation(exploitation),
+    ))
+}
+
+pub fn add_city_building(city_id: CityId, building: BuildingType) -> Effect {
+    Effect::State(StateEffect::City(
+        city_id,
+        CityEffect::AddBuilding(building),
+    ))
+}
+
+pub fn remove_city_building(city_id: CityId, building: BuildingType) -> Effect {
+    Effect::State(StateEffect::City(
+        city_id,
+        CityEffect::RemoveBuilding(building),
+    ))
+}
+
+pub fn set_city_production_task(city_id: CityId, task: CityProductionTask) -> Effect {
+    Effect::State(StateEffect::City(
+        city_id,
+        CityEffect::SetProductionTask(task),
+    ))
+}
+
+pub fn set_city_growth_task(city_id: CityId, task: CityGrowthTask) -> Effect {
+    Effect::State(StateEffect::City(city_id, CityEffect::SetGrowthTask(task)))
 }
 
 pub fn add_tasks(tasks: Vec<TaskBox>) -> Effect {
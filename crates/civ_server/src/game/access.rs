@@ -19,15 +19,24 @@ impl<'a> Access<'a> {
     pub fn can(&self, flag: &Flag, message: &ClientToServerInGameMessage) -> bool {
         match message {
             ClientToServerInGameMessage::SetWindow(_) => true,
+            // Player research and treasury are the ones of the client flag
+            ClientToServerInGameMessage::SetResearch(_) => true,
+            ClientToServerInGameMessage::SetTaxRate(_) => true,
             ClientToServerInGameMessage::Unit(uuid, message) => match message {
                 ClientToServerUnitMessage::Settle(_) => self.unit_is_owned_by_client(uuid, flag),
+                ClientToServerUnitMessage::MoveTo(_) => self.unit_is_owned_by_client(uuid, flag),
+                ClientToServerUnitMessage::Attack(_) => self.unit_is_owned_by_client(uuid, flag),
                 ClientToServerUnitMessage::CancelCurrentTask => {
                     self.unit_is_owned_by_client(uuid, flag)
                 }
             },
             ClientToServerInGameMessage::City(uuid, message) => match message {
                 ClientToServerCityMessage::SetProduction(_)
-                | ClientToServerCityMessage::SetExploitation(_) => {
+                | ClientToServerCityMessage::PushProduct(_)
+                | ClientToServerCityMessage::RemoveProduct(_)
+                | ClientToServerCityMessage::MoveProduct(_, _)
+                | ClientToServerCityMessage::SetRepeat(_)
+                | ClientToServerCityMessage::SetWorkedTiles(_) => {
                     self.city_is_owned_by_client(uuid, flag)
                 }
             },
@@ -1,7 +1,7 @@
 use bon::Builder;
 use civ_derive::Geo;
 use common::game::{
-    city::{CityExploitation, CityId, CityProduction},
+    city::{BuildingType, CityExploitation, CityId, CityPopulation, CityProduction},
     nation::flag::Flag,
     slice::ClientCity,
 };
@@ -21,6 +21,11 @@ pub struct City {
     geo: GeoContext,
     production: CityProduction,
     exploitation: CityExploitation,
+    #[builder(default)]
+    population: CityPopulation,
+    /// Built improvements
+    #[builder(default)]
+    buildings: Vec<BuildingType>,
     tasks: CityTasks,
 }
 
@@ -37,10 +42,19 @@ impl City {
         &self.flag
     }
 
+    /// City is captured by given flag
+    pub fn set_flag(&mut self, flag: Flag) {
+        self.flag = flag;
+    }
+
     pub fn production(&self) -> &CityProduction {
         &self.production
     }
 
+    pub fn production_mut(&mut self) -> &mut CityProduction {
+        &mut self.production
+    }
+
     pub fn tasks(&self) -> &CityTasks {
         &self.tasks
     }
@@ -56,6 +70,32 @@ impl City {
     pub fn exploitation_mut(&mut self) -> &mut CityExploitation {
         &mut self.exploitation
     }
+
+    pub fn population(&self) -> &CityPopulation {
+        &self.population
+    }
+
+    pub fn set_population(&mut self, population: CityPopulation) {
+        self.population = population;
+    }
+
+    pub fn buildings(&self) -> &[BuildingType] {
+        &self.buildings
+    }
+
+    pub fn has_building(&self, building: &BuildingType) -> bool {
+        self.buildings.contains(building)
+    }
+
+    pub fn add_building(&mut self, building: BuildingType) {
+        if !self.has_building(&building) {
+            self.buildings.push(building);
+        }
+    }
+
+    pub fn remove_building(&mut self, building: &BuildingType) {
+        self.buildings.retain(|building_| building_ != building);
+    }
 }
 
 impl IntoClientModel<ClientCity> for City {
@@ -66,6 +106,8 @@ impl IntoClientModel<ClientCity> for City {
             .name(self.name.clone())
             .production(self.production.clone())
             .exploitation(self.exploitation.clone())
+            .population(self.population)
+            .buildings(self.buildings.clone())
             .tasks(self.tasks.clone().into())
             .flag(self.flag)
             .build()
@@ -2,6 +2,7 @@ use crate::state::State;
 
 pub mod access;
 pub mod city;
+pub mod path;
 pub mod placer;
 pub mod task;
 pub mod unit;
@@ -0,0 +1,81 @@
+use common::{
+    game::{nation::flag::Flag, unit::UnitType, GameFrame},
+    geo::{Direction, WorldPoint},
+    path::{PathError, PathFinder},
+    rules::RuleSetBox,
+    world::{TerrainType, Tile},
+};
+use strum::IntoEnumIterator;
+
+use crate::{state::State, world::reader::WorldReader};
+
+/// Cheapest path for given unit type of given flag, according to terrain and ruleset move
+/// durations. Returned path exclude `from`.
+pub fn unit_path(
+    rules: &RuleSetBox,
+    world: &WorldReader,
+    state: &State,
+    flag: &Flag,
+    unit_type: &UnitType,
+    from: &WorldPoint,
+    to: &WorldPoint,
+) -> Result<Vec<WorldPoint>, PathError> {
+    // Consider flat tiles without vegetation as the cheapest ones
+    let min_cost = TerrainType::iter()
+        .map(Tile::new)
+        .filter(|tile| rules.can_enter(unit_type, tile))
+        .map(|tile| rules.move_duration(unit_type, &tile).0)
+        .chain([rules.river_move_duration(unit_type).0])
+        .min()
+        .unwrap_or(0);
+
+    PathFinder::builder()
+        .width(world.width())
+        .height(world.height())
+        .min_cost(min_cost)
+        .build()
+        .find(from, to, |from, to| {
+            step_duration(rules, world, state, flag, unit_type, from, to).map(|duration| duration.0)
+        })
+}
+
+/// Duration for given unit type of given flag to step from a tile to its neighbor (cheaper
+/// along rivers). `None` if the unit can't enter this neighbor.
+pub fn step_duration(
+    rules: &RuleSetBox,
+    world: &WorldReader,
+    state: &State,
+    flag: &Flag,
+    unit_type: &UnitType,
+    from: &WorldPoint,
+    to: &WorldPoint,
+) -> Option<GameFrame> {
+    let tile = world
+        .tile(to.x, to.y)
+        .filter(|tile| rules.can_enter(unit_type, tile))
+        .filter(|_| !occupied(state, flag, to))?;
+    let along_river = Direction::between(from, to)
+        .zip(world.tile(from.x, from.y))
+        .is_some_and(|(direction, from_tile)| from_tile.rivers().contains(direction));
+
+    if along_river {
+        Some(rules.river_move_duration(unit_type))
+    } else {
+        Some(rules.move_duration(unit_type, tile))
+    }
+}
+
+/// If given tile (inside the world) holds a city or units of another flag. Such tile can only
+/// be entered by attacking it.
+pub fn occupied(state: &State, flag: &Flag, point: &WorldPoint) -> bool {
+    state
+        .cities()
+        .get_by_point(*point)
+        .as_ref()
+        .is_some_and(|city| city.flag() != flag)
+        || state
+            .units()
+            .get_by_point(*point)
+            .as_ref()
+            .is_some_and(|units| units.iter().any(|unit| unit.flag() != flag))
+}
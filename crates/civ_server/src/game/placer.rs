@@ -1,8 +1,8 @@
 use std::sync::RwLockReadGuard;
 
-use common::{geo::WorldPoint, rules::RuleSetBox};
+use common::{game::PlayerId, geo::WorldPoint, rules::RuleSetBox};
 use dyn_clone::DynClone;
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use thiserror::Error;
 
 use crate::state::State;
@@ -11,11 +11,13 @@ use crate::world::reader::WorldReader;
 pub type PlacerBox = Box<dyn for<'a> Placer<'a> + Sync + Send>;
 
 pub trait Placer<'a>: DynClone {
+    /// Startup point of given player
     fn startup(
         &self,
         rules: &'a RuleSetBox,
         state: &'a RwLockReadGuard<State>,
         world: &'a RwLockReadGuard<WorldReader>,
+        player_id: &'a PlayerId,
     ) -> Result<WorldPoint, PlacerError>;
 }
 dyn_clone::clone_trait_object!(Placer<'_>);
@@ -35,11 +37,19 @@ impl<'a> Placer<'a> for RandomPlacer {
         rules: &'a RuleSetBox,
         state: &'a RwLockReadGuard<State>,
         world: &'a RwLockReadGuard<WorldReader>,
+        player_id: &'a PlayerId,
     ) -> Result<WorldPoint, PlacerError> {
+        // Same world, game frame and player produce same placements (players placed during
+        // the same frame don't see each other settlers, so they must not share a seed)
+        let (high, low) = player_id.0.as_u64_pair();
+        let mut rng = StdRng::seed_from_u64(
+            world.seed() ^ state.frame().0.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ high ^ low,
+        );
+
         // TODO: something more smart than this
         for _ in 0..1000 {
-            let x = rand::rng().random_range(0..world.width());
-            let y = rand::rng().random_range(0..world.height());
+            let x = rng.random_range(0..world.width());
+            let y = rng.random_range(0..world.height());
             let point = WorldPoint::new(x, y);
 
             if let Some(tile) = world.tile(x, y) {
@@ -57,3 +67,41 @@ impl<'a> Placer<'a> for RandomPlacer {
         Err(PlacerError::NoPlaceFound)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use common::{rules::std1::Std1RuleSet, space::D2Size};
+    use uuid::Uuid;
+
+    use crate::test::context::build_context;
+
+    use super::*;
+
+    #[test]
+    fn test_startup_differs_by_player() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let context = build_context(rules.clone(), State::empty(D2Size::new(64, 64)));
+        let state = context.state();
+        let world = context.world.read().unwrap();
+        let player1 = PlayerId(Uuid::from_u128(1));
+        let player2 = PlayerId(Uuid::from_u128(2));
+
+        // WHEN
+        let point1 = RandomPlacer
+            .startup(&rules, &state, &world, &player1)
+            .unwrap();
+        let point2 = RandomPlacer
+            .startup(&rules, &state, &world, &player2)
+            .unwrap();
+
+        // THEN
+        assert_ne!(point1, point2);
+        assert_eq!(
+            RandomPlacer
+                .startup(&rules, &state, &world, &player1)
+                .unwrap(),
+            point1
+        );
+    }
+}
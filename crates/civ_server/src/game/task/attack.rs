@@ -0,0 +1,511 @@
+use std::sync::RwLockReadGuard;
+
+use bon::Builder;
+use common::{
+    game::{
+        nation::flag::Flag,
+        tasks::client::{attack::ClientAttack, ClientTaskType},
+        unit::{AttackTarget, TaskType, UnitTaskType},
+        GameFrame,
+    },
+    geo::{Geo, WorldPoint},
+    network::message::{ClientStateMessage, ServerToClientInGameMessage, ServerToClientMessage},
+    rules::RuleSetBox,
+    task::{CantAttackReason, CreateTaskError, GamePlayReason},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    context::Context,
+    effect::{self, Effect, StateEffect, UnitEffect},
+    game::{city::City, unit::Unit},
+    impl_boxed, impl_into_unit_task_wrapper, impl_with_context, impl_with_unit,
+    runner::RunnerContext,
+    state::{State, StateError},
+    task::{
+        unit::UnitTaskWrapper, Concern, Task, TaskBox, TaskContext, TaskError, TaskId, Then,
+        WithUnit,
+    },
+    world::reader::WorldReader,
+};
+
+/// Strength of veteran units, in percent of their type strength
+const VETERAN_STRENGTH: u64 = 150;
+
+/// One round of a combat against a neighbor unit or city. When finished, the round loser
+/// takes one damage (and dies when out of hit points), then a new [`Attack`] round is
+/// created while both sides stand. A city without defender left is captured.
+#[derive(Debug, Builder, Clone, Serialize, Deserialize)]
+pub struct Attack {
+    context: TaskContext,
+    unit: Box<Unit>,
+    target: AttackTarget,
+    /// Seed of this round outcome
+    seed: u64,
+}
+
+impl Attack {
+    pub fn new(
+        task_id: TaskId,
+        context: Context,
+        state: RwLockReadGuard<State>,
+        world: RwLockReadGuard<WorldReader>,
+        unit: Unit,
+        target: AttackTarget,
+    ) -> Result<Self, CreateTaskError> {
+        if context.rules().unit_attack(unit.type_()) == 0 {
+            return Err(cant_attack(CantAttackReason::WrongUnitType(*unit.type_())));
+        }
+
+        let (flag, point) = target_position(&state, &target)
+            .map_err(|_| cant_attack(CantAttackReason::TargetNoLongerExist))?;
+        if &flag == unit.flag() {
+            return Err(cant_attack(CantAttackReason::SameFlag));
+        }
+        if unit.geo().point().distance(&point) != 1 {
+            return Err(cant_attack(CantAttackReason::TooFar));
+        }
+
+        // Same world, game frame and attacker produce same combat
+        let seed = world.seed()
+            ^ state.frame().0.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            ^ unit.id().0.as_u64_pair().0;
+
+        Ok(Self::round(
+            task_id,
+            context.rules(),
+            *state.frame(),
+            unit,
+            target,
+            seed,
+        ))
+    }
+
+    fn round(
+        task_id: TaskId,
+        rules: &RuleSetBox,
+        frame: GameFrame,
+        unit: Unit,
+        target: AttackTarget,
+        seed: u64,
+    ) -> Self {
+        Self::builder()
+            .context(
+                TaskContext::builder()
+                    .id(task_id)
+                    .start(frame)
+                    .end(frame + rules.combat_round_duration().0)
+                    .build(),
+            )
+            .unit(Box::new(unit))
+            .target(target)
+            .seed(seed)
+            .build()
+    }
+
+    pub fn target(&self) -> &AttackTarget {
+        &self.target
+    }
+}
+
+impl_boxed!(Attack);
+impl_with_context!(Attack);
+impl_with_unit!(Attack, unit);
+impl_into_unit_task_wrapper!(Attack, UnitTaskWrapper::Attack);
+
+#[typetag::serde]
+impl Task for Attack {
+    fn type_(&self) -> TaskType {
+        TaskType::Unit(UnitTaskType::Attack)
+    }
+
+    fn concern(&self) -> Concern {
+        Concern::Unit(*self.unit.id())
+    }
+}
+
+impl Then for Attack {
+    fn then(&self, context: &RunnerContext) -> Result<(Vec<Effect>, Vec<TaskBox>), TaskError> {
+        let rules = context.context.rules();
+        let state = context.state();
+        // Unit may have been damaged by other combats since this round started
+        let mut unit = state.find_unit(self.unit.id())?.clone();
+        unit.set_task(None);
+
+        // Combat is over when target vanished, moved away or was captured meanwhile
+        let Ok((flag, point)) = target_position(&state, &self.target) else {
+            return Ok((vec![effect::replace_unit(unit)], vec![]));
+        };
+        if &flag == unit.flag() || unit.geo().point().distance(&point) != 1 {
+            return Ok((vec![effect::replace_unit(unit)], vec![]));
+        }
+
+        let city = state
+            .cities()
+            .get_by_point(point)
+            .as_deref()
+            .filter(|city| city.flag() == &flag);
+        let defender = match &self.target {
+            AttackTarget::Unit(unit_id) => state.find_unit(unit_id)?.clone(),
+            AttackTarget::City(city_id) => {
+                match best_defender(rules, &state, &flag, &point, city) {
+                    Some(defender) => defender,
+                    None => {
+                        // Last defender died: city is captured
+                        let city = state.find_city(city_id)?;
+                        return Ok((capture(&state, unit, city), vec![]));
+                    }
+                }
+            }
+        };
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let attacker_wins = round(
+            &mut rng,
+            attack_strength(rules, &unit),
+            defence_strength(rules, &defender, city),
+        );
+        let mut loser = if attacker_wins {
+            defender
+        } else {
+            unit.clone()
+        };
+        loser.set_damage(loser.damage() + 1);
+        // Other combats can damage the same units during the tick: out of hit points
+        // units die once all damages applied, see [`deaths`]
+        let mut effects = vec![effect::damage_unit(*loser.id(), 1)];
+
+        if !attacker_wins && !is_alive(rules, &loser) {
+            return Ok((effects, vec![]));
+        }
+
+        let mut tasks: Vec<TaskBox> = vec![];
+        // Other defenders may stand in the city, else it will be captured next round
+        let over = attacker_wins
+            && !is_alive(rules, &loser)
+            && matches!(self.target, AttackTarget::Unit(_));
+
+        if !over {
+            let task = Self::round(
+                TaskId::default(),
+                rules,
+                *state.frame(),
+                unit.clone(),
+                self.target,
+                rng.random(),
+            );
+            unit.set_task(Some(task.clone().into()));
+            tasks.push(Box::new(task));
+        }
+        effects.push(effect::replace_unit(unit));
+
+        Ok((effects, tasks))
+    }
+}
+
+impl From<Attack> for ClientTaskType {
+    fn from(value: Attack) -> Self {
+        ClientTaskType::Attack(ClientAttack::new(value.target))
+    }
+}
+
+fn cant_attack(reason: CantAttackReason) -> CreateTaskError {
+    CreateTaskError::GamePlay(GamePlayReason::CantAttack(reason))
+}
+
+fn target_position(state: &State, target: &AttackTarget) -> Result<(Flag, WorldPoint), StateError> {
+    Ok(match target {
+        AttackTarget::Unit(unit_id) => {
+            let unit = state.find_unit(unit_id)?;
+            (*unit.flag(), *unit.geo().point())
+        }
+        AttackTarget::City(city_id) => {
+            let city = state.find_city(city_id)?;
+            (*city.flag(), *city.geo().point())
+        }
+    })
+}
+
+/// Unit of given flag which best defends given tile
+fn best_defender(
+    rules: &RuleSetBox,
+    state: &State,
+    flag: &Flag,
+    point: &WorldPoint,
+    city: Option<&City>,
+) -> Option<Unit> {
+    state
+        .units()
+        .get_by_point(*point)
+        .as_ref()?
+        .iter()
+        .filter(|unit| unit.flag() == flag)
+        .max_by_key(|unit| {
+            (
+                defence_strength(rules, unit, city),
+                rules
+                    .unit_hit_points(unit.type_())
+                    .saturating_sub(unit.damage()),
+            )
+        })
+        .cloned()
+}
+
+fn veteran_strength(unit: &Unit) -> u64 {
+    if unit.veteran() {
+        VETERAN_STRENGTH
+    } else {
+        100
+    }
+}
+
+fn attack_strength(rules: &RuleSetBox, unit: &Unit) -> u64 {
+    rules.unit_attack(unit.type_()) * veteran_strength(unit)
+}
+
+/// Defence strength, with bonus of the city the unit stands in
+fn defence_strength(rules: &RuleSetBox, unit: &Unit, city: Option<&City>) -> u64 {
+    let city_defence = city
+        .map(|city| rules.city_defence(city.buildings()))
+        .unwrap_or(100);
+    rules.unit_defence(unit.type_()) * veteran_strength(unit) * city_defence / 100
+}
+
+/// Attacker wins the round with a probability of its part in total strength
+fn round(rng: &mut StdRng, attack: u64, defence: u64) -> bool {
+    if defence == 0 {
+        return true;
+    }
+    rng.random_range(0..attack + defence) < attack
+}
+
+fn is_alive(rules: &RuleSetBox, unit: &Unit) -> bool {
+    unit.damage() < rules.unit_hit_points(unit.type_())
+}
+
+/// Effects making units damaged by given (applied) effects die when out of hit points
+pub fn deaths(context: &RunnerContext, effects: &[Effect]) -> Vec<Effect> {
+    let rules = context.context.rules();
+    let state = context.state();
+    let mut unit_ids = vec![];
+    for effect in effects {
+        if let Effect::State(StateEffect::Unit(unit_id, UnitEffect::Damage(_))) = effect {
+            if !unit_ids.contains(unit_id) {
+                unit_ids.push(*unit_id);
+            }
+        }
+    }
+
+    unit_ids
+        .iter()
+        .filter_map(|unit_id| state.find_unit(unit_id).ok())
+        .filter(|unit| !is_alive(rules, unit))
+        .flat_map(|unit| effect::disband_unit(unit.clone()))
+        .collect()
+}
+
+/// City changes of flag and the attacker enters it
+fn capture(state: &State, mut unit: Unit, city: &City) -> Vec<Effect> {
+    let mut city = city.clone();
+    city.set_flag(*unit.flag());
+
+    // Clients which see the unit at its previous position must forget it
+    let previous = *unit.geo();
+    unit.geo_mut().set_point(*city.geo().point());
+
+    vec![
+        Effect::Shines(vec![(
+            ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
+                ClientStateMessage::RemoveUnit(*previous.point(), *unit.id()),
+            )),
+            state.clients().seeing(&previous, state.visibility()),
+        )]),
+        effect::replace_city(city),
+        effect::replace_unit(unit),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use common::{
+        game::unit::{UnitId, UnitType},
+        geo::GeoContext,
+        rules::std1::Std1RuleSet,
+        space::D2Size,
+    };
+    use rstest::rstest;
+
+    use crate::{game::unit::UnitCanBuilder, test::context::build_context};
+
+    use super::*;
+
+    fn warriors(flag: Flag, point: WorldPoint) -> Unit {
+        Unit::builder()
+            .id(UnitId::default())
+            .type_(UnitType::Warriors)
+            .geo(GeoContext::builder().point(point).build())
+            .flag(flag)
+            .can(UnitCanBuilder::new().build())
+            .build()
+    }
+
+    /// Seed of a first round won by the attacker (at equal strength)
+    fn winning_seed() -> u64 {
+        (0..)
+            .find(|seed| round(&mut StdRng::seed_from_u64(*seed), 100, 100))
+            .unwrap()
+    }
+
+    fn attack(rules: &RuleSetBox, unit: &Unit, target: AttackTarget) -> Attack {
+        Attack::round(
+            TaskId::default(),
+            rules,
+            GameFrame(0),
+            unit.clone(),
+            target,
+            winning_seed(),
+        )
+    }
+
+    #[rstest]
+    #[case(1, 1, 450, 550)]
+    #[case(3, 1, 700, 800)]
+    #[case(1, 3, 200, 300)]
+    #[case(1, 0, 1000, 1000)]
+    fn test_round(
+        #[case] attack: u64,
+        #[case] defence: u64,
+        #[case] min_wins: usize,
+        #[case] max_wins: usize,
+    ) {
+        // GIVEN
+        let mut rng = StdRng::seed_from_u64(42);
+
+        // WHEN
+        let wins = (0..1000)
+            .filter(|_| round(&mut rng, attack, defence))
+            .count();
+
+        // THEN
+        assert!((min_wins..=max_wins).contains(&wins), "{} wins", wins);
+    }
+
+    #[test]
+    fn test_round_is_seeded() {
+        // GIVEN
+        let outcomes = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..100).map(|_| round(&mut rng, 1, 1)).collect::<Vec<_>>()
+        };
+
+        // WHEN/THEN
+        assert_eq!(outcomes(7), outcomes(7));
+    }
+
+    #[test]
+    fn test_then_defender_dies_under_several_attacks() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let attacker1 = warriors(Flag::Abkhazia, WorldPoint::new(0, 1));
+        let attacker2 = warriors(Flag::Abkhazia, WorldPoint::new(2, 1));
+        let mut defender = warriors(Flag::Aborigines, WorldPoint::new(1, 1));
+        defender.set_damage(rules.unit_hit_points(defender.type_()) - 1);
+        let mut state = State::empty(D2Size::new(3, 3));
+        state.apply(
+            &vec![
+                effect::new_unit(attacker1.clone()),
+                effect::new_unit(attacker2.clone()),
+                effect::new_unit(defender.clone()),
+            ],
+            &rules,
+        );
+        let context = build_context(rules.clone(), state);
+        let target = AttackTarget::Unit(*defender.id());
+
+        // WHEN (both rounds end at the same frame)
+        let (mut effects, tasks1) = attack(&rules, &attacker1, target).then(&context).unwrap();
+        let (effects2, tasks2) = attack(&rules, &attacker2, target).then(&context).unwrap();
+        effects.extend(effects2);
+        context.state.write().unwrap().apply(&effects, &rules);
+        let deaths = deaths(&context, &effects);
+        context.state.write().unwrap().apply(&deaths, &rules);
+
+        // THEN
+        let state = context.state();
+        assert!(tasks1.is_empty() && tasks2.is_empty());
+        assert_eq!(state.units_count(), 2);
+        assert!(state.find_unit(defender.id()).is_err());
+        assert!(state.find_unit(attacker1.id()).is_ok());
+        assert!(state.find_unit(attacker2.id()).is_ok());
+    }
+
+    #[test]
+    fn test_then_defender_takes_damage_of_several_attacks() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let attacker1 = warriors(Flag::Abkhazia, WorldPoint::new(0, 1));
+        let attacker2 = warriors(Flag::Abkhazia, WorldPoint::new(2, 1));
+        let defender = warriors(Flag::Aborigines, WorldPoint::new(1, 1));
+        let mut state = State::empty(D2Size::new(3, 3));
+        state.apply(
+            &vec![
+                effect::new_unit(attacker1.clone()),
+                effect::new_unit(attacker2.clone()),
+                effect::new_unit(defender.clone()),
+            ],
+            &rules,
+        );
+        let context = build_context(rules.clone(), state);
+        let target = AttackTarget::Unit(*defender.id());
+
+        // WHEN (both rounds end at the same frame)
+        let (mut effects, tasks1) = attack(&rules, &attacker1, target).then(&context).unwrap();
+        let (effects2, tasks2) = attack(&rules, &attacker2, target).then(&context).unwrap();
+        effects.extend(effects2);
+        context.state.write().unwrap().apply(&effects, &rules);
+        let deaths = deaths(&context, &effects);
+
+        // THEN
+        let state = context.state();
+        assert_eq!(tasks1.len(), 1);
+        assert_eq!(tasks2.len(), 1);
+        assert!(deaths.is_empty());
+        assert_eq!(state.find_unit(defender.id()).unwrap().damage(), 2);
+        assert_eq!(state.find_unit(attacker1.id()).unwrap().damage(), 0);
+        assert_eq!(state.find_unit(attacker2.id()).unwrap().damage(), 0);
+    }
+
+    #[test]
+    fn test_then_city_without_defender_is_captured() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let attacker = warriors(Flag::Abkhazia, WorldPoint::new(0, 1));
+        let mut city = crate::test::city::build_city(1);
+        city.set_flag(Flag::Aborigines);
+        let mut state = State::empty(D2Size::new(3, 3));
+        state.apply(
+            &vec![
+                effect::new_unit(attacker.clone()),
+                effect::new_city(city.clone()),
+            ],
+            &rules,
+        );
+        let context = build_context(rules.clone(), state);
+
+        // WHEN
+        let (effects, tasks) = attack(&rules, &attacker, AttackTarget::City(*city.id()))
+            .then(&context)
+            .unwrap();
+        context.state.write().unwrap().apply(&effects, &rules);
+
+        // THEN
+        let state = context.state();
+        assert!(tasks.is_empty());
+        assert_eq!(state.find_city(city.id()).unwrap().flag(), &Flag::Abkhazia);
+        assert_eq!(
+            state.find_unit(attacker.id()).unwrap().geo().point(),
+            &WorldPoint::new(1, 1)
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use bon::Builder;
+use common::{
+    game::{
+        city::{BuildingType, CityId, CityPopulation},
+        unit::{CityTaskType, TaskType},
+        GameFrame,
+    },
+    rules::RuleSetBox,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    effect::{self, Effect},
+    impl_boxed, impl_with_context,
+    runner::RunnerContext,
+    task::{Concern, Task, TaskBox, TaskContext, TaskError, TaskId, Then},
+};
+
+/// Growth cycle of a city: when finished, city food surplus is stored, which can make
+/// the city grow (or starve)
+#[derive(Debug, Builder, Clone, Serialize, Deserialize)]
+pub struct CityGrowthTask {
+    context: TaskContext,
+    city: CityId,
+}
+
+impl_boxed!(CityGrowthTask);
+impl_with_context!(CityGrowthTask);
+
+#[typetag::serde]
+impl Task for CityGrowthTask {
+    fn type_(&self) -> TaskType {
+        TaskType::City(CityTaskType::Growth)
+    }
+
+    fn concern(&self) -> Concern {
+        Concern::City(self.city)
+    }
+}
+
+impl Then for CityGrowthTask {
+    fn then(&self, context: &RunnerContext) -> Result<(Vec<Effect>, Vec<TaskBox>), TaskError> {
+        let rules = context.context.rules();
+        let state = context.state();
+        let city = state.find_city(&self.city)?;
+
+        let eaten = city.population().size() * rules.citizen_food();
+        let surplus = city.exploitation().yield_().food as i64 - eaten as i64;
+        let population = next_population(rules, city.population(), city.buildings(), surplus);
+        let task = growth_task(rules, &self.context.end(), &self.city);
+
+        // Citizens come and go: worked tiles (and so production) are refreshed once all
+        // effects of the tick are applied
+        Ok((
+            vec![
+                effect::set_city_population(self.city, population),
+                effect::set_city_growth_task(self.city, task.clone()),
+            ],
+            vec![Box::new(task)],
+        ))
+    }
+}
+
+pub fn growth_task(rules: &RuleSetBox, start: &GameFrame, city_id: &CityId) -> CityGrowthTask {
+    CityGrowthTask::builder()
+        .context(
+            TaskContext::builder()
+                .id(TaskId::default())
+                .start(*start)
+                .end(*start + rules.growth_duration().0)
+                .build(),
+        )
+        .city(*city_id)
+        .build()
+}
+
+/// Population after storing given food surplus (negative when citizens starve)
+pub fn next_population(
+    rules: &RuleSetBox,
+    population: &CityPopulation,
+    buildings: &[BuildingType],
+    surplus: i64,
+) -> CityPopulation {
+    let food = population.food() as i64 + surplus;
+
+    if food < 0 {
+        // A city never starves under one citizen
+        return CityPopulation::new(population.size().saturating_sub(1).max(1), 0);
+    }
+
+    let required = rules.growth_food(population.size());
+    if food as u64 >= required {
+        let kept = rules.growth_kept_food(required, buildings);
+        return CityPopulation::new(population.size() + 1, food as u64 - required + kept);
+    }
+
+    CityPopulation::new(population.size(), food as u64)
+}
+
+#[cfg(test)]
+mod test {
+    use common::rules::std1::Std1RuleSet;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(CityPopulation::new(1, 0), vec![], 2, CityPopulation::new(1, 2))]
+    #[case(CityPopulation::new(1, 19), vec![], 2, CityPopulation::new(2, 1))]
+    #[case(CityPopulation::new(1, 19), vec![BuildingType::Granary], 2, CityPopulation::new(2, 11))]
+    #[case(CityPopulation::new(3, 5), vec![], -2, CityPopulation::new(3, 3))]
+    #[case(CityPopulation::new(3, 1), vec![], -2, CityPopulation::new(2, 0))]
+    #[case(CityPopulation::new(1, 0), vec![], -2, CityPopulation::new(1, 0))]
+    fn test_next_population(
+        #[case] population: CityPopulation,
+        #[case] buildings: Vec<BuildingType>,
+        #[case] surplus: i64,
+        #[case] expected: CityPopulation,
+    ) {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+
+        // WHEN
+        let population = next_population(&rules, &population, &buildings, surplus);
+
+        // THEN
+        assert_eq!(population, expected);
+    }
+}
@@ -1,2 +1,7 @@
+pub mod attack;
+pub mod growth;
+pub mod move_;
 pub mod production;
+pub mod research;
 pub mod settle;
+pub mod upkeep;
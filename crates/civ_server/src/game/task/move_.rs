@@ -0,0 +1,349 @@
+use std::sync::RwLockReadGuard;
+
+use bon::Builder;
+use common::{
+    game::{
+        tasks::client::{move_::ClientMove, ClientTaskType},
+        unit::{TaskType, UnitTaskType},
+    },
+    geo::{Geo, WorldPoint},
+    network::message::{ClientStateMessage, ServerToClientInGameMessage, ServerToClientMessage},
+    path::PathError,
+    rules::RuleSetBox,
+    task::{CantMoveReason, CreateTaskError, GamePlayReason},
+};
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    context::Context,
+    effect::{self, Effect},
+    game::{
+        path::{occupied, step_duration, unit_path},
+        unit::Unit,
+    },
+    impl_boxed, impl_into_unit_task_wrapper, impl_with_context, impl_with_unit,
+    runner::RunnerContext,
+    state::State,
+    task::{
+        unit::UnitTaskWrapper, Concern, Task, TaskBox, TaskContext, TaskError, TaskId, Then,
+        WithUnit,
+    },
+    world::reader::WorldReader,
+};
+
+/// Move the unit by one tile along its path. When finished, unit is placed on the
+/// next tile and a new [`Move`] task is created with remaining path.
+#[derive(Debug, Builder, Clone, Serialize, Deserialize)]
+pub struct Move {
+    context: TaskContext,
+    unit: Box<Unit>,
+    /// Remaining tiles to enter, first one is entered during this task
+    path: Vec<WorldPoint>,
+}
+
+impl Move {
+    pub fn new(
+        task_id: TaskId,
+        context: Context,
+        state: RwLockReadGuard<State>,
+        world: RwLockReadGuard<WorldReader>,
+        unit: Unit,
+        destination: WorldPoint,
+    ) -> Result<Self, CreateTaskError> {
+        if !context.rules().can_move(unit.type_()) {
+            return Err(CreateTaskError::GamePlay(GamePlayReason::CantMove(
+                CantMoveReason::WrongUnitType(*unit.type_()),
+            )));
+        }
+
+        if destination.x >= world.width() || destination.y >= world.height() {
+            return Err(CreateTaskError::GamePlay(GamePlayReason::CantMove(
+                CantMoveReason::OutsideWorld,
+            )));
+        }
+
+        if unit.geo().point() == &destination {
+            return Err(CreateTaskError::GamePlay(GamePlayReason::CantMove(
+                CantMoveReason::AlreadyThere,
+            )));
+        }
+
+        if occupied(&state, unit.flag(), &destination) {
+            return Err(CreateTaskError::GamePlay(GamePlayReason::CantMove(
+                CantMoveReason::Occupied,
+            )));
+        }
+
+        let path = unit_path(
+            context.rules(),
+            &world,
+            &state,
+            unit.flag(),
+            unit.type_(),
+            unit.geo().point(),
+            &destination,
+        )
+        .map_err(|e| CreateTaskError::GamePlay(GamePlayReason::CantMove(e.into())))?;
+
+        Self::step(task_id, context.rules(), &state, &world, unit, path)
+    }
+
+    /// Build the task moving the unit on the first tile of given path
+    fn step(
+        task_id: TaskId,
+        rules: &RuleSetBox,
+        state: &State,
+        world: &WorldReader,
+        unit: Unit,
+        path: Vec<WorldPoint>,
+    ) -> Result<Self, CreateTaskError> {
+        let next = path
+            .first()
+            .ok_or(CreateTaskError::GamePlay(GamePlayReason::CantMove(
+                CantMoveReason::AlreadyThere,
+            )))?;
+        if world.tile(next.x, next.y).is_none() {
+            return Err(CreateTaskError::GamePlay(GamePlayReason::CantMove(
+                CantMoveReason::OutsideWorld,
+            )));
+        }
+        if occupied(state, unit.flag(), next) {
+            return Err(CreateTaskError::GamePlay(GamePlayReason::CantMove(
+                CantMoveReason::Occupied,
+            )));
+        }
+        let frame = *state.frame();
+        let duration = step_duration(
+            rules,
+            world,
+            state,
+            unit.flag(),
+            unit.type_(),
+            unit.geo().point(),
+            next,
+        )
+        .ok_or(CreateTaskError::GamePlay(GamePlayReason::CantMove(
+            CantMoveReason::Path(PathError::NotFound),
+        )))?;
+
+        Ok(Self::builder()
+            .context(
+                TaskContext::builder()
+                    .id(task_id)
+                    .start(frame)
+                    .end(frame + duration.0)
+                    .build(),
+            )
+            .unit(Box::new(unit))
+            .path(path)
+            .build())
+    }
+
+    pub fn destination(&self) -> Option<&WorldPoint> {
+        self.path.last()
+    }
+}
+
+impl_boxed!(Move);
+impl_with_context!(Move);
+impl_with_unit!(Move, unit);
+impl_into_unit_task_wrapper!(Move, UnitTaskWrapper::Move);
+
+#[typetag::serde]
+impl Task for Move {
+    fn type_(&self) -> TaskType {
+        TaskType::Unit(UnitTaskType::Move)
+    }
+
+    fn concern(&self) -> Concern {
+        Concern::Unit(*self.unit.id())
+    }
+}
+
+impl Then for Move {
+    fn then(&self, context: &RunnerContext) -> Result<(Vec<Effect>, Vec<TaskBox>), TaskError> {
+        let state = context.state();
+        // Unit may have been damaged by combats since this step started
+        let mut unit = state.find_unit(self.unit.id())?.clone();
+        let mut tasks: Vec<TaskBox> = vec![];
+
+        // Tile can have been occupied by another player since this step started
+        if let Some(next) = self
+            .path
+            .first()
+            .filter(|next| occupied(&state, unit.flag(), next))
+        {
+            debug!(
+                "Unit {} stop moving: {} ({:?})",
+                unit.id(),
+                CantMoveReason::Occupied,
+                next
+            );
+            unit.set_task(None);
+            return Ok((vec![effect::replace_unit(unit)], tasks));
+        }
+
+        // Clients which see the unit at its previous position must forget it
+        let previous = *unit.geo();
+        let mut effects = vec![Effect::Shines(vec![(
+            ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
+                ClientStateMessage::RemoveUnit(*previous.point(), *unit.id()),
+            )),
+            state.clients().seeing(&previous, state.visibility()),
+        )])];
+
+        if let Some(next) = self.path.first() {
+            unit.geo_mut().set_point(*next);
+        }
+        unit.set_task(None);
+
+        let remaining = self
+            .path
+            .iter()
+            .skip(1)
+            .copied()
+            .collect::<Vec<WorldPoint>>();
+        if !remaining.is_empty() {
+            let world = context.world.read().unwrap();
+            match Self::step(
+                TaskId::default(),
+                context.context.rules(),
+                &state,
+                &world,
+                unit.clone(),
+                remaining,
+            ) {
+                Ok(task) => {
+                    unit.set_task(Some(task.clone().into()));
+                    tasks.push(Box::new(task));
+                }
+                Err(error) => {
+                    debug!("Unit {} stop moving: {}", unit.id(), error)
+                }
+            }
+        }
+
+        effects.push(effect::replace_unit(unit));
+
+        Ok((effects, tasks))
+    }
+}
+
+impl From<Move> for ClientTaskType {
+    fn from(value: Move) -> Self {
+        let destination = value
+            .destination()
+            .copied()
+            .unwrap_or(*value.unit.geo().point());
+        ClientTaskType::Move(ClientMove::new(destination))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::{
+        game::{
+            nation::flag::Flag,
+            unit::{UnitId, UnitType},
+        },
+        geo::{GeoContext, GeoVec},
+        rules::std1::Std1RuleSet,
+        space::D2Size,
+    };
+
+    use crate::{
+        effect::{StateEffect, UnitEffect},
+        game::unit::UnitCanBuilder,
+        test::{city::build_city, context::build_context},
+    };
+
+    use super::*;
+
+    fn warriors(flag: Flag, point: WorldPoint) -> Unit {
+        Unit::builder()
+            .id(UnitId::default())
+            .type_(UnitType::Warriors)
+            .geo(GeoContext::builder().point(point).build())
+            .flag(flag)
+            .can(UnitCanBuilder::new().build())
+            .build()
+    }
+
+    #[test]
+    fn test_new_refuses_enemy_city_tile() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let city = build_city(3);
+        let unit = warriors(Flag::Aborigines, WorldPoint::new(2, 3));
+        let state = State::build_from(
+            Default::default(),
+            D2Size::new(5, 5),
+            Default::default(),
+            vec![city.clone()],
+            vec![GeoVec::new(*unit.geo(), vec![unit.clone()])],
+            &vec![],
+        );
+        let context = build_context(rules, state);
+
+        // WHEN
+        let result = Move::new(
+            TaskId::default(),
+            context.context.clone(),
+            context.state(),
+            context.world.read().unwrap(),
+            unit,
+            *city.geo().point(),
+        );
+
+        // THEN
+        assert!(matches!(
+            result,
+            Err(CreateTaskError::GamePlay(GamePlayReason::CantMove(
+                CantMoveReason::Occupied
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_then_stops_before_tile_occupied_since() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let unit = warriors(Flag::Abkhazia, WorldPoint::new(1, 1));
+        let state = State::build_from(
+            Default::default(),
+            D2Size::new(5, 5),
+            Default::default(),
+            vec![],
+            vec![GeoVec::new(*unit.geo(), vec![unit.clone()])],
+            &vec![],
+        );
+        let context = build_context(rules.clone(), state);
+        let task = Move::new(
+            TaskId::default(),
+            context.context.clone(),
+            context.state(),
+            context.world.read().unwrap(),
+            unit.clone(),
+            WorldPoint::new(2, 1),
+        )
+        .unwrap();
+        let enemy = warriors(Flag::Aborigines, WorldPoint::new(2, 1));
+        context
+            .state
+            .write()
+            .unwrap()
+            .apply(&vec![effect::new_unit(enemy)], &rules);
+
+        // WHEN
+        let (effects, tasks) = task.then(&context).unwrap();
+
+        // THEN
+        assert!(tasks.is_empty());
+        assert!(matches!(
+            effects.as_slice(),
+            [Effect::State(StateEffect::Unit(_, UnitEffect::Replace(moved)))]
+                if moved.geo().point() == &WorldPoint::new(1, 1) && moved.task().is_none()
+        ));
+    }
+}
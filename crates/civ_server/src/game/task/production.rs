@@ -1,16 +1,26 @@
 use bon::Builder;
-use common::game::{
-    city::{CityId, CityProductionTons},
-    tasks::client::city::production::ClientCityProductionTask,
-    unit::{CityTaskType, TaskType},
+use common::{
+    game::{
+        city::{CityId, CityProduct, CityProductionTons},
+        tasks::client::city::production::ClientCityProductionTask,
+        unit::{CityTaskType, TaskType, UnitId},
+    },
+    geo::{Geo, GeoContext},
 };
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    effect::Effect,
+    effect::{self, Effect},
+    game::unit::{Unit, UnitCanBuilder},
     impl_boxed, impl_with_context,
     runner::RunnerContext,
-    task::{Concern, Task, TaskBox, TaskContext, TaskError, Then, WithContext},
+    task::{
+        city::{
+            exploitation,
+            production::{product_task, stored_tons},
+        },
+        Concern, Task, TaskBox, TaskContext, TaskError, Then, WithContext,
+    },
 };
 
 #[derive(Debug, Builder, Clone, Serialize, Deserialize)]
@@ -21,7 +31,7 @@ pub struct CityProductionTask {
 }
 
 impl CityProductionTask {
-    pub fn _tons(&self) -> CityProductionTons {
+    pub fn tons(&self) -> CityProductionTons {
         self.tons
     }
 }
@@ -41,14 +51,73 @@ impl Task for CityProductionTask {
 }
 
 impl Then for CityProductionTask {
-    fn then(&self, _context: &RunnerContext) -> Result<(Vec<Effect>, Vec<TaskBox>), TaskError> {
-        todo!()
+    fn then(&self, context: &RunnerContext) -> Result<(Vec<Effect>, Vec<TaskBox>), TaskError> {
+        let rules = context.context.rules();
+        let state = context.state();
+        let city = state.find_city(&self.city)?;
+
+        let stored = stored_tons(city, &self.context.end());
+        let mut production = city.production().clone();
+        let done = production.next(&rules.default_product(), city.buildings());
+        // A building the city already has, or a product which required resources are no
+        // longer in work radius (the city moved since queued), is skipped and costs nothing
+        let resources = {
+            let world = context.world.read().unwrap();
+            exploitation::resources(rules, &world, city.geo().point())
+        };
+        let owned =
+            matches!(&done, CityProduct::Building(building) if city.buildings().contains(building));
+        let missing = rules
+            .required_resources(&done)
+            .iter()
+            .any(|resource| !resources.contains(resource));
+        let skipped = owned || missing;
+        // Surplus tons are kept for the next product
+        let spent = if skipped {
+            0
+        } else {
+            rules.required_tons(&done).0
+        };
+        production.set_tons(CityProductionTons(stored.0.saturating_sub(spent)));
+
+        let task = product_task(
+            rules,
+            &self.context.end(),
+            &self.city,
+            production.current(),
+            production.tons(),
+            city.exploitation().production_tons(),
+        );
+        let mut effects = vec![
+            effect::set_city_production(self.city, production),
+            effect::set_city_production_task(self.city, task.clone()),
+        ];
+        match &done {
+            _ if skipped => {}
+            CityProduct::Unit(type_) => effects.push(effect::new_unit(
+                Unit::builder()
+                    .id(UnitId::default())
+                    .type_(*type_)
+                    .geo(GeoContext::builder().point(*city.geo().point()).build())
+                    .flag(*city.flag())
+                    .can(UnitCanBuilder::new().build())
+                    .veteran(rules.veteran_units(city.buildings()))
+                    .build(),
+            )),
+            // Buildings can bring yield bonuses: worked tiles (and so production) are
+            // refreshed once all effects of the tick are applied
+            CityProduct::Building(building) => {
+                effects.push(effect::add_city_building(self.city, *building))
+            }
+        };
+
+        Ok((effects, vec![Box::new(task)]))
     }
 }
 
 impl From<CityProductionTask> for ClientCityProductionTask {
     fn from(value: CityProductionTask) -> Self {
         let context = value.context();
-        ClientCityProductionTask::new(context.start(), context.end())
+        ClientCityProductionTask::new(context.start(), context.end(), value.tons)
     }
 }
@@ -0,0 +1,192 @@
+use bon::Builder;
+use common::{
+    game::{
+        nation::flag::Flag,
+        tech::{Research, TechType},
+        unit::{PlayerTaskType, TaskType},
+        GameFrame, PlayerId,
+    },
+    rules::RuleSetBox,
+    task::CantResearchReason,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    effect::{self, Effect},
+    impl_boxed, impl_with_context,
+    runner::RunnerContext,
+    state::{NoLongerExist, State, StateError},
+    task::{Concern, Task, TaskBox, TaskContext, TaskError, TaskId, Then},
+};
+
+/// Research cycle of a player: when finished, science part of its cities trade is stored,
+/// which can make its current research known
+#[derive(Debug, Builder, Clone, Serialize, Deserialize)]
+pub struct ResearchTask {
+    context: TaskContext,
+    player: PlayerId,
+}
+
+impl_boxed!(ResearchTask);
+impl_with_context!(ResearchTask);
+
+#[typetag::serde]
+impl Task for ResearchTask {
+    fn type_(&self) -> TaskType {
+        TaskType::Player(PlayerTaskType::Research)
+    }
+
+    fn concern(&self) -> Concern {
+        Concern::Nothing
+    }
+
+    fn player(&self) -> Option<PlayerId> {
+        Some(self.player)
+    }
+}
+
+impl Then for ResearchTask {
+    fn then(&self, context: &RunnerContext) -> Result<(Vec<Effect>, Vec<TaskBox>), TaskError> {
+        let rules = context.context.rules();
+        let state = context.state();
+        let player =
+            state
+                .clients()
+                .player_state(&self.player)
+                .ok_or(StateError::NoLongerExist(NoLongerExist::Player(
+                    self.player,
+                )))?;
+
+        let (_, science) = player
+            .treasury()
+            .split(player_trade(rules, &state, player.flag()));
+        let task = research_task(rules, &self.context.end(), &self.player);
+
+        Ok((
+            vec![effect::store_science(self.player, science)],
+            vec![Box::new(task)],
+        ))
+    }
+}
+
+pub fn research_task(rules: &RuleSetBox, start: &GameFrame, player: &PlayerId) -> ResearchTask {
+    ResearchTask::builder()
+        .context(
+            TaskContext::builder()
+                .id(TaskId::default())
+                .start(*start)
+                .end(*start + rules.research_duration().0)
+                .build(),
+        )
+        .player(*player)
+        .build()
+}
+
+/// Trade of all cities of given flag
+pub fn player_trade(rules: &RuleSetBox, state: &State, flag: &Flag) -> u64 {
+    state
+        .index()
+        .flag_cities()
+        .get(flag)
+        .map(|city_ids| {
+            city_ids
+                .iter()
+                .filter_map(|city_id| state.find_city(city_id).ok())
+                .map(|city| rules.city_trade(city.exploitation().yield_()))
+                .sum::<u64>()
+        })
+        .unwrap_or(0)
+}
+
+/// Research after storing given science. Science beyond current tech cost is kept for
+/// the next one.
+pub fn next_research(rules: &RuleSetBox, research: &Research, science: u64) -> Research {
+    let mut research = research.clone();
+    research.set_science(research.science() + science);
+
+    if let Some(tech) = research.current().and_then(|type_| rules.tech(type_)) {
+        if research.science() >= tech.cost() {
+            research.set_science(research.science() - tech.cost());
+            research.learn(*tech.type_());
+        }
+    }
+
+    research
+}
+
+/// Research once given tech chosen as the current one
+pub fn choose_research(
+    rules: &RuleSetBox,
+    research: &Research,
+    tech: &TechType,
+) -> Result<Research, CantResearchReason> {
+    let tech = rules
+        .tech(tech)
+        .ok_or(CantResearchReason::NotInTree(*tech))?;
+
+    if research.knows(tech.type_()) {
+        return Err(CantResearchReason::AlreadyKnown(*tech.type_()));
+    }
+    if let Some(missing) = tech
+        .prerequisites()
+        .iter()
+        .find(|prerequisite| !research.knows(prerequisite))
+    {
+        return Err(CantResearchReason::MissingPrerequisite(*missing));
+    }
+
+    let mut research = research.clone();
+    research.set_current(Some(*tech.type_()));
+    Ok(research)
+}
+
+#[cfg(test)]
+mod test {
+    use common::rules::std1::Std1RuleSet;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Research::new(vec![], None, 0), 5, Research::new(vec![], None, 5))]
+    #[case(Research::new(vec![], Some(TechType::Pottery), 3), 5, Research::new(vec![], Some(TechType::Pottery), 8))]
+    #[case(Research::new(vec![], Some(TechType::Pottery), 8), 5, Research::new(vec![TechType::Pottery], None, 3))]
+    fn test_next_research(
+        #[case] research: Research,
+        #[case] science: u64,
+        #[case] expected: Research,
+    ) {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+
+        // WHEN
+        let research = next_research(&rules, &research, science);
+
+        // THEN
+        assert_eq!(research, expected);
+    }
+
+    #[rstest]
+    #[case(vec![], TechType::Alphabet, Ok(()))]
+    #[case(vec![TechType::Alphabet], TechType::Alphabet, Err(CantResearchReason::AlreadyKnown(TechType::Alphabet)))]
+    #[case(vec![], TechType::Writing, Err(CantResearchReason::MissingPrerequisite(TechType::Alphabet)))]
+    #[case(vec![TechType::Alphabet], TechType::Writing, Ok(()))]
+    fn test_choose_research(
+        #[case] known: Vec<TechType>,
+        #[case] tech: TechType,
+        #[case] expected: Result<(), CantResearchReason>,
+    ) {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let research = Research::new(known, None, 0);
+
+        // WHEN
+        let result = choose_research(&rules, &research, &tech);
+
+        // THEN
+        assert_eq!(result.map(|_| ()), expected);
+        if let Ok(research) = choose_research(&rules, &research, &tech) {
+            assert_eq!(research.current(), Some(&tech));
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use bon::Builder;
+use common::{
+    game::{
+        city::{BuildingType, CityId},
+        treasury::Treasury,
+        unit::{PlayerTaskType, TaskType, UnitId, UnitType},
+        GameFrame, PlayerId,
+    },
+    network::message::{NotificationLevel, ServerToClientInGameMessage, ServerToClientMessage},
+    rules::RuleSetBox,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    effect::{self, Effect},
+    game::task::research::player_trade,
+    impl_boxed, impl_with_context,
+    runner::RunnerContext,
+    state::{NoLongerExist, StateError},
+    task::{Concern, Task, TaskBox, TaskContext, TaskError, TaskId, Then},
+};
+
+/// Upkeep cycle of a player: when finished, gold part of its cities trade is collected and
+/// its units and buildings upkeep is paid. When gold runs out, buildings are sold then units
+/// disbanded.
+#[derive(Debug, Builder, Clone, Serialize, Deserialize)]
+pub struct UpkeepTask {
+    context: TaskContext,
+    player: PlayerId,
+}
+
+impl_boxed!(UpkeepTask);
+impl_with_context!(UpkeepTask);
+
+#[typetag::serde]
+impl Task for UpkeepTask {
+    fn type_(&self) -> TaskType {
+        TaskType::Player(PlayerTaskType::Upkeep)
+    }
+
+    fn concern(&self) -> Concern {
+        Concern::Nothing
+    }
+
+    fn player(&self) -> Option<PlayerId> {
+        Some(self.player)
+    }
+}
+
+impl Then for UpkeepTask {
+    fn then(&self, context: &RunnerContext) -> Result<(Vec<Effect>, Vec<TaskBox>), TaskError> {
+        let rules = context.context.rules();
+        let state = context.state();
+        let player =
+            state
+                .clients()
+                .player_state(&self.player)
+                .ok_or(StateError::NoLongerExist(NoLongerExist::Player(
+                    self.player,
+                )))?;
+        let flag = player.flag();
+
+        let units = state
+            .index()
+            .flag_units()
+            .get(flag)
+            .map(|unit_ids| {
+                unit_ids
+                    .iter()
+                    .filter_map(|unit_id| state.find_unit(unit_id).ok())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let cities = state
+            .index()
+            .flag_cities()
+            .get(flag)
+            .map(|city_ids| {
+                city_ids
+                    .iter()
+                    .filter_map(|city_id| state.find_city(city_id).ok())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let (income, _) = player.treasury().split(player_trade(rules, &state, flag));
+        let upkeep = pay_upkeep(
+            rules,
+            player.treasury(),
+            income,
+            cities.len() as u64 * rules.city_free_upkeep(),
+            &units
+                .iter()
+                .map(|unit| (*unit.id(), *unit.type_()))
+                .collect::<Vec<_>>(),
+            &cities
+                .iter()
+                .flat_map(|city| {
+                    city.buildings()
+                        .iter()
+                        .map(|building| (*city.id(), *building))
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        let task = upkeep_task(rules, &self.context.end(), &self.player);
+        // Gold change only, so tax rate chosen during the tick is kept
+        let gold = player.treasury().gold();
+        let mut effects = vec![];
+        if upkeep.treasury.gold() > gold {
+            effects.push(effect::add_gold(self.player, upkeep.treasury.gold() - gold));
+        } else if upkeep.treasury.gold() < gold {
+            effects.push(effect::remove_gold(
+                self.player,
+                gold - upkeep.treasury.gold(),
+            ));
+        }
+        let tasks: Vec<TaskBox> = vec![Box::new(task)];
+        let mut notifications = vec![];
+
+        for unit in units
+            .into_iter()
+            .filter(|unit| upkeep.disbanded.contains(unit.id()))
+        {
+            notifications.push(format!(
+                "{} disbanded: no gold left for its upkeep",
+                unit.type_()
+            ));
+            effects.extend(effect::disband_unit(unit.clone()));
+        }
+
+        // Refreshed once applied, as buildings can bring yield bonuses
+        for (city_id, building) in &upkeep.sold {
+            if let Some(city) = cities.iter().find(|city| city.id() == city_id) {
+                notifications.push(format!(
+                    "{} of {} sold: no gold left for its upkeep",
+                    building,
+                    city.name()
+                ));
+            }
+            effects.push(effect::remove_city_building(*city_id, *building));
+        }
+
+        if let Some(client_id) = state
+            .clients()
+            .index()
+            .player_client(&self.player)
+            .filter(|_| !notifications.is_empty())
+        {
+            effects.push(Effect::Shines(
+                notifications
+                    .into_iter()
+                    .map(|message| {
+                        (
+                            ServerToClientMessage::InGame(
+                                ServerToClientInGameMessage::Notification(
+                                    NotificationLevel::Warning,
+                                    message,
+                                ),
+                            ),
+                            vec![*client_id],
+                        )
+                    })
+                    .collect(),
+            ));
+        }
+
+        Ok((effects, tasks))
+    }
+}
+
+pub fn upkeep_task(rules: &RuleSetBox, start: &GameFrame, player: &PlayerId) -> UpkeepTask {
+    UpkeepTask::builder()
+        .context(
+            TaskContext::builder()
+                .id(TaskId::default())
+                .start(*start)
+                .end(*start + rules.upkeep_duration().0)
+                .build(),
+        )
+        .player(*player)
+        .build()
+}
+
+/// Outcome of an upkeep cycle
+#[derive(Debug, PartialEq)]
+pub struct Upkeep {
+    pub treasury: Treasury,
+    pub disbanded: Vec<UnitId>,
+    pub sold: Vec<(CityId, BuildingType)>,
+}
+
+/// Pay upkeep of given units and buildings (minus `free` upkeep) with treasury gold and
+/// given income. While gold is lacking, the last buildings are sold (which brings gold
+/// back), then the last units disbanded.
+pub fn pay_upkeep(
+    rules: &RuleSetBox,
+    treasury: &Treasury,
+    income: u64,
+    free: u64,
+    units: &[(UnitId, UnitType)],
+    buildings: &[(CityId, BuildingType)],
+) -> Upkeep {
+    let mut units = units.to_vec();
+    let mut buildings = buildings.to_vec();
+    let mut disbanded = vec![];
+    let mut sold = vec![];
+    let mut available = treasury.gold() + income;
+
+    loop {
+        let cost = (units
+            .iter()
+            .map(|(_, type_)| rules.unit_upkeep(type_))
+            .sum::<u64>()
+            + buildings
+                .iter()
+                .map(|(_, building)| rules.building_upkeep(building))
+                .sum::<u64>())
+        .saturating_sub(free);
+        if cost <= available {
+            available -= cost;
+            break;
+        }
+
+        if let Some(index) = buildings
+            .iter()
+            .rposition(|(_, building)| rules.building_upkeep(building) > 0)
+        {
+            let (city_id, building) = buildings.remove(index);
+            available += rules.building_sale(&building);
+            sold.push((city_id, building));
+        } else if let Some(index) = units
+            .iter()
+            .rposition(|(_, type_)| rules.unit_upkeep(type_) > 0)
+        {
+            disbanded.push(units.remove(index).0);
+        } else {
+            available = 0;
+            break;
+        }
+    }
+
+    let mut treasury = treasury.clone();
+    treasury.set_gold(available);
+    Upkeep {
+        treasury,
+        disbanded,
+        sold,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::rules::std1::Std1RuleSet;
+    use rstest::rstest;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn unit_id(n: u128) -> UnitId {
+        UnitId(Uuid::from_u128(n))
+    }
+
+    fn city_id(n: u128) -> CityId {
+        CityId(Uuid::from_u128(n))
+    }
+
+    #[rstest]
+    #[case(10, 1, 0, vec![], vec![], Upkeep { treasury: Treasury::new(11, 50), disbanded: vec![], sold: vec![] })]
+    #[case(10, 0, 0, vec![UnitType::Warriors, UnitType::Settlers], vec![], Upkeep { treasury: Treasury::new(9, 50), disbanded: vec![], sold: vec![] })]
+    #[case(0, 1, 2, vec![UnitType::Warriors, UnitType::Warriors], vec![BuildingType::Granary], Upkeep { treasury: Treasury::new(0, 50), disbanded: vec![], sold: vec![] })]
+    #[case(0, 0, 0, vec![UnitType::Warriors, UnitType::Settlers], vec![], Upkeep { treasury: Treasury::new(0, 50), disbanded: vec![unit_id(0)], sold: vec![] })]
+    #[case(0, 0, 0, vec![UnitType::Warriors], vec![BuildingType::Granary, BuildingType::Walls], Upkeep { treasury: Treasury::new(58, 50), disbanded: vec![], sold: vec![(city_id(1), BuildingType::Walls)] })]
+    fn test_pay_upkeep(
+        #[case] gold: u64,
+        #[case] income: u64,
+        #[case] free: u64,
+        #[case] units: Vec<UnitType>,
+        #[case] buildings: Vec<BuildingType>,
+        #[case] expected: Upkeep,
+    ) {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let units = units
+            .into_iter()
+            .enumerate()
+            .map(|(i, type_)| (unit_id(i as u128), type_))
+            .collect::<Vec<_>>();
+        let buildings = buildings
+            .into_iter()
+            .enumerate()
+            .map(|(i, building)| (city_id(i as u128), building))
+            .collect::<Vec<_>>();
+
+        // WHEN
+        let upkeep = pay_upkeep(
+            &rules,
+            &Treasury::new(gold, 50),
+            income,
+            free,
+            &units,
+            &buildings,
+        );
+
+        // THEN
+        assert_eq!(upkeep, expected);
+    }
+}
@@ -24,6 +24,12 @@ pub struct Unit {
     pub task: Option<UnitTaskWrapper>,
     pub geo: GeoContext,
     pub can: Vec<UnitCan>,
+    /// Veteran units are produced in cities with barracks (or so, according to ruleset)
+    #[builder(default)]
+    pub veteran: bool,
+    /// Hit points lost in combats, unit dies when it reaches its type hit points
+    #[builder(default)]
+    pub damage: u64,
 }
 
 impl Unit {
@@ -46,6 +52,18 @@ impl Unit {
     pub fn set_task(&mut self, task: Option<UnitTaskWrapper>) {
         self.task = task;
     }
+
+    pub fn veteran(&self) -> bool {
+        self.veteran
+    }
+
+    pub fn damage(&self) -> u64 {
+        self.damage
+    }
+
+    pub fn set_damage(&mut self, damage: u64) {
+        self.damage = damage;
+    }
 }
 
 impl IntoClientModel<ClientUnit> for Unit {
@@ -57,6 +75,8 @@ impl IntoClientModel<ClientUnit> for Unit {
             .geo(self.geo)
             .flag(self.flag)
             .can(self.can.clone())
+            .veteran(self.veteran)
+            .damage(self.damage)
             .build()
     }
 }
@@ -70,6 +90,8 @@ factori!(Unit, {
         task = None,
         geo = GeoContext::new(WorldPoint::new(0, 0)),
         can = Vec::new(),
+        veteran = false,
+        damage = 0,
     }
 });
 
@@ -0,0 +1,292 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    thread::{self, JoinHandle},
+};
+
+use async_std::channel::{unbounded, Receiver, Sender};
+use common::rules::RuleSetBox;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    effect::{Effect, StateEffect},
+    snapshot::{rotation::Rotation, Snapshot, SnapshotError},
+    state::State,
+};
+
+/// Append-only journal of the effects applied since the last full snapshot. Recovery loads
+/// the snapshot then replays the journal entries it doesn't include.
+#[derive(Debug, Default)]
+pub struct Journal {
+    entries: Vec<JournalEntry>,
+    /// Length of the journal file part which can be read (a crash can cut the last entry)
+    valid_len: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JournalEntry {
+    sequence: u64,
+    effects: Vec<StateEffect>,
+}
+
+/// Journal file of given snapshot file
+pub fn journal_path(snapshot: &Path) -> PathBuf {
+    let mut path = snapshot.as_os_str().to_owned();
+    path.push(".journal");
+    PathBuf::from(path)
+}
+
+/// Effects which must survive a restart: connected clients must not
+fn journaled(effect: &StateEffect) -> bool {
+    !matches!(effect, StateEffect::Clients(_) | StateEffect::Testing)
+}
+
+impl Journal {
+    pub fn read(path: &Path) -> Result<Self, SnapshotError> {
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => return Err(SnapshotError::Io(error.kind())),
+        };
+
+        let mut entries = vec![];
+        let mut position = 0;
+        // Each entry is its length followed by its bincode
+        while let Some(entry) = read_entry(&data[position..]) {
+            let (entry, len) = entry;
+            entries.push(entry);
+            position += len;
+        }
+        if position < data.len() {
+            warn!(
+                "Journal {} ends with {} unreadable bytes, ignore them",
+                path.display(),
+                data.len() - position
+            );
+        }
+
+        Ok(Self {
+            entries,
+            valid_len: position as u64,
+        })
+    }
+
+    pub fn valid_len(&self) -> u64 {
+        self.valid_len
+    }
+
+    /// Whether entries follow given snapshot sequence without gap. They don't when the
+    /// snapshot they follow can't be loaded and an older one is loaded instead.
+    pub fn follows(&self, sequence: u64) -> bool {
+        self.entries
+            .first()
+            .map(|entry| entry.sequence <= sequence + 1)
+            .unwrap_or(true)
+    }
+
+    /// Apply on given state the entries which follow given snapshot sequence. Return the
+    /// sequence of the last entry.
+    pub fn replay(self, state: &mut State, rules: &RuleSetBox, sequence: u64) -> u64 {
+        let mut last = sequence;
+        let mut replayed = 0;
+
+        for entry in self
+            .entries
+            .into_iter()
+            .filter(|entry| entry.sequence > sequence)
+        {
+            let effects: Vec<Effect> = entry.effects.into_iter().map(Effect::State).collect();
+            state.apply(&effects, rules);
+            last = entry.sequence;
+            replayed += 1;
+        }
+
+        // Nobody is connected yet, and visibility is computed for clients to come
+        state.clients_mut().forget_clients();
+        state.visibility_mut().take_changed();
+        info!("Replayed {} journal entries", replayed);
+
+        last
+    }
+}
+
+fn read_entry(data: &[u8]) -> Option<(JournalEntry, usize)> {
+    let len = u64::from_le_bytes(data.get(..8)?.try_into().ok()?) as usize;
+    let end = len.checked_add(8)?;
+    let entry = bincode::deserialize(data.get(8..end)?).ok()?;
+    Some((entry, end))
+}
+
+pub enum Persist {
+    /// Effects applied on the state
+    Effects(Vec<StateEffect>),
+    /// Full snapshot (to rotate at given path), after which the journal restarts
+    Snapshot(PathBuf),
+}
+
+/// Write journal and snapshots from its own thread, so the runner doesn't wait for disk.
+/// It applies the effects on its own copy of the state, so snapshots are built (and
+/// written) without holding the runner state lock. Trade-off: this copy doubles the memory
+/// used by the game state (world tiles excepted, which are not part of it), where cloning
+/// the state at each snapshot would hold the runner lock during the clone instead.
+pub struct Persister {
+    journal: File,
+    /// Sequence of the last written journal entry
+    sequence: u64,
+    /// Copy of the game state, up to date with the received effects
+    state: State,
+    rules: RuleSetBox,
+    /// Count of snapshots to keep
+    keep: usize,
+}
+
+impl Persister {
+    /// Open journal at given path, keeping only its first `valid_len` bytes, and start
+    /// writing it from a dedicated thread (which ends once all senders are dropped). Given
+    /// state must be a copy of the one the runner starts with.
+    pub fn spawn(
+        path: &Path,
+        valid_len: u64,
+        sequence: u64,
+        state: State,
+        rules: RuleSetBox,
+        keep: usize,
+    ) -> Result<(Sender<Persist>, JoinHandle<()>), SnapshotError> {
+        let mut journal = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .map_err(|e| SnapshotError::Io(e.kind()))?;
+        journal
+            .set_len(valid_len)
+            .and_then(|_| journal.seek(SeekFrom::End(0)))
+            .map_err(|e| SnapshotError::Io(e.kind()))?;
+
+        let (sender, receiver) = unbounded();
+        let mut persister = Self {
+            journal,
+            sequence,
+            state,
+            rules,
+            keep,
+        };
+        let handle = thread::spawn(move || persister.run(receiver));
+
+        Ok((sender, handle))
+    }
+
+    fn run(&mut self, receiver: Receiver<Persist>) {
+        while let Ok(persist) = receiver.recv_blocking() {
+            let result = match persist {
+                Persist::Effects(effects) => self.append(effects),
+                Persist::Snapshot(path) => self.snapshot(&path),
+            };
+            if let Err(error) = result {
+                error!("Error when persisting game: {}", error);
+            }
+        }
+    }
+
+    fn append(&mut self, effects: Vec<StateEffect>) -> Result<(), SnapshotError> {
+        let effects: Vec<Effect> = effects.into_iter().map(Effect::State).collect();
+        self.state.apply(&effects, &self.rules);
+        self.state.visibility_mut().take_changed();
+
+        let effects: Vec<StateEffect> = effects
+            .into_iter()
+            .filter_map(|effect| match effect {
+                Effect::State(effect) if journaled(&effect) => Some(effect),
+                _ => None,
+            })
+            .collect();
+        if effects.is_empty() {
+            return Ok(());
+        }
+
+        self.sequence += 1;
+        let entry = JournalEntry {
+            sequence: self.sequence,
+            effects,
+        };
+        let data =
+            bincode::serialize(&entry).map_err(|e| SnapshotError::Serialize(e.to_string()))?;
+        self.journal
+            .write_all(&(data.len() as u64).to_le_bytes())
+            .and_then(|_| self.journal.write_all(&data))
+            .map_err(|e| SnapshotError::Io(e.kind()))
+    }
+
+    fn snapshot(&mut self, path: &Path) -> Result<(), SnapshotError> {
+        let mut snapshot = Snapshot::from(&self.state);
+        snapshot.set_journal_sequence(self.sequence);
+        let path = Rotation::new(path.to_path_buf(), self.keep)
+            .save(&snapshot, self.rules.file().fingerprint())?;
+        info!("Snapshot to {}", path.display());
+
+        // Entries are now in the snapshot (replay would skip them anyway)
+        self.journal
+            .set_len(0)
+            .and_then(|_| self.journal.seek(SeekFrom::Start(0)))
+            .map(|_| ())
+            .map_err(|e| SnapshotError::Io(e.kind()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::{game::GameFrame, rules::std1::Std1RuleSet, space::D2Size};
+
+    use super::*;
+
+    #[test]
+    fn test_journal_replay() {
+        // GIVEN
+        let dir = std::env::temp_dir().join(format!("civ_journal_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let snapshot_path = dir.join("snapshot.civ");
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let mut persister = Persister {
+            journal: File::create(journal_path(&snapshot_path)).unwrap(),
+            sequence: 0,
+            state: State::empty(D2Size::new(1, 1)),
+            rules: rules.clone(),
+            keep: 1,
+        };
+
+        // WHEN
+        persister
+            .append(vec![StateEffect::IncrementGameFrame])
+            .unwrap();
+        persister.snapshot(&snapshot_path).unwrap();
+        persister
+            .append(vec![StateEffect::IncrementGameFrame])
+            .unwrap();
+        persister
+            .append(vec![StateEffect::Testing, StateEffect::IncrementGameFrame])
+            .unwrap();
+        // Crash while writing an entry
+        persister.journal.write_all(&[42, 0, 0]).unwrap();
+
+        // THEN
+        let (_, snapshot) = Rotation::new(snapshot_path.clone(), 1)
+            .load()
+            .unwrap()
+            .unwrap();
+        assert_eq!(snapshot.journal_sequence(), 1);
+        assert_eq!(snapshot.frame_i(), GameFrame(1));
+        let journal = Journal::read(&journal_path(&snapshot_path)).unwrap();
+        assert_eq!(journal.entries.len(), 2);
+        assert!(journal.follows(1));
+        assert!(!journal.follows(0));
+        let mut state = State::from(snapshot);
+        let sequence = journal.replay(&mut state, &rules, 1);
+        assert_eq!(sequence, 3);
+        assert_eq!(state.frame(), &GameFrame(3));
+        assert_eq!(state.testing(), 0);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -7,8 +7,9 @@ use std::path::PathBuf;
 use crate::config::ServerConfig;
 use crate::context::Context;
 use crate::game::placer::RandomPlacer;
+use crate::journal::{journal_path, Journal, Persister};
 use crate::runner::{Runner, RunnerContext};
-use crate::snapshot::{Snapshot, SnapshotError};
+use crate::snapshot::{rotation::Rotation, Snapshot, SnapshotError};
 use crate::state::State;
 use crate::task::snapshot::SnapshotTask;
 use crate::task::{TaskContext, TaskId};
@@ -19,11 +20,12 @@ use bridge::{Bridge, BridgeBuilder};
 use clap::Parser;
 use common::game::unit::{SystemTaskType, TaskType};
 use common::game::GameFrame;
+use common::rules::file::{FileRuleSet, FileRuleSetError};
 use common::rules::std1::Std1RuleSet;
+use common::rules::RuleSetBox;
 use common::space::D2Size;
 use common::utils::Progress;
 use log::{info, warn};
-use std::io;
 use std::{
     sync::{Arc, RwLock},
     thread,
@@ -35,6 +37,7 @@ pub mod config;
 pub mod context;
 pub mod effect;
 pub mod game;
+pub mod journal;
 pub mod reflect;
 pub mod runner;
 pub mod snapshot;
@@ -51,12 +54,19 @@ pub const TICK_BASE_PERIOD: u64 = 60;
 pub struct Args {
     /// World path to load
     world: PathBuf,
+    /// Ruleset (RON) file to play with, instead of the built-in std1 ruleset
+    #[arg(long)]
+    rules: Option<PathBuf>,
     /// Path where load and save server snapshot
     #[arg(short, long)]
     snapshot: Option<PathBuf>,
     /// Game frame interval count between two snapshot
     #[arg(long, default_value = "120000")]
     snapshot_interval: u64,
+    /// Count of snapshots to keep (named with their frame), older ones are removed
+    #[arg(long, default_value = "3")]
+    #[builder(default = 3)]
+    snapshot_keep: usize,
     /// TCP listen address
     #[arg(short, long, default_value = "127.0.0.1:9876")]
     tcp_listen_address: String,
@@ -73,6 +83,10 @@ pub enum Error {
     PrepareBridge(String),
     #[error("World error: {0}")]
     World(#[from] WorldReaderError),
+    #[error("Ruleset error: {0}")]
+    Rules(#[from] FileRuleSetError),
+    #[error("Interrupt handler error: {0}")]
+    Interrupt(String),
 }
 
 #[builder]
@@ -81,25 +95,59 @@ pub fn start<B: Bridge + 'static>(
     bridge_builder: &dyn BridgeBuilder<B>,
     state: Option<State>,
     progress: Option<Sender<Progress<WorldReaderError>>>,
+    /// Stop the server (after a final snapshot) on interrupt signal (Ctrl-C)
+    #[builder(default)]
+    interruptible: bool,
 ) -> Result<(), Error> {
     progress
         .as_ref()
         .map(|s| s.send_blocking(Progress::InProgress(0.)));
     let config = ServerConfig::from(args.clone());
-    let rules = Std1RuleSet;
+    let rules: RuleSetBox = match &args.rules {
+        Some(path) => {
+            info!("Read ruleset ...");
+            let rules = FileRuleSet::from_path(path)?;
+            info!("Read ruleset ... OK");
+            Box::new(rules)
+        }
+        None => Box::new(Std1RuleSet),
+    };
 
     info!("Read world ...");
     let world = WorldReader::from(args.world.clone(), &progress)?;
     info!("Read world ... OK ({} tiles)", world.shape());
 
     info!("Read snapshot or create from scratch ...");
-    let state = match state {
-        Some(state) => state,
-        None => build_state(&config, world.size())?,
+    let (state, journal) = match state {
+        Some(state) => (state, (0, 0)),
+        None => build_state(&config, &rules, world.size())?,
     };
     info!("Read snapshot or create from scratch ... OK");
-
-    let context = Context::new(Box::new(rules), config.clone());
+    let persister = match config.snapshot() {
+        Some(snapshot_path) => {
+            let (valid_len, sequence) = journal;
+            Some(Persister::spawn(
+                &journal_path(snapshot_path),
+                valid_len,
+                sequence,
+                State::from(Snapshot::from(&state)),
+                rules.clone(),
+                config.snapshot_keep(),
+            )?)
+        }
+        None => None,
+    };
+    let (persister, persister_handle) = persister.unzip();
+
+    let context = Context::new(rules, config.clone());
+    if interruptible {
+        let context = context.clone();
+        ctrlc::set_handler(move || {
+            info!("Interrupted, stop ...");
+            context.require_stop();
+        })
+        .map_err(|e| Error::Interrupt(e.to_string()))?;
+    }
     let state = Arc::new(RwLock::new(state));
     let world = Arc::new(RwLock::new(world));
     let (mut bridge, from_clients_receiver, to_clients_sender) = bridge_builder
@@ -108,6 +156,7 @@ pub fn start<B: Bridge + 'static>(
 
     let mut runner = Runner::builder()
         .tick_base_period(TICK_BASE_PERIOD)
+        .maybe_persister(persister)
         .context(RunnerContext::new(
             context.clone(),
             Arc::clone(&state),
@@ -123,11 +172,21 @@ pub fn start<B: Bridge + 'static>(
 
     network.join().unwrap();
     runner.join().unwrap();
+    // Runner dropped its persister sender: wait for the last writes (like final snapshot)
+    if let Some(persister_handle) = persister_handle {
+        persister_handle.join().unwrap();
+    }
 
     Ok(())
 }
 
-fn build_state(config: &ServerConfig, world_size: D2Size) -> Result<State, Error> {
+/// State from snapshot and its journal (or from scratch), with the journal valid length and
+/// last sequence
+fn build_state(
+    config: &ServerConfig,
+    rules: &RuleSetBox,
+    world_size: D2Size,
+) -> Result<(State, (u64, u64)), Error> {
     let state = match config.snapshot() {
         Some(snapshot_path) => {
             let snapshot_task = Box::new(SnapshotTask::new(
@@ -139,19 +198,53 @@ fn build_state(config: &ServerConfig, world_size: D2Size) -> Result<State, Error
                 snapshot_path.clone(),
             ));
 
-            match Snapshot::try_from(snapshot_path) {
-                Ok(snapshot) => State::from(snapshot),
-                Err(SnapshotError::Io(io::ErrorKind::NotFound)) => {
-                    warn!("No snapshot found, create from scratch");
-                    State::empty(world_size)
+            let rotation = Rotation::new(snapshot_path.clone(), config.snapshot_keep());
+            let (mut state, sequence) = match rotation.load()? {
+                Some((header, snapshot)) => {
+                    if snapshot.world_size() != world_size {
+                        return Err(Error::from(SnapshotError::WorldSize(
+                            snapshot.world_size(),
+                            world_size,
+                        )));
+                    }
+                    let sequence = snapshot.journal_sequence();
+                    let mut state = State::from(snapshot);
+                    match header {
+                        Some(header) if header.rules() != rules.file().fingerprint() => {
+                            warn!("Snapshot was played with other rules than the current ones")
+                        }
+                        None => {
+                            info!("Snapshot has no header, migrate it from its first format");
+                            state.see_around(rules);
+                        }
+                        _ => {}
+                    }
+
+                    (state, sequence)
                 }
-                Err(error) => {
-                    return Err(Error::from(error));
+                None => {
+                    warn!("No snapshot found, create from scratch");
+                    (State::empty(world_size), 0)
                 }
+            };
+
+            let mut journal = Journal::read(&journal_path(snapshot_path))?;
+            if !journal.follows(sequence) {
+                warn!("Journal doesn't follow the loaded snapshot, ignore it");
+                journal = Journal::default();
             }
-            .with_replaced_task_type(TaskType::System(SystemTaskType::Snapshot), snapshot_task)
+            let valid_len = journal.valid_len();
+            let sequence = journal.replay(&mut state, rules, sequence);
+
+            (
+                state.with_replaced_task_type(
+                    TaskType::System(SystemTaskType::Snapshot),
+                    snapshot_task,
+                ),
+                (valid_len, sequence),
+            )
         }
-        None => State::empty(world_size),
+        None => (State::empty(world_size), (0, 0)),
     };
     Ok(state)
 }
@@ -8,5 +8,6 @@ fn main() -> Result<(), Error> {
     start()
         .args(args)
         .bridge_builder(&NetworkBridgeBuilder)
+        .interruptible(true)
         .call()
 }
@@ -1,5 +1,8 @@
+use std::collections::{HashMap, HashSet};
+
 use common::{
-    geo::Geo,
+    game::{city::CityId, nation::flag::Flag, unit::UnitId, PlayerId},
+    geo::{Geo, GeoContext, WorldPoint},
     network::{
         message::{ClientStateMessage, ServerToClientInGameMessage, ServerToClientMessage},
         ClientId,
@@ -9,7 +12,7 @@ use log::error;
 use thiserror::Error;
 
 use crate::{
-    effect::{CityEffect, Effect, StateEffect, UnitEffect},
+    effect::{CityEffect, Effect, PlayerEffect, StateEffect, UnitEffect},
     game::{city::City, unit::Unit, IntoClientModel},
     runner::Runner,
     state::StateError,
@@ -42,10 +45,19 @@ impl Runner {
     ) -> Result<Vec<(ServerToClientMessage, Vec<ClientId>)>, ReflectError> {
         match effect {
             Effect::Shines(reflects) => Ok(reflects.clone()),
+            Effect::Snapshot(_) => Ok(vec![]),
             Effect::State(effect) => match effect {
                 StateEffect::Testing => Ok(vec![]),
                 StateEffect::Clients(_) => Ok(vec![]),
                 StateEffect::Client(_, _) => Ok(vec![]),
+                StateEffect::Player(player_id, effect) => match effect {
+                    PlayerEffect::SetResearching(_) | PlayerEffect::StoreScience(_) => {
+                        Ok(self.research_reflects(player_id))
+                    }
+                    PlayerEffect::SetTaxRate(_)
+                    | PlayerEffect::AddGold(_)
+                    | PlayerEffect::RemoveGold(_) => Ok(self.treasury_reflects(player_id)),
+                },
                 StateEffect::Task(_, _) => {
                     // Task are reflected into City & Unit in server side,
                     // then City & Units are entirely send to client
@@ -56,21 +68,60 @@ impl Runner {
                     // then City & Units are entirely send to client
                     Ok(vec![])
                 }
-                StateEffect::City(_, effect) => match effect {
+                StateEffect::City(city_id, effect) => match effect {
                     CityEffect::New(city) => self.set_city_reflects(city),
                     CityEffect::Replace(city) => self.set_city_reflects(city),
                     CityEffect::Remove(city) => self.removed_city_reflects(city),
+                    CityEffect::SetPopulation(_)
+                    | CityEffect::SetProduction(_)
+                    | CityEffect::SetExploitation(_)
+                    | CityEffect::AddBuilding(_)
+                    | CityEffect::RemoveBuilding(_)
+                    | CityEffect::SetProductionTask(_)
+                    | CityEffect::SetGrowthTask(_) => self.changed_city_reflects(city_id),
                 },
-                StateEffect::Unit(_, effect) => match effect {
+                StateEffect::Unit(unit_id, effect) => match effect {
                     UnitEffect::New(unit) => self.set_unit_reflects(unit),
                     UnitEffect::Replace(unit) => self.set_unit_reflects(unit),
                     UnitEffect::Remove(unit) => self.removed_unit_reflects(unit),
+                    UnitEffect::Damage(_) => self.damaged_unit_reflects(unit_id),
                 },
                 StateEffect::IncrementGameFrame => self.increment_game_frame_reflects(),
             },
         }
     }
 
+    /// Send tiles which visibility changed (inside their window) to their players
+    pub(crate) fn visibility_reflects(&self, changed: &HashMap<Flag, HashSet<WorldPoint>>) {
+        if changed.is_empty() {
+            return;
+        }
+
+        let state = self.state();
+        for (player_id, player_state) in state.clients().states() {
+            let Some(points) = changed.get(player_state.flag()) else {
+                continue;
+            };
+
+            if let Some(client_id) = state.clients().index().player_client(player_id) {
+                let points = points
+                    .iter()
+                    .filter(|point| player_state.window().contains(&GeoContext::new(**point)))
+                    .copied()
+                    .collect::<Vec<_>>();
+                if points.is_empty() {
+                    continue;
+                }
+
+                let tiles = self.context.client_tiles(player_state.flag(), &points);
+                let _ = self
+                    .context
+                    .to_client_sender
+                    .send_blocking((*client_id, ClientStateMessage::SetTiles(tiles).into()));
+            }
+        }
+    }
+
     // FIXME BS NOW: keep in memory (in client state ?) which client are still connected
     // to send only to connected
     fn increment_game_frame_reflects(
@@ -86,12 +137,60 @@ impl Runner {
         )])
     }
 
+    /// Send given message to the client of given player, if connected
+    fn player_reflects(
+        &self,
+        player_id: &PlayerId,
+        message: ClientStateMessage,
+    ) -> Vec<(ServerToClientMessage, Vec<ClientId>)> {
+        let state = self.state();
+        state
+            .clients()
+            .index()
+            .player_client(player_id)
+            .map(|client_id| (message.into(), vec![*client_id]))
+            .into_iter()
+            .collect()
+    }
+
+    fn research_reflects(
+        &self,
+        player_id: &PlayerId,
+    ) -> Vec<(ServerToClientMessage, Vec<ClientId>)> {
+        let research = self
+            .state()
+            .clients()
+            .player_state(player_id)
+            .map(|state| state.research().clone());
+        research
+            .map(|research| {
+                self.player_reflects(player_id, ClientStateMessage::SetResearch(research))
+            })
+            .unwrap_or_default()
+    }
+
+    fn treasury_reflects(
+        &self,
+        player_id: &PlayerId,
+    ) -> Vec<(ServerToClientMessage, Vec<ClientId>)> {
+        let treasury = self
+            .state()
+            .clients()
+            .player_state(player_id)
+            .map(|state| state.treasury().clone());
+        treasury
+            .map(|treasury| {
+                self.player_reflects(player_id, ClientStateMessage::SetTreasury(treasury))
+            })
+            .unwrap_or_default()
+    }
+
     fn set_city_reflects(
         &self,
         city: &City,
     ) -> Result<Vec<(ServerToClientMessage, Vec<ClientId>)>, ReflectError> {
         let state = self.state();
-        let clients = state.clients().concerned(city.geo());
+        let clients = state.clients().seeing(city.geo(), state.visibility());
         if !clients.is_empty() {
             return Ok(vec![(
                 ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
@@ -104,12 +203,21 @@ impl Runner {
         Ok(vec![])
     }
 
+    /// Send the city, as it is once all effects applied
+    fn changed_city_reflects(
+        &self,
+        city_id: &CityId,
+    ) -> Result<Vec<(ServerToClientMessage, Vec<ClientId>)>, ReflectError> {
+        let city = self.state().find_city(city_id)?.clone();
+        self.set_city_reflects(&city)
+    }
+
     fn removed_city_reflects(
         &self,
         city: &City,
     ) -> Result<Vec<(ServerToClientMessage, Vec<ClientId>)>, ReflectError> {
         let state = self.state();
-        let clients = state.clients().concerned(city.geo());
+        let clients = state.clients().seeing(city.geo(), state.visibility());
         if !clients.is_empty() {
             return Ok(vec![(
                 ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
@@ -127,7 +235,7 @@ impl Runner {
         unit: &Unit,
     ) -> Result<Vec<(ServerToClientMessage, Vec<ClientId>)>, ReflectError> {
         let state = self.state();
-        let clients = state.clients().concerned(unit.geo());
+        let clients = state.clients().seeing(unit.geo(), state.visibility());
         if !clients.is_empty() {
             return Ok(vec![(
                 ClientStateMessage::SetUnit(unit.clone().into_client(&state)).into(),
@@ -138,12 +246,23 @@ impl Runner {
         Ok(vec![])
     }
 
+    /// Send the unit, as it is once all effects applied (unless it died)
+    fn damaged_unit_reflects(
+        &self,
+        unit_id: &UnitId,
+    ) -> Result<Vec<(ServerToClientMessage, Vec<ClientId>)>, ReflectError> {
+        let Ok(unit) = self.state().find_unit(unit_id).cloned() else {
+            return Ok(vec![]);
+        };
+        self.set_unit_reflects(&unit)
+    }
+
     fn removed_unit_reflects(
         &self,
         unit: &Unit,
     ) -> Result<Vec<(ServerToClientMessage, Vec<ClientId>)>, ReflectError> {
         let state = self.state();
-        let clients = state.clients().concerned(unit.geo());
+        let clients = state.clients().seeing(unit.geo(), state.visibility());
         if !clients.is_empty() {
             return Ok(vec![(
                 ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
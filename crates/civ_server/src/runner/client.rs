@@ -1,24 +1,38 @@
+use std::collections::HashSet;
+
 use crate::{
     effect::{self, ClientEffect, ClientsEffect, Effect, StateEffect, UnitEffect},
     game::{
         access::Access,
-        task::settle::Settle,
+        city::City,
+        task::{
+            attack::Attack,
+            move_::Move,
+            research::{choose_research, research_task},
+            settle::Settle,
+            upkeep::upkeep_task,
+        },
         unit::{Unit, UnitCanBuilder},
     },
     runner::{DealClientRequestError, RunnerContext, RunnerError},
-    state::flag::player_flag,
+    state::{flag::player_flag, State},
     task::{
-        city::generator::{BuildCityFrom, BuildCityFromChange, CityGenerator},
+        city::{
+            exploitation,
+            generator::{BuildCityFrom, BuildCityFromChange, CityGenerator},
+        },
+        unit::UnitTaskWrapper,
         Concern, TaskId,
     },
 };
 use common::{
     game::{
-        city::CityId,
+        city::{CityId, CityProduct, CityProduction, CityProductionError},
         nation::flag::Flag,
-        unit::{UnitId, UnitType},
+        tech::{TechType, TechUnlock},
+        unit::{TaskType, UnitId, UnitTaskType, UnitType},
     },
-    geo::GeoContext,
+    geo::{Geo, GeoContext},
     network::{
         message::{
             ClientStateMessage, ClientToServerCityMessage, ClientToServerEstablishmentMessage,
@@ -29,7 +43,10 @@ use common::{
         },
         Client,
     },
+    rules::RuleSetBox,
     space::window::{Resolution, Window},
+    task::{CantResearchReason, CreateTaskError, GamePlayReason},
+    world::Resource,
 };
 use log::debug;
 
@@ -48,14 +65,18 @@ pub fn deal_client(
 
 fn client_network(
     context: &RunnerContext,
-    _client: &Client,
+    client: &Client,
     message: &ClientToServerNetworkMessage,
 ) -> Result<Vec<Effect>, RunnerError> {
     match &message {
-        ClientToServerNetworkMessage::Hello(client, resolution) => {
+        // Protocol version is checked by network bridge
+        ClientToServerNetworkMessage::Hello(_, client, resolution) => {
             client_hello(context, client, resolution)
         }
-        ClientToServerNetworkMessage::Goodbye => Ok(vec![]),
+        // Also sent by network bridge when client disconnected
+        ClientToServerNetworkMessage::Goodbye => Ok(vec![Effect::State(StateEffect::Clients(
+            ClientsEffect::Remove(*client.client_id()),
+        ))]),
     }
 }
 
@@ -67,19 +88,35 @@ fn client_hello(
     let state = context.state();
     let server_resume = state.server_resume(context.context.rules());
     let player_flag = state.player_flag(client.player_id());
+    let mut effects = vec![Effect::State(StateEffect::Clients(ClientsEffect::Insert(
+        *client.client_id(),
+        *client.player_id(),
+    )))];
     let mut shines = vec![(
         ServerToClientMessage::Establishment(ServerToClientEstablishmentMessage::ServerResume(
-            server_resume,
+            Box::new(server_resume),
             player_flag,
         )),
         vec![*client.client_id()],
     )];
-    if let Some(window) = state
+    if let Some((flag, window, research, treasury)) = state
         .clients()
         .states()
         .get(client.player_id())
-        .map(|state| Window::from_around(&state.window().center(), resolution))
+        .map(|state| {
+            (
+                *state.flag(),
+                Window::from_around(&state.window().center(), resolution),
+                state.research().clone(),
+                state.treasury().clone(),
+            )
+        })
     {
+        // Reconnecting player resumes where it was, with its new resolution
+        effects.push(Effect::State(StateEffect::Client(
+            *client,
+            ClientEffect::SetWindow(window),
+        )));
         shines.extend(vec![
             (
                 ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
@@ -95,22 +132,25 @@ fn client_hello(
             ),
         ]);
 
-        let game_slice = context.game_slice(&window);
+        let game_slice = context.game_slice(&flag, &window);
         shines.push((
             ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
                 ClientStateMessage::SetGameSlice(game_slice),
             )),
             vec![*client.client_id()],
         ));
+        shines.push((
+            ClientStateMessage::SetResearch(research).into(),
+            vec![*client.client_id()],
+        ));
+        shines.push((
+            ClientStateMessage::SetTreasury(treasury).into(),
+            vec![*client.client_id()],
+        ));
     }
 
-    Ok(vec![
-        Effect::State(StateEffect::Clients(ClientsEffect::Insert(
-            *client.client_id(),
-            *client.player_id(),
-        ))),
-        Effect::Shines(shines),
-    ])
+    effects.push(Effect::Shines(shines));
+    Ok(effects)
 }
 
 fn client_game(
@@ -132,8 +172,8 @@ fn client_establishment(
     message: &ClientToServerEstablishmentMessage,
 ) -> Result<Vec<Effect>, RunnerError> {
     match message {
-        ClientToServerEstablishmentMessage::TakePlace(flag, resolution) => {
-            client_take_place(context, client, flag, *resolution)
+        ClientToServerEstablishmentMessage::TakePlace(flag, resolution, rules) => {
+            client_take_place(context, client, flag, *resolution, *rules)
         }
     }
 }
@@ -153,7 +193,7 @@ fn client_ingame(
 
     match message {
         ClientToServerInGameMessage::SetWindow(window) => {
-            let game_slice = context.game_slice(window);
+            let game_slice = context.game_slice(flag, window);
 
             Ok(vec![
                 Effect::State(StateEffect::Client(
@@ -176,6 +216,19 @@ fn client_ingame(
             //
             refresh_city_on(context, city_id, message)
         }
+        ClientToServerInGameMessage::SetResearch(tech) => {
+            let research = state.clients().research(flag).cloned().unwrap_or_default();
+            choose_research(context.context.rules(), &research, tech).map_err(cant_research)?;
+            Ok(vec![effect::set_researching(*client.player_id(), *tech)])
+        }
+        ClientToServerInGameMessage::SetTaxRate(tax_rate) => {
+            if *tax_rate > 100 {
+                return Err(
+                    CreateTaskError::GamePlay(GamePlayReason::InvalidTaxRate(*tax_rate)).into(),
+                );
+            }
+            Ok(vec![effect::set_tax_rate(*client.player_id(), *tax_rate)])
+        }
     }
 }
 
@@ -184,11 +237,28 @@ fn client_take_place(
     client: &Client,
     flag: &Flag,
     resolution: Resolution,
+    fingerprint: u64,
 ) -> Result<Vec<Effect>, RunnerError> {
     let rules = context.context.rules();
     let world = context.world.read().unwrap();
     let state = context.state();
 
+    // Client would disagree with server about what can be done
+    if fingerprint != rules.file().fingerprint() {
+        debug!(
+            "Client {}: establishment refused (rules)",
+            client.client_id()
+        );
+        return Ok(vec![Effect::Shines(vec![(
+            ServerToClientMessage::Establishment(
+                ServerToClientEstablishmentMessage::TakePlaceRefused(
+                    TakePlaceRefusedReason::IncompatibleRules,
+                ),
+            ),
+            vec![*client.client_id()],
+        )])]);
+    }
+
     if state
         .clients()
         .states()
@@ -207,9 +277,12 @@ fn client_take_place(
         )])]);
     }
 
-    let point = context.placer.startup(rules, &state, &world).map_err(|e| {
-        RunnerError::DealClientRequest(DealClientRequestError::Unfeasible(e.to_string()))
-    })?;
+    let point = context
+        .placer
+        .startup(rules, &state, &world, client.player_id())
+        .map_err(|e| {
+            RunnerError::DealClientRequest(DealClientRequestError::Unfeasible(e.to_string()))
+        })?;
 
     // TODO: move code of unit generation and make it depend on ruleset
     let settler_id = UnitId::default();
@@ -223,9 +296,13 @@ fn client_take_place(
 
     let server_resume = state.server_resume(rules);
     let window = Window::from_around(&point.into(), &resolution);
-    let game_slice = context.game_slice(&window);
+    let research = research_task(rules, state.frame(), client.player_id());
+    let upkeep = upkeep_task(rules, state.frame(), client.player_id());
+    let game_slice = context.game_slice(flag, &window);
     Ok(vec![
         Effect::State(StateEffect::Unit(settler_id, UnitEffect::New(settler))),
+        effect::add_task(Box::new(research)),
+        effect::add_task(Box::new(upkeep)),
         Effect::State(StateEffect::Client(
             *client,
             ClientEffect::PlayerTookPlace(*flag, window),
@@ -234,14 +311,9 @@ fn client_take_place(
             *client,
             ClientEffect::SetWindow(window),
         )),
+        // Tiles seen by the settler are sent when its visibility is applied
         Effect::Shines(vec![
             // Need to send window to client as he took place and is not the origin of this window
-            (
-                ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
-                    ClientStateMessage::SetGameSlice(game_slice),
-                )),
-                vec![*client.client_id()],
-            ),
             (
                 ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
                     ClientStateMessage::SetWindow(window),
@@ -250,10 +322,17 @@ fn client_take_place(
             ),
             (
                 ServerToClientMessage::Establishment(
-                    ServerToClientEstablishmentMessage::ServerResume(server_resume, Some(*flag)),
+                    ServerToClientEstablishmentMessage::ServerResume(
+                        Box::new(server_resume),
+                        Some(*flag),
+                    ),
                 ),
                 vec![*client.client_id()],
             ),
+            (
+                ClientStateMessage::SetGameSlice(game_slice).into(),
+                vec![*client.client_id()],
+            ),
         ]),
     ])
 }
@@ -270,27 +349,72 @@ pub fn refresh_unit_on(
     let unit = state.find_unit(unit_id).unwrap(); // TODO: unwrap -> same error management than crate_task
     let old_task = unit.task();
 
-    let new_task = match message {
-        ClientToServerUnitMessage::Settle(city_name) => Some(Settle::new(
-            TaskId::default(),
-            context.context.clone(),
-            context.state(),
-            unit.clone(),
-            city_name.clone(),
-        )?),
+    let task_type = match message {
+        ClientToServerUnitMessage::Settle(_) => Some(UnitTaskType::Settle),
+        ClientToServerUnitMessage::MoveTo(_) => Some(UnitTaskType::Move),
+        ClientToServerUnitMessage::Attack(_) => Some(UnitTaskType::Attack),
+        ClientToServerUnitMessage::CancelCurrentTask => None,
+    };
+    if let Some(task_type) = task_type {
+        let techs = known_techs(&state, unit.flag());
+        if !context
+            .context
+            .rules()
+            .unlocked(&TechUnlock::Task(task_type.clone()), &techs)
+        {
+            return Err(
+                CreateTaskError::GamePlay(GamePlayReason::TaskLocked(TaskType::Unit(task_type)))
+                    .into(),
+            );
+        }
+    }
+
+    let new_task: Option<UnitTaskWrapper> = match message {
+        ClientToServerUnitMessage::Settle(city_name) => Some(
+            Settle::new(
+                TaskId::default(),
+                context.context.clone(),
+                context.state(),
+                unit.clone(),
+                city_name.clone(),
+            )?
+            .into(),
+        ),
+        ClientToServerUnitMessage::MoveTo(destination) => Some(
+            Move::new(
+                TaskId::default(),
+                context.context.clone(),
+                context.state(),
+                context.world.read().unwrap(),
+                unit.clone(),
+                *destination,
+            )?
+            .into(),
+        ),
+        ClientToServerUnitMessage::Attack(target) => Some(
+            Attack::new(
+                TaskId::default(),
+                context.context.clone(),
+                context.state(),
+                context.world.read().unwrap(),
+                unit.clone(),
+                *target,
+            )?
+            .into(),
+        ),
         ClientToServerUnitMessage::CancelCurrentTask => None,
     };
     let mut unit = unit.clone();
     unit.task = None;
 
     if let Some(new_task) = &new_task {
-        unit.set_task(Some(new_task.clone().into()));
+        unit.set_task(Some(new_task.clone()));
     }
 
     let mut effects = vec![effect::replace_unit(unit)];
 
     if let Some(new_task) = new_task {
-        effects.push(effect::add_task(Box::new(new_task)));
+        effects.push(effect::add_task(new_task.into()));
     }
 
     if let Some(old_task) = old_task {
@@ -308,13 +432,46 @@ pub fn refresh_city_on(
     let state = context.state();
     let city = state.find_city(city_id).unwrap(); // TODO: unwrap -> same error management than crate_task
     let from = match message {
-        ClientToServerCityMessage::SetProduction(production) => {
-            BuildCityFrom::Change(city, BuildCityFromChange::Production(production.clone()))
+        ClientToServerCityMessage::SetProduction(_)
+        | ClientToServerCityMessage::PushProduct(_)
+        | ClientToServerCityMessage::RemoveProduct(_)
+        | ClientToServerCityMessage::MoveProduct(_, _)
+        | ClientToServerCityMessage::SetRepeat(_) => {
+            let rules = context.context.rules();
+            let techs = known_techs(&state, city.flag());
+            let resources = {
+                let world = context.world.read().unwrap();
+                exploitation::resources(rules, &world, city.geo().point())
+            };
+            let production = change_production(rules, city, &techs, &resources, message)
+                .map_err(cant_change_production)?;
+            BuildCityFrom::Change(city, BuildCityFromChange::Production(production))
+        }
+        ClientToServerCityMessage::SetWorkedTiles(tiles) => {
+            let rules = context.context.rules();
+            let world = context.world.read().unwrap();
+            let center = city.geo().point();
+            let taken = exploitation::taken(rules, &state, center);
+            exploitation::check(
+                rules,
+                &world,
+                center,
+                city.population().size(),
+                &taken,
+                tiles,
+            )
+            .map_err(|reason| CreateTaskError::GamePlay(GamePlayReason::CantExploit(reason)))?;
+            BuildCityFrom::Change(
+                city,
+                BuildCityFromChange::Exploitation(exploitation::exploitation(
+                    rules,
+                    &world,
+                    center,
+                    city.buildings(),
+                    tiles.clone(),
+                )),
+            )
         }
-        ClientToServerCityMessage::SetExploitation(exploitation) => BuildCityFrom::Change(
-            city,
-            BuildCityFromChange::Exploitation(exploitation.clone()),
-        ),
     };
     let old_tasks = state
         .index()
@@ -338,3 +495,208 @@ pub fn refresh_city_on(
         effect::add_tasks(new_tasks),
     ])
 }
+
+/// City production queue once given client change applied
+fn change_production(
+    rules: &RuleSetBox,
+    city: &City,
+    techs: &[TechType],
+    resources: &HashSet<Resource>,
+    message: &ClientToServerCityMessage,
+) -> Result<CityProduction, CityProductionError> {
+    let mut production = city.production().clone();
+
+    match message {
+        ClientToServerCityMessage::SetProduction(production_) => {
+            if production_.stack().is_empty() {
+                return Err(CityProductionError::Empty);
+            }
+            for (index, product) in production_.stack().iter().enumerate() {
+                let queued = &production_.stack()[..index];
+                check_product(rules, city, techs, resources, queued, product)?;
+            }
+            production = production_.clone();
+        }
+        ClientToServerCityMessage::PushProduct(product) => {
+            check_product(rules, city, techs, resources, production.stack(), product)?;
+            production.push(product.clone())
+        }
+        ClientToServerCityMessage::RemoveProduct(index) => {
+            production.remove(*index)?;
+        }
+        ClientToServerCityMessage::MoveProduct(from, to) => production.move_(*from, *to)?,
+        ClientToServerCityMessage::SetRepeat(repeat) => production.set_repeat(*repeat),
+        ClientToServerCityMessage::SetWorkedTiles(_) => {}
+    }
+
+    Ok(production)
+}
+
+/// Ensure given product can be added to the city production, after `queued` products,
+/// with `resources` available in the city work radius
+fn check_product(
+    rules: &RuleSetBox,
+    city: &City,
+    techs: &[TechType],
+    resources: &HashSet<Resource>,
+    queued: &[CityProduct],
+    product: &CityProduct,
+) -> Result<(), CityProductionError> {
+    if !rules.can_produce(product, techs) {
+        return Err(CityProductionError::Unavailable(product.clone()));
+    }
+    if let CityProduct::Building(building) = product {
+        if !rules.buildings().contains(building) {
+            return Err(CityProductionError::Unavailable(product.clone()));
+        }
+        if city.has_building(building) {
+            return Err(CityProductionError::AlreadyBuilt(*building));
+        }
+        if queued.contains(product) {
+            return Err(CityProductionError::AlreadyQueued(*building));
+        }
+    }
+    if let Some(resource) = rules
+        .required_resources(product)
+        .into_iter()
+        .find(|resource| !resources.contains(resource))
+    {
+        return Err(CityProductionError::MissingResource(resource));
+    }
+
+    Ok(())
+}
+
+fn cant_change_production(error: CityProductionError) -> CreateTaskError {
+    CreateTaskError::GamePlay(GamePlayReason::CantChangeProduction(error))
+}
+
+fn cant_research(reason: CantResearchReason) -> CreateTaskError {
+    CreateTaskError::GamePlay(GamePlayReason::CantResearch(reason))
+}
+
+/// Techs known by the player playing given flag
+fn known_techs(state: &State, flag: &Flag) -> Vec<TechType> {
+    state
+        .clients()
+        .research(flag)
+        .map(|research| research.known().to_vec())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use common::{
+        game::city::{BuildingType, CityProduction},
+        rules::std1::Std1RuleSet,
+    };
+    use rstest::rstest;
+
+    use crate::test::city::build_city;
+
+    use super::*;
+
+    #[rstest]
+    #[case(
+        ClientToServerCityMessage::SetProduction(CityProduction::new(vec![
+            CityProduct::Unit(UnitType::Warriors),
+        ])),
+        Ok(vec![CityProduct::Unit(UnitType::Warriors)])
+    )]
+    #[case(
+        ClientToServerCityMessage::SetProduction(CityProduction::new(vec![])),
+        Err(CityProductionError::Empty)
+    )]
+    #[case(
+        ClientToServerCityMessage::SetProduction(CityProduction::new(vec![
+            CityProduct::Unit(UnitType::Warriors),
+            CityProduct::Building(BuildingType::Granary),
+        ])),
+        Err(CityProductionError::AlreadyBuilt(BuildingType::Granary))
+    )]
+    #[case(
+        ClientToServerCityMessage::PushProduct(CityProduct::Building(BuildingType::Granary)),
+        Err(CityProductionError::AlreadyBuilt(BuildingType::Granary))
+    )]
+    #[case(
+        ClientToServerCityMessage::PushProduct(CityProduct::Building(BuildingType::Walls)),
+        Ok(vec![CityProduct::Building(BuildingType::Walls)])
+    )]
+    #[case(
+        ClientToServerCityMessage::SetProduction(CityProduction::new(vec![
+            CityProduct::Building(BuildingType::Walls),
+            CityProduct::Building(BuildingType::Walls),
+        ])),
+        Err(CityProductionError::AlreadyQueued(BuildingType::Walls))
+    )]
+    fn test_change_production(
+        #[case] message: ClientToServerCityMessage,
+        #[case] expected: Result<Vec<CityProduct>, CityProductionError>,
+    ) {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let techs: Vec<TechType> = rules.techs().iter().map(|tech| *tech.type_()).collect();
+        let mut city = build_city(0);
+        city.add_building(BuildingType::Granary);
+
+        // WHEN
+        let production = change_production(&rules, &city, &techs, &HashSet::new(), &message);
+
+        // THEN
+        assert_eq!(
+            production.map(|production| production.stack().to_vec()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_change_production_push_queued_building() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let techs: Vec<TechType> = rules.techs().iter().map(|tech| *tech.type_()).collect();
+        let mut city = build_city(0);
+        *city.production_mut() = CityProduction::new(vec![
+            CityProduct::Building(BuildingType::Walls),
+            CityProduct::Unit(UnitType::Warriors),
+        ]);
+        let message =
+            ClientToServerCityMessage::PushProduct(CityProduct::Building(BuildingType::Walls));
+
+        // WHEN
+        let production = change_production(&rules, &city, &techs, &HashSet::new(), &message);
+
+        // THEN
+        assert_eq!(
+            production,
+            Err(CityProductionError::AlreadyQueued(BuildingType::Walls))
+        );
+    }
+
+    #[rstest]
+    #[case(vec![], Err(CityProductionError::MissingResource(Resource::Iron)))]
+    #[case(
+        vec![Resource::Iron],
+        Ok(vec![CityProduct::Building(BuildingType::Barracks)])
+    )]
+    fn test_change_production_required_resources(
+        #[case] resources: Vec<Resource>,
+        #[case] expected: Result<Vec<CityProduct>, CityProductionError>,
+    ) {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let techs: Vec<TechType> = rules.techs().iter().map(|tech| *tech.type_()).collect();
+        let city = build_city(0);
+        let resources = resources.into_iter().collect();
+        let message =
+            ClientToServerCityMessage::PushProduct(CityProduct::Building(BuildingType::Barracks));
+
+        // WHEN
+        let production = change_production(&rules, &city, &techs, &resources, &message);
+
+        // THEN
+        assert_eq!(
+            production.map(|production| production.stack().to_vec()),
+            expected
+        );
+    }
+}
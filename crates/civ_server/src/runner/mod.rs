@@ -1,11 +1,7 @@
 use async_std::channel::{Receiver, Sender};
 use bon::Builder;
 use common::{
-    game::{
-        city::{CityProduct, CityProduction},
-        unit::UnitType,
-        GameFrame, GAME_FRAMES_PER_SECOND,
-    },
+    game::{city::CityProduction, GameFrame, GAME_FRAMES_PER_SECOND},
     network::{
         message::{
             ClientToServerMessage, NotificationLevel, ServerToClientInGameMessage,
@@ -15,7 +11,7 @@ use common::{
     },
     task::{CreateTaskError, GamePlayReason},
 };
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use std::{
     sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
     thread,
@@ -26,10 +22,14 @@ use thiserror::Error;
 use crate::{
     context::Context,
     effect::{Effect, StateEffect, TaskEffect},
-    game::placer::{PlacerBox, RandomPlacer},
+    game::{
+        placer::{PlacerBox, RandomPlacer},
+        task::attack::deaths,
+    },
+    journal::Persist,
     runner::{client::deal_client, worker::setup_task_workers},
     state::{NoLongerExist, State, StateError},
-    task::{TaskBox, TaskError},
+    task::{city::refresh::refresh_cities, TaskBox, TaskError},
     world::reader::WorldReader,
 };
 
@@ -72,8 +72,7 @@ impl RunnerContext {
     }
 
     pub fn default_production(&self) -> CityProduction {
-        // Default according to context (warrior, then phalanx, etc) and tons
-        CityProduction::new(vec![CityProduct::Unit(UnitType::Warriors)])
+        CityProduction::new(vec![self.context.rules().default_product()])
     }
 }
 
@@ -108,6 +107,8 @@ pub struct Runner {
     // TODO: pub for benches ...
     #[builder(default = vec![])]
     pub task_workers: Vec<(Sender<()>, Receiver<Vec<Effect>>)>,
+    /// Journal and snapshots writer, see [`crate::journal::Persister`]
+    persister: Option<Sender<Persist>>,
 }
 
 #[derive(Debug, Error)]
@@ -158,6 +159,16 @@ impl Runner {
         while !self.context.context.stop_is_required() {
             self.do_one_iteration();
         }
+
+        self.final_snapshot();
+    }
+
+    /// Snapshot the game when stopping, so nothing played since the last one is lost
+    fn final_snapshot(&self) {
+        if let Some(path) = self.context.context.config().snapshot() {
+            info!("Final snapshot before stop");
+            self.persist(vec![Effect::Snapshot(path.clone())]);
+        }
     }
 
     pub fn do_one_iteration(&mut self) {
@@ -239,12 +250,63 @@ impl Runner {
         self.apply_effects(effects);
     }
 
-    fn apply_effects(&mut self, effects: Vec<Effect>) {
-        self.state_mut().apply(&effects);
+    fn apply_effects(&mut self, mut effects: Vec<Effect>) {
+        let rules = self.context.context.rules();
+        self.state_mut().apply(&effects, rules);
+        let consequences = consequences(&self.context, &effects);
+        let visibility_changed = {
+            let mut state = self.state_mut();
+            state.apply(&consequences, rules);
+            state.visibility_mut().take_changed()
+        };
+        effects.extend(consequences);
         self.reflects(&effects);
+        self.visibility_reflects(&visibility_changed);
+        self.persist(effects);
+    }
+
+    fn persist(&self, effects: Vec<Effect>) {
+        let mut state_effects = vec![];
+        let mut snapshots = vec![];
+        for effect in effects {
+            match effect {
+                Effect::State(effect) => state_effects.push(effect),
+                Effect::Snapshot(path) => snapshots.push(path),
+                Effect::Shines(_) => {}
+            }
+        }
+
+        // Snapshots are only written by the persister, never from the runner thread
+        let Some(persister) = &self.persister else {
+            for path in snapshots {
+                warn!("No persister: snapshot to {} ignored", path.display());
+            }
+            return;
+        };
+
+        // Snapshot (which includes these effects) is sent after so journal restarts after
+        let mut persists = vec![];
+        if !state_effects.is_empty() {
+            persists.push(Persist::Effects(state_effects));
+        }
+        for path in snapshots {
+            persists.push(Persist::Snapshot(path));
+        }
+        for persist in persists {
+            if persister.send_blocking(persist).is_err() {
+                error!("Persister channel closed: game is no longer persisted");
+            }
+        }
     }
 }
 
+/// Effects following given (applied) effects, which depend on all changes of the tick
+fn consequences(context: &RunnerContext, effects: &[Effect]) -> Vec<Effect> {
+    let mut consequences = refresh_cities(context, effects);
+    consequences.extend(deaths(context, effects));
+    consequences
+}
+
 fn tick_task(
     context: &RunnerContext,
     task: &TaskBox,
@@ -376,35 +438,50 @@ mod test {
     use async_std::channel::unbounded;
     use common::{
         game::{
-            city::CityProductionTons,
+            city::{
+                BuildingType, CityExploitation, CityPopulation, CityProduct, CityProduction,
+                CityProductionTons,
+            },
             nation::flag::Flag,
             server::ServerResume,
-            slice::ClientUnit,
+            slice::{ClientTile, ClientUnit},
             tasks::client::{settle::ClientSettle, ClientTask, ClientTaskType},
-            unit::{TaskType, UnitCan, UnitType},
-            GameFrame, PlayerId,
+            tech::{Tech, TechType},
+            unit::{CityTaskType, TaskType, UnitCan, UnitType},
+            GameFrame, PlayerId, PRODUCTION_FRAMES_PER_TONS,
         },
         geo::{ImaginaryWorldPoint, WorldPoint},
         network::message::{
             ClientStateMessage, ClientToServerEstablishmentMessage, ClientToServerInGameMessage,
             ClientToServerUnitMessage, ServerToClientEstablishmentMessage,
         },
-        rules::{RuleSet, RuleSetType},
+        rules::{
+            file::FileRuleSet, std1::Std1RuleSet, RuleSet, RuleSetBox, RuleSetType, TileYield,
+        },
         space::{
             window::{DisplayStep, Resolution, Window},
             D2Size,
         },
-        world::{slice::Slice, CtxTile, TerrainType, Tile},
+        world::{slice::Slice, CtxTile, Resource, TerrainType, Tile},
     };
 
     use crate::{
         config::ServerConfig,
         effect::{self},
+        game::task::growth::growth_task,
         game::{
             placer::{Placer, PlacerError},
             unit::Unit,
         },
         state::clients::Clients,
+        task::{
+            city::{
+                generator::{BuildCityFrom, BuildCityFromChange, CityGenerator},
+                production::{product_task, stored_tons},
+            },
+            Task, WithContext,
+        },
+        test::context::build_context,
     };
 
     use super::*;
@@ -419,7 +496,7 @@ mod test {
             unreachable!()
         }
 
-        fn unit_can(&self, _: &UnitType) -> Vec<TaskType> {
+        fn unit_can(&self, _: &UnitType, _: &[TechType]) -> Vec<TaskType> {
             unreachable!()
         }
 
@@ -431,12 +508,65 @@ mod test {
             true
         }
 
+        fn can_move(&self, _: &UnitType) -> bool {
+            true
+        }
+
+        fn can_enter(&self, _: &UnitType, _: &Tile) -> bool {
+            true
+        }
+
+        fn move_duration(&self, _: &UnitType, _: &Tile) -> GameFrame {
+            GameFrame(10)
+        }
+
+        fn river_move_duration(&self, _: &UnitType) -> GameFrame {
+            GameFrame(10)
+        }
+
+        fn tile_yield(&self, _: &Tile) -> TileYield {
+            TileYield::default()
+        }
+
+        fn resource_yield(&self, _: &Resource) -> TileYield {
+            TileYield::default()
+        }
+
+        fn default_product(&self) -> CityProduct {
+            CityProduct::Unit(UnitType::Warriors)
+        }
+
+        fn buildings(&self) -> Vec<BuildingType> {
+            vec![]
+        }
+
+        fn city_yield(&self, yield_: TileYield, _: &[BuildingType]) -> TileYield {
+            yield_
+        }
+
+        fn growth_kept_food(&self, _: u64, _: &[BuildingType]) -> u64 {
+            0
+        }
+
+        fn city_defence(&self, _: &[BuildingType]) -> u64 {
+            100
+        }
+
+        fn veteran_units(&self, _: &[BuildingType]) -> bool {
+            false
+        }
+
+        fn required_resources(&self, _: &CityProduct) -> Vec<Resource> {
+            vec![]
+        }
+
         fn required_tons(&self, product: &CityProduct) -> CityProductionTons {
             match product {
                 CityProduct::Unit(unit_type) => match unit_type {
                     UnitType::Settlers => CityProductionTons(40),
                     UnitType::Warriors => CityProductionTons(8),
                 },
+                CityProduct::Building(_) => CityProductionTons(10),
             }
         }
 
@@ -444,9 +574,86 @@ mod test {
             RuleSetType::Testing
         }
 
+        fn file(&self) -> FileRuleSet {
+            // Testing clients only need to agree with server about rules
+            FileRuleSet::std1()
+        }
+
         fn can_be_startup(&self, _tile: &common::world::Tile) -> bool {
             true
         }
+
+        fn growth_duration(&self) -> GameFrame {
+            GameFrame(100)
+        }
+
+        fn citizen_food(&self) -> u64 {
+            2
+        }
+
+        fn growth_food(&self, _: u64) -> u64 {
+            10
+        }
+
+        fn unit_sight(&self, _: &UnitType) -> u64 {
+            1
+        }
+
+        fn city_sight(&self) -> u64 {
+            2
+        }
+
+        fn city_work_radius(&self) -> u64 {
+            2
+        }
+
+        fn unit_attack(&self, _: &UnitType) -> u64 {
+            1
+        }
+
+        fn unit_defence(&self, _: &UnitType) -> u64 {
+            1
+        }
+
+        fn unit_hit_points(&self, _: &UnitType) -> u64 {
+            1
+        }
+
+        fn combat_round_duration(&self) -> GameFrame {
+            GameFrame(1)
+        }
+
+        fn techs(&self) -> Vec<Tech> {
+            vec![]
+        }
+
+        fn research_duration(&self) -> GameFrame {
+            GameFrame(1000)
+        }
+
+        fn city_trade(&self, _: &TileYield) -> u64 {
+            1
+        }
+
+        fn upkeep_duration(&self) -> GameFrame {
+            GameFrame(1000)
+        }
+
+        fn unit_upkeep(&self, _: &UnitType) -> u64 {
+            0
+        }
+
+        fn building_upkeep(&self, _: &BuildingType) -> u64 {
+            0
+        }
+
+        fn building_sale(&self, _: &BuildingType) -> u64 {
+            0
+        }
+
+        fn city_free_upkeep(&self) -> u64 {
+            0
+        }
     }
 
     #[derive(Debug, Clone)]
@@ -458,6 +665,7 @@ mod test {
             _rules: &'a common::rules::RuleSetBox,
             _state: &'a RwLockReadGuard<State>,
             _world: &'a RwLockReadGuard<WorldReader>,
+            _player_id: &'a PlayerId,
         ) -> Result<WorldPoint, PlacerError> {
             Ok(WorldPoint::new(0, 0))
         }
@@ -527,8 +735,9 @@ mod test {
                 ],
             );
 
+            let rules: RuleSetBox = Box::new(self.rule_set.clone());
             while let Some(unit) = self.units.pop() {
-                state.apply(&vec![effect::new_unit(unit)]);
+                state.apply(&vec![effect::new_unit(unit)], &rules);
             }
 
             let mut clients = HashMap::new();
@@ -542,8 +751,8 @@ mod test {
 
             *state.clients_mut() = Clients::new(HashMap::new());
 
-            let config = ServerConfig::new(None, GameFrame(0), "".to_string(), "".to_string());
-            let context = Context::new(Box::new(self.rule_set.clone()), config);
+            let config = ServerConfig::new(None, GameFrame(0), 1, "".to_string(), "".to_string());
+            let context = Context::new(rules, config);
             let state = Arc::new(RwLock::new(state));
 
             let context = RunnerContext::new(
@@ -585,7 +794,12 @@ mod test {
         let mut runner = context.build();
         let client = Client::new(client_id, player_id);
 
-        let take_place = ClientToServerEstablishmentMessage::TakePlace(flag, resolution).into();
+        let take_place = ClientToServerEstablishmentMessage::TakePlace(
+            flag,
+            resolution,
+            FileRuleSet::std1().fingerprint(),
+        )
+        .into();
 
         let expected_window_start = ImaginaryWorldPoint::new(-1, -1);
         let expected_window_end = ImaginaryWorldPoint::new(1, 1);
@@ -595,16 +809,17 @@ mod test {
             expected_window_end,
             expected_window_step,
         );
+        // Nothing seen yet when player takes place
         let expected_game_slice_tiles = vec![
             CtxTile::Outside,
             CtxTile::Outside,
             CtxTile::Outside,
             CtxTile::Outside,
-            CtxTile::Visible(Tile::new(TerrainType::GrassLand)),
-            CtxTile::Visible(Tile::new(TerrainType::GrassLand)),
+            CtxTile::Unknown,
+            CtxTile::Unknown,
             CtxTile::Outside,
-            CtxTile::Visible(Tile::new(TerrainType::GrassLand)),
-            CtxTile::Visible(Tile::new(TerrainType::GrassLand)),
+            CtxTile::Unknown,
+            CtxTile::Unknown,
         ];
         let expected_game_slice_world = Slice::new(
             expected_window_start,
@@ -613,17 +828,18 @@ mod test {
             expected_game_slice_tiles,
         );
 
-        let expected_server_resume = ServerResume::new(RuleSetType::Testing, vec![]);
+        let expected_server_resume = ServerResume::new(FileRuleSet::std1(), vec![]);
 
         // WHEN/THEN
         context.to_server(client, take_place);
         runner.do_one_iteration();
 
-        assert_eq!(context.to_clients_receiver.len(), 4);
+        assert_eq!(context.to_clients_receiver.len(), 5);
         let message1 = context.to_clients_receiver.try_recv();
         let message2 = context.to_clients_receiver.try_recv();
         let message3 = context.to_clients_receiver.try_recv();
         let message4 = context.to_clients_receiver.try_recv();
+        let message5 = context.to_clients_receiver.try_recv();
 
         assert_matches!(
             message1,
@@ -639,7 +855,7 @@ mod test {
             Ok((
                 _,
                 ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
-                    ClientStateMessage::SetGameSlice(_)
+                    ClientStateMessage::SetWindow(_)
                 ))
             ))
         );
@@ -647,18 +863,18 @@ mod test {
             message3,
             Ok((
                 _,
-                ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
-                    ClientStateMessage::SetWindow(_)
-                ))
+                ServerToClientMessage::Establishment(
+                    ServerToClientEstablishmentMessage::ServerResume(_, _)
+                )
             ))
         );
         assert_matches!(
             message4,
             Ok((
                 _,
-                ServerToClientMessage::Establishment(
-                    ServerToClientEstablishmentMessage::ServerResume(_, _)
-                )
+                ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
+                    ClientStateMessage::SetGameSlice(_)
+                ))
             ))
         );
 
@@ -673,7 +889,7 @@ mod test {
 
         let ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
             ClientStateMessage::SetGameSlice(game_slice),
-        )) = message2.unwrap().1
+        )) = message4.unwrap().1
         else {
             unreachable!()
         };
@@ -695,11 +911,35 @@ mod test {
                 3,
                 vec![None, None, None, None, None, None, None, None, None]
             )
-        ); // FIXME Unit will be added next in separated message ? it has been received ?
+        );
+
+        // Then tiles seen by the settler (except the one two steps away, hexagonal grid)
+        let ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
+            ClientStateMessage::SetTiles(mut tiles),
+        )) = message5.unwrap().1
+        else {
+            unreachable!()
+        };
+        tiles.sort_by_key(|tile| (tile.point().y, tile.point().x));
+        let grass_land = |point: WorldPoint, units: Vec<ClientUnit>| {
+            ClientTile::builder()
+                .point(point)
+                .tile(CtxTile::Visible(Tile::new(TerrainType::GrassLand)))
+                .units(units)
+                .build()
+        };
+        assert_eq!(
+            tiles,
+            vec![
+                grass_land(WorldPoint::new(0, 0), vec![set_unit.clone()]),
+                grass_land(WorldPoint::new(1, 0), vec![]),
+                grass_land(WorldPoint::new(0, 1), vec![]),
+            ]
+        );
 
         let ServerToClientMessage::InGame(ServerToClientInGameMessage::State(
             ClientStateMessage::SetWindow(set_window),
-        )) = message3.unwrap().1
+        )) = message2.unwrap().1
         else {
             unreachable!()
         };
@@ -708,11 +948,11 @@ mod test {
         let ServerToClientMessage::Establishment(ServerToClientEstablishmentMessage::ServerResume(
             set_server_resume,
             set_flag,
-        )) = message4.unwrap().1
+        )) = message3.unwrap().1
         else {
             unreachable!()
         };
-        assert_eq!(set_server_resume, expected_server_resume);
+        assert_eq!(*set_server_resume, expected_server_resume);
         assert_eq!(set_flag, Some(flag));
 
         // WHEN/THEN
@@ -740,7 +980,116 @@ mod test {
             ClientStateMessage::SetUnit(expected_client_unit),
         ));
 
-        let message5 = context.to_clients_receiver.try_recv();
-        assert_eq!(message5, Ok((client_id, expected_set_unit)));
+        let message6 = context.to_clients_receiver.try_recv();
+        assert_eq!(message6, Ok((client_id, expected_set_unit)));
+    }
+
+    #[test]
+    fn test_change_production_keeps_buildings() {
+        // GIVEN
+        let runner = TestingRunnerContext::new().build();
+        let mut city = crate::test::city::build_city(0);
+        city.add_building(BuildingType::Granary);
+        *city.exploitation_mut() =
+            CityExploitation::new(vec![WorldPoint::new(0, 0)], TileYield::new(2, 1, 0));
+        let production = CityProduction::new(vec![CityProduct::Unit(UnitType::Settlers)]);
+
+        // WHEN
+        let changed = CityGenerator::builder()
+            .context(&runner.context)
+            .game_frame(&GameFrame(0))
+            .from(BuildCityFrom::Change(
+                &city,
+                BuildCityFromChange::Production(production),
+            ))
+            .build()
+            .generate()
+            .unwrap();
+
+        // THEN
+        assert_eq!(changed.buildings(), &[BuildingType::Granary]);
+    }
+
+    #[test]
+    fn test_growth_and_production_end_on_same_frame() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let frame = GameFrame(PRODUCTION_FRAMES_PER_TONS * 4);
+        let mut city = crate::test::city::build_city(1);
+        let city_id = *city.id();
+        city.set_population(CityPopulation::new(1, rules.growth_food(1)));
+        *city.exploitation_mut() =
+            CityExploitation::new(vec![WorldPoint::new(1, 1)], TileYield::new(2, 2, 0));
+        *city.production_mut() = CityProduction::new(vec![
+            CityProduct::Unit(UnitType::Warriors),
+            CityProduct::Unit(UnitType::Settlers),
+        ]);
+        // Warriors (8 tons at 2 tons) are produced when the growth cycle ends
+        let production = product_task(
+            &rules,
+            &GameFrame(0),
+            &city_id,
+            city.production().current(),
+            CityProductionTons(0),
+            city.exploitation().production_tons(),
+        );
+        let growth = growth_task(
+            &rules,
+            &GameFrame(frame.0 - rules.growth_duration().0),
+            &city_id,
+        );
+        assert_eq!(production.context().end(), frame);
+        assert_eq!(growth.context().end(), frame);
+        city.tasks_mut().set_production(production.clone());
+        city.tasks_mut().set_growth(growth.clone());
+        let tasks: Vec<Box<dyn Task>> = vec![Box::new(production), Box::new(growth)];
+        let state = State::build_from(
+            frame,
+            D2Size::new(3, 3),
+            Clients::default(),
+            vec![city],
+            vec![],
+            &tasks,
+        );
+        let mut runner = Runner::builder()
+            .tick_base_period(9999)
+            .context(build_context(rules.clone(), state))
+            .build();
+
+        // WHEN
+        let due = runner
+            .state()
+            .tasks()
+            .due(frame)
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        let effects = due
+            .iter()
+            .flat_map(|task| tick_task(&runner.context, task, &frame).unwrap())
+            .collect();
+        runner.apply_effects(effects);
+
+        // THEN
+        let state = runner.state();
+        let city = state.find_city(&city_id).unwrap();
+        assert_eq!(city.population().size(), 2);
+        assert_eq!(city.exploitation().tiles().len(), 3);
+        assert_eq!(
+            city.production().current(),
+            &CityProduct::Unit(UnitType::Settlers)
+        );
+        assert_eq!(stored_tons(city, &frame), CityProductionTons(0));
+        assert_eq!(state.units_count(), 1);
+        let production_tasks = state
+            .tasks()
+            .iter()
+            .filter(|task| matches!(task.type_(), TaskType::City(CityTaskType::Production(_))))
+            .map(|task| *task.context().id())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            production_tasks,
+            vec![*city.tasks().production().context().id()]
+        );
     }
 }
@@ -1,9 +1,18 @@
-use common::{game::slice::GameSlice, space::window::Window};
+use common::{
+    game::{
+        nation::flag::Flag,
+        slice::{ClientTile, GameSlice},
+    },
+    geo::WorldPoint,
+    space::window::Window,
+    world::{slice::Slice, CtxTile, Tile},
+};
 
-use crate::runner::RunnerContext;
+use crate::{game::IntoClientModel, runner::RunnerContext, state::visibility::TileVisibility};
 
 impl RunnerContext {
-    pub fn game_slice(&self, window: &Window) -> GameSlice {
+    /// Game slice of window, as known by given flag
+    pub fn game_slice(&self, flag: &Flag, window: &Window) -> GameSlice {
         let state = self.state();
         let world = self
             .world
@@ -11,16 +20,79 @@ impl RunnerContext {
             .expect("Consider world as always readable");
 
         let tiles = world.slice(window);
-        let cities = state.client_cities_slice(window);
-        let units = state.client_units_slice(window);
+        let tiles = Slice::new(
+            *tiles.original(),
+            tiles.width(),
+            tiles.height(),
+            tiles
+                .items()
+                .iter()
+                .zip(state.visibility().window(flag, window))
+                .map(|(tile, visibility)| known_tile(tile, visibility))
+                .collect(),
+        );
+        let cities = state.client_cities_slice(flag, window);
+        let units = state.client_units_slice(flag, window);
 
         GameSlice::new(
             *window.start(),
             (window.end().x - window.start().x + 1) as u64,
             (window.end().y - window.start().y + 1) as u64,
-            tiles.clone(),
+            tiles,
             cities,
             units,
         )
     }
+
+    /// Given tiles, with their city and units, as known by given flag
+    pub fn client_tiles(&self, flag: &Flag, points: &[WorldPoint]) -> Vec<ClientTile> {
+        let state = self.state();
+        let world = self
+            .world
+            .read()
+            .expect("Consider world as always readable");
+
+        points
+            .iter()
+            .map(|point| {
+                let visibility = state.visibility().tile(flag, point);
+                let tile = world
+                    .tile(point.x, point.y)
+                    .map(|tile| CtxTile::Visible(tile.clone()))
+                    .unwrap_or(CtxTile::Outside);
+                let visible = visibility == TileVisibility::Visible;
+                let city = state
+                    .cities()
+                    .get_by_point(*point)
+                    .as_ref()
+                    .filter(|_| visible)
+                    .map(|city| city.as_ref().clone().into_client(&state));
+                let units = state
+                    .units()
+                    .get_by_point(*point)
+                    .iter()
+                    .flatten()
+                    .filter(|_| visible)
+                    .map(|unit| unit.clone().into_client(&state))
+                    .collect();
+
+                ClientTile::builder()
+                    .point(*point)
+                    .tile(known_tile(&tile, visibility))
+                    .maybe_city(city)
+                    .units(units)
+                    .build()
+            })
+            .collect()
+    }
+}
+
+/// World tile as known with given visibility
+fn known_tile(tile: &CtxTile<Tile>, visibility: TileVisibility) -> CtxTile<Tile> {
+    match (tile, visibility) {
+        (CtxTile::Visible(tile), TileVisibility::Visible) => CtxTile::Visible(tile.clone()),
+        (CtxTile::Visible(tile), TileVisibility::Explored) => CtxTile::Explored(tile.clone()),
+        (CtxTile::Visible(_), TileVisibility::Unknown) => CtxTile::Unknown,
+        (tile, _) => tile.clone(),
+    }
 }
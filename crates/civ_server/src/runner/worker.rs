@@ -7,6 +7,7 @@ use log::error;
 use crate::{
     effect::Effect,
     runner::{tick_task, RunnerContext},
+    task::TaskBox,
     utils::collection::slices,
 };
 
@@ -24,12 +25,13 @@ pub fn setup_task_workers(context: &RunnerContext) -> Vec<(Sender<()>, Receiver<
             while start_work_receiver.recv_blocking().is_ok() {
                 let state = context.state();
                 let frame = *state.frame();
-                let tasks = state.tasks();
+                // Only ticking and finished tasks produce effects
+                let tasks = state.tasks().due(frame);
                 deal(
                     &context,
                     workers_count,
-                    tasks,
-                    tick_task,
+                    &tasks,
+                    |context, task: &&TaskBox, frame| tick_task(context, task, frame),
                     frame,
                     i,
                     &results_sender_,
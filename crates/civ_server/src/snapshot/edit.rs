@@ -0,0 +1,364 @@
+//! Scripted edits of a game, applied on the state loaded from a snapshot (while the server
+//! is stopped)
+use common::{
+    game::{
+        city::{CityExploitation, CityId},
+        nation::flag::Flag,
+        unit::{UnitId, UnitType},
+        PlayerId,
+    },
+    geo::{Geo, GeoContext, WorldPoint},
+    rules::RuleSetBox,
+};
+use thiserror::Error;
+
+use crate::{
+    effect::{self, CityEffect, Effect, StateEffect},
+    game::unit::{Unit, UnitCanBuilder},
+    state::{State, StateError},
+    task::{
+        city::{
+            exploitation::{resize, taken},
+            generator::{BuildCityFrom, BuildCityFromChange},
+            production::{production_task, stored_tons},
+        },
+        Concern,
+    },
+    world::reader::WorldReader,
+};
+
+#[derive(Debug, Clone)]
+pub enum Edit {
+    /// Give a new unit of given type, at given point, to the player playing given flag
+    GiveUnit(Flag, UnitType, WorldPoint),
+    /// Move given city (and its exploited tiles, when they can still be worked) to given
+    /// point
+    MoveCity(CityId, WorldPoint),
+    /// Remove given player, with its cities, units and tasks
+    RemovePlayer(PlayerId),
+}
+
+#[derive(Debug, Error)]
+pub enum EditError {
+    #[error("No player plays {0}")]
+    UnknownFlag(Flag),
+    #[error("Unknown player {0}")]
+    UnknownPlayer(PlayerId),
+    #[error("Point {0:?} is outside the world")]
+    OutsideWorld(WorldPoint),
+    #[error("There is already a city at {0:?}")]
+    CityAlreadyAt(WorldPoint),
+    #[error("World is required to move a city")]
+    WorldRequired,
+    #[error("State error: {0}")]
+    State(#[from] StateError),
+}
+
+impl Edit {
+    /// Apply the edit. The world is only required to move a city, as its worked tiles
+    /// are chosen again.
+    pub fn apply(
+        &self,
+        state: &mut State,
+        rules: &RuleSetBox,
+        world: Option<&WorldReader>,
+    ) -> Result<(), EditError> {
+        match self {
+            Edit::GiveUnit(flag, type_, point) => give_unit(state, rules, flag, type_, point),
+            Edit::MoveCity(city_id, point) => move_city(
+                state,
+                rules,
+                world.ok_or(EditError::WorldRequired)?,
+                city_id,
+                point,
+            ),
+            Edit::RemovePlayer(player_id) => remove_player(state, rules, player_id),
+        }
+    }
+}
+
+fn give_unit(
+    state: &mut State,
+    rules: &RuleSetBox,
+    flag: &Flag,
+    type_: &UnitType,
+    point: &WorldPoint,
+) -> Result<(), EditError> {
+    if !state.clients().flags().contains(flag) {
+        return Err(EditError::UnknownFlag(*flag));
+    }
+    inside_world(state, point)?;
+
+    let unit = Unit::builder()
+        .id(UnitId::default())
+        .type_(*type_)
+        .geo(GeoContext::builder().point(*point).build())
+        .flag(*flag)
+        .can(UnitCanBuilder::new().build())
+        .build();
+    state.apply(&vec![effect::new_unit(unit)], rules);
+
+    Ok(())
+}
+
+fn move_city(
+    state: &mut State,
+    rules: &RuleSetBox,
+    world: &WorldReader,
+    city_id: &CityId,
+    point: &WorldPoint,
+) -> Result<(), EditError> {
+    inside_world(state, point)?;
+    if state.cities().get_by_point(*point).is_some() {
+        return Err(EditError::CityAlreadyAt(*point));
+    }
+
+    let city = state.find_city(city_id)?.clone();
+    let from = *city.geo().point();
+    // Exploited tiles keep their position relative to the city
+    let tiles = city
+        .exploitation()
+        .tiles()
+        .iter()
+        .filter_map(|tile| {
+            let x = (tile.x + point.x).checked_sub(from.x)?;
+            let y = (tile.y + point.y).checked_sub(from.y)?;
+            world.tile(x, y).map(|_| WorldPoint::new(x, y))
+        })
+        .collect();
+
+    // Removed first, so its previous tiles are not taken for the moved one
+    state.apply(
+        &vec![Effect::State(StateEffect::City(
+            *city_id,
+            CityEffect::Remove(Box::new(city.clone())),
+        ))],
+        rules,
+    );
+
+    // Tiles worked by other cities are released, and missing ones chosen again
+    let exploitation = resize(
+        rules,
+        world,
+        point,
+        city.buildings(),
+        &CityExploitation::new(tiles, *city.exploitation().yield_()),
+        &taken(rules, state, point),
+        city.population().size(),
+    );
+    let task = production_task(
+        rules,
+        state.frame(),
+        &BuildCityFrom::Change(
+            &city,
+            BuildCityFromChange::Exploitation(exploitation.clone()),
+        ),
+        city_id,
+        &exploitation,
+        city.production().current(),
+    );
+
+    let mut moved = city.clone();
+    moved.geo_mut().set_point(*point);
+    *moved.exploitation_mut() = exploitation;
+    moved
+        .production_mut()
+        .set_tons(stored_tons(&city, state.frame()));
+    moved.tasks_mut().set_production(task.clone());
+
+    state.apply(
+        &vec![
+            effect::remove_task(Box::new(city.tasks().production().clone())),
+            effect::add_task(Box::new(task)),
+            effect::new_city(moved),
+        ],
+        rules,
+    );
+
+    Ok(())
+}
+
+fn remove_player(
+    state: &mut State,
+    rules: &RuleSetBox,
+    player_id: &PlayerId,
+) -> Result<(), EditError> {
+    let flag = *state
+        .clients()
+        .player_state(player_id)
+        .ok_or(EditError::UnknownPlayer(*player_id))?
+        .flag();
+
+    let unit_ids = state
+        .index()
+        .flag_units()
+        .get(&flag)
+        .cloned()
+        .unwrap_or_default();
+    let city_ids = state
+        .index()
+        .flag_cities()
+        .get(&flag)
+        .cloned()
+        .unwrap_or_default();
+
+    let tasks = state
+        .tasks()
+        .iter()
+        .filter(|task| match task.concern() {
+            Concern::Unit(unit_id) => unit_ids.contains(&unit_id),
+            Concern::City(city_id) => city_ids.contains(&city_id),
+            Concern::Nothing => task.player() == Some(*player_id),
+        })
+        .map(|task| (*task.context().id(), task.concern()))
+        .collect();
+    let mut effects = vec![effect::remove_tasks(tasks)];
+    for unit_id in &unit_ids {
+        effects.push(effect::remove_unit(state.find_unit(unit_id)?.clone()));
+    }
+    for city_id in &city_ids {
+        let city = state.find_city(city_id)?.clone();
+        effects.push(Effect::State(StateEffect::City(
+            *city_id,
+            CityEffect::Remove(Box::new(city)),
+        )));
+    }
+
+    state.apply(&effects, rules);
+    state.clients_mut().remove_player(player_id);
+    state.visibility_mut().forget(&flag);
+
+    Ok(())
+}
+
+fn inside_world(state: &State, point: &WorldPoint) -> Result<(), EditError> {
+    let world_size = state.world_size();
+    if point.x as usize >= world_size.width() || point.y as usize >= world_size.height() {
+        return Err(EditError::OutsideWorld(*point));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use common::{
+        game::city::{CityPopulation, CityProduct, CityProduction, CityProductionTons},
+        network::{Client, ClientId},
+        rules::{std1::Std1RuleSet, TileYield},
+        space::{window::Window, D2Size},
+        world::{TerrainType, Tile},
+    };
+
+    use crate::{effect::ClientEffect, task::WithContext, test::city::build_city};
+
+    use super::*;
+
+    fn state_with_player(rules: &RuleSetBox) -> (State, PlayerId) {
+        let player_id = PlayerId::default();
+        let client = Client::new(ClientId::default(), player_id);
+        let mut state = State::empty(D2Size::new(10, 10));
+        state.apply(
+            &vec![Effect::State(StateEffect::Client(
+                client,
+                ClientEffect::PlayerTookPlace(Flag::Abkhazia, Window::default()),
+            ))],
+            rules,
+        );
+        (state, player_id)
+    }
+
+    #[test]
+    fn test_give_unit_then_remove_player() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let (mut state, player_id) = state_with_player(&rules);
+
+        // WHEN
+        Edit::GiveUnit(Flag::Abkhazia, UnitType::Warriors, WorldPoint::new(2, 3))
+            .apply(&mut state, &rules, None)
+            .unwrap();
+
+        // THEN
+        assert_eq!(state.units_count(), 1);
+        assert!(state
+            .units()
+            .get_by_point(WorldPoint::new(2, 3))
+            .as_ref()
+            .is_some_and(|units| units[0].flag() == &Flag::Abkhazia));
+
+        // WHEN
+        Edit::RemovePlayer(player_id)
+            .apply(&mut state, &rules, None)
+            .unwrap();
+
+        // THEN
+        assert_eq!(state.units_count(), 0);
+        assert_eq!(state.clients().players_count(), 0);
+        assert!(matches!(
+            Edit::GiveUnit(Flag::Abkhazia, UnitType::Warriors, WorldPoint::new(2, 3))
+                .apply(&mut state, &rules, None),
+            Err(EditError::UnknownFlag(Flag::Abkhazia))
+        ));
+    }
+
+    #[test]
+    fn test_move_city() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let (mut state, _) = state_with_player(&rules);
+        let world = WorldReader::new(
+            PathBuf::new(),
+            10,
+            10,
+            vec![Tile::new(TerrainType::GrassLand); 100],
+        );
+        let mut city = build_city(1);
+        city.set_population(CityPopulation::new(2, 0));
+        *city.exploitation_mut() = CityExploitation::new(
+            vec![
+                WorldPoint::new(1, 1),
+                WorldPoint::new(0, 1),
+                WorldPoint::new(2, 1),
+            ],
+            TileYield::new(6, 0, 0),
+        );
+        *city.production_mut() = CityProduction::new(vec![CityProduct::Unit(UnitType::Settlers)]);
+        city.production_mut().set_tons(CityProductionTons(3));
+        let city_id = *city.id();
+        let stored = stored_tons(&city, state.frame());
+        state.apply(&vec![effect::new_city(city)], &rules);
+
+        // WHEN
+        Edit::MoveCity(city_id, WorldPoint::new(9, 5))
+            .apply(&mut state, &rules, Some(&world))
+            .unwrap();
+
+        // THEN
+        assert!(state.cities().get_by_point(WorldPoint::new(1, 1)).is_none());
+        let city = state.find_city(&city_id).unwrap();
+        assert_eq!(city.geo().point(), &WorldPoint::new(9, 5));
+        // (10, 5) is outside the world, a new tile is worked instead
+        assert_eq!(city.exploitation().tiles().len(), 3);
+        assert!(city.exploitation().tiles().contains(&WorldPoint::new(8, 5)));
+        assert!(city
+            .exploitation()
+            .tiles()
+            .iter()
+            .all(|point| world.tile(point.x, point.y).is_some()));
+        assert_eq!(stored_tons(city, state.frame()), stored);
+        assert!(state
+            .tasks()
+            .iter()
+            .any(|task| task.context().id() == city.tasks().production().context().id()));
+        assert!(matches!(
+            Edit::MoveCity(city_id, WorldPoint::new(10, 0)).apply(&mut state, &rules, Some(&world)),
+            Err(EditError::OutsideWorld(_))
+        ));
+        assert!(matches!(
+            Edit::MoveCity(city_id, WorldPoint::new(5, 5)).apply(&mut state, &rules, None),
+            Err(EditError::WorldRequired)
+        ));
+    }
+}
@@ -0,0 +1,384 @@
+//! Snapshots of older format versions, upgraded on load from one version to the next one.
+//!
+//! Types of each older version are frozen here (suffixed by their version): they must never
+//! change, whatever becomes of the current ones. Types which didn't change since (ids, geo,
+//! window, etc.) are reused as is.
+use std::{collections::HashMap, fmt, path::PathBuf};
+
+use common::{
+    game::{
+        city::{CityExploitation, CityId, CityProduct, CityProduction, CityProductionTons},
+        nation::flag::Flag,
+        unit::{UnitCan, UnitId, UnitType},
+        GameFrame, PlayerId,
+    },
+    geo::{GeoContext, GeoVec},
+    rules::TileYield,
+    space::{window::Window, D2Size},
+    utils::Vec2d,
+};
+use serde::{
+    de::{
+        self, value::MapAccessDeserializer, DeserializeOwned, DeserializeSeed, IntoDeserializer,
+        MapAccess, Visitor,
+    },
+    Deserialize, Deserializer,
+};
+
+use crate::{
+    game::{
+        city::City,
+        task::{growth::CityGrowthTask, production::CityProductionTask, settle::Settle},
+        unit::Unit,
+    },
+    state::{clients::PlayerState, visibility::Visibility},
+    task::{city::CityTasks, snapshot::SnapshotTask, unit::UnitTaskWrapper, Task, TaskContext},
+};
+
+use super::{Snapshot, SnapshotError, SNAPSHOT_VERSION};
+
+/// Decode given snapshot content (header excluded) written with given format version, then
+/// upgrade it to the current version
+pub fn migrate(version: u32, data: &[u8]) -> Result<Snapshot, SnapshotError> {
+    match version {
+        0 => Ok(decode::<SnapshotV0>(data)?.into()),
+        SNAPSHOT_VERSION => decode(data),
+        version => Err(SnapshotError::UnknownVersion(version)),
+    }
+}
+
+fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, SnapshotError> {
+    bincode::deserialize(data).map_err(|e| SnapshotError::Serialize(e.to_string()))
+}
+
+/// Snapshot written before format versioning (no header, no journal, no visibility)
+#[derive(Deserialize)]
+pub struct SnapshotV0 {
+    frame_i: GameFrame,
+    world_size: D2Size,
+    tasks: Vec<TaskV0>,
+    cities: Vec2d<Box<CityV0>>,
+    cities_count: usize,
+    units: Vec2d<Vec<UnitV0>>,
+    units_count: usize,
+    client_states: HashMap<PlayerId, PlayerStateV0>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CityV0 {
+    id: CityId,
+    flag: Flag,
+    name: String,
+    geo: GeoContext,
+    production: CityProductionV0,
+    exploitation: CityExploitationV0,
+    tasks: CityTasksV0,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CityProductionV0 {
+    stack: Vec<CityProductV0>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub enum CityProductV0 {
+    Unit(UnitTypeV0),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CityExploitationV0 {
+    tons: CityProductionTons,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CityTasksV0 {
+    production: CityProductionTaskV0,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CityProductionTaskV0 {
+    context: TaskContext,
+    city: CityId,
+    tons: CityProductionTons,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UnitV0 {
+    id: UnitId,
+    flag: Flag,
+    type_: UnitTypeV0,
+    task: Option<UnitTaskWrapperV0>,
+    geo: GeoContext,
+    can: Vec<UnitCanV0>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum UnitTypeV0 {
+    Warriors,
+    Settlers,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum UnitCanV0 {
+    Settle,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub enum UnitTaskWrapperV0 {
+    Settle(SettleV0),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SettleV0 {
+    context: TaskContext,
+    geo: GeoContext,
+    settler: Box<UnitV0>,
+    city_name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SnapshotTaskV0 {
+    context: TaskContext,
+    snapshot_to: PathBuf,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlayerStateV0 {
+    flag: Flag,
+    window: Window,
+}
+
+/// Tasks as written by `typetag` (a map with the task type name as first entry, then the
+/// task fields keyed by their names)
+#[derive(Debug)]
+pub enum TaskV0 {
+    CityProduction(CityProductionTaskV0),
+    Settle(SettleV0),
+    Snapshot(SnapshotTaskV0),
+}
+
+const TASKS_V0: &[&str] = &["CityProductionTask", "Settle", "SnapshotTask"];
+
+impl<'de> Deserialize<'de> for TaskV0 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(TaskV0Visitor)
+    }
+}
+
+struct TaskV0Visitor;
+
+impl<'de> Visitor<'de> for TaskV0Visitor {
+    type Value = TaskV0;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a typetag task map")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        match map.next_key::<String>()? {
+            Some(key) if key == "type" => {}
+            _ => return Err(de::Error::missing_field("type")),
+        }
+        let name: String = map.next_value()?;
+        let fields = MapAccessDeserializer::new(StringKeys(map));
+
+        match name.as_str() {
+            "CityProductionTask" => {
+                CityProductionTaskV0::deserialize(fields).map(TaskV0::CityProduction)
+            }
+            "Settle" => SettleV0::deserialize(fields).map(TaskV0::Settle),
+            "SnapshotTask" => SnapshotTaskV0::deserialize(fields).map(TaskV0::Snapshot),
+            name => Err(de::Error::unknown_variant(name, TASKS_V0)),
+        }
+    }
+}
+
+/// Map giving its keys as strings, as the struct field identifiers can't be read from bincode
+struct StringKeys<A>(A);
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for StringKeys<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match self.0.next_key::<String>()? {
+            Some(key) => seed.deserialize(key.into_deserializer()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl From<SnapshotV0> for Snapshot {
+    fn from(value: SnapshotV0) -> Self {
+        let frame_i = value.frame_i;
+        let cities: Vec<City> = value
+            .cities
+            .iter()
+            .flatten()
+            .map(|city| city.clone().into_city(frame_i))
+            .collect();
+        let units: Vec<GeoVec<Unit>> = value
+            .units
+            .iter()
+            .flatten()
+            .filter_map(|units| {
+                let geo = units.first()?.geo;
+                Some(GeoVec::new(
+                    geo,
+                    units.iter().cloned().map(Unit::from).collect(),
+                ))
+            })
+            .collect();
+
+        // Cities had no growth: their first growth cycle is due at once, which resizes
+        // their exploitation and schedules next cycles according to current rules
+        let mut tasks: Vec<Box<dyn Task>> = value.tasks.into_iter().map(Into::into).collect();
+        tasks.extend(
+            cities
+                .iter()
+                .map(|city| Box::new(city.tasks().growth().clone()) as Box<dyn Task>),
+        );
+
+        Self {
+            frame_i,
+            world_size: value.world_size,
+            tasks,
+            cities: Vec2d::from(value.world_size, cities),
+            cities_count: value.cities_count,
+            units: Vec2d::from(value.world_size, units),
+            units_count: value.units_count,
+            client_states: value
+                .client_states
+                .into_iter()
+                .map(|(player_id, state)| (player_id, PlayerState::new(state.flag, state.window)))
+                .collect(),
+            // Rebuilt from cities and units once rules are known
+            visibility: Visibility::default(),
+            journal_sequence: 0,
+        }
+    }
+}
+
+impl CityV0 {
+    fn into_city(self, frame_i: GameFrame) -> City {
+        let growth = CityGrowthTask::builder()
+            .context(
+                TaskContext::builder()
+                    .id(Default::default())
+                    .start(frame_i)
+                    .end(frame_i)
+                    .build(),
+            )
+            .city(self.id)
+            .build();
+
+        City::builder()
+            .id(self.id)
+            .flag(self.flag)
+            .name(self.name)
+            .geo(self.geo)
+            .production(CityProduction::new(
+                self.production
+                    .stack
+                    .into_iter()
+                    .map(CityProduct::from)
+                    .collect(),
+            ))
+            .exploitation(CityExploitation::new(
+                vec![*self.geo.point()],
+                TileYield::new(0, self.exploitation.tons.0, 0),
+            ))
+            .tasks(CityTasks::new(self.tasks.production.into(), growth))
+            .build()
+    }
+}
+
+impl From<CityProductV0> for CityProduct {
+    fn from(value: CityProductV0) -> Self {
+        match value {
+            CityProductV0::Unit(type_) => CityProduct::Unit(type_.into()),
+        }
+    }
+}
+
+impl From<CityProductionTaskV0> for CityProductionTask {
+    fn from(value: CityProductionTaskV0) -> Self {
+        CityProductionTask::builder()
+            .context(value.context)
+            .city(value.city)
+            .tons(value.tons)
+            .build()
+    }
+}
+
+impl From<UnitV0> for Unit {
+    fn from(value: UnitV0) -> Self {
+        Unit::builder()
+            .id(value.id)
+            .flag(value.flag)
+            .type_(value.type_.into())
+            .maybe_task(value.task.map(Into::into))
+            .geo(value.geo)
+            .can(value.can.into_iter().map(Into::into).collect())
+            .build()
+    }
+}
+
+impl From<UnitTypeV0> for UnitType {
+    fn from(value: UnitTypeV0) -> Self {
+        match value {
+            UnitTypeV0::Warriors => UnitType::Warriors,
+            UnitTypeV0::Settlers => UnitType::Settlers,
+        }
+    }
+}
+
+impl From<UnitCanV0> for UnitCan {
+    fn from(value: UnitCanV0) -> Self {
+        match value {
+            UnitCanV0::Settle => UnitCan::Settle,
+        }
+    }
+}
+
+impl From<UnitTaskWrapperV0> for UnitTaskWrapper {
+    fn from(value: UnitTaskWrapperV0) -> Self {
+        match value {
+            UnitTaskWrapperV0::Settle(settle) => UnitTaskWrapper::Settle(settle.into()),
+        }
+    }
+}
+
+impl From<SettleV0> for Settle {
+    fn from(value: SettleV0) -> Self {
+        Settle::builder()
+            .context(value.context)
+            .geo(value.geo)
+            .settler(Box::new((*value.settler).into()))
+            .city_name(value.city_name)
+            .build()
+    }
+}
+
+impl From<TaskV0> for Box<dyn Task> {
+    fn from(value: TaskV0) -> Self {
+        match value {
+            TaskV0::CityProduction(task) => Box::new(CityProductionTask::from(task)),
+            TaskV0::Settle(task) => Box::new(Settle::from(task)),
+            TaskV0::Snapshot(task) => Box::new(SnapshotTask::new(task.context, task.snapshot_to)),
+        }
+    }
+}
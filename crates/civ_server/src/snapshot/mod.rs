@@ -0,0 +1,308 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use common::{
+    game::{GameFrame, PlayerId},
+    space::D2Size,
+    utils::Vec2d,
+};
+use migration::migrate;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    game::{city::City, unit::Unit},
+    state::{
+        clients::{Clients, PlayerState},
+        index::Index,
+        tasks::Tasks,
+        visibility::Visibility,
+        State,
+    },
+    task::Task,
+};
+
+pub mod edit;
+pub mod migration;
+pub mod rotation;
+pub mod summary;
+
+/// First bytes of snapshot files (the ones written before format versioning have none)
+const MAGIC: [u8; 8] = *b"CIVSNAPS";
+/// Current snapshot format version. Must be incremented on each change of the snapshot
+/// content (including `City`, `Unit`, etc.), with a migration from the previous version.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Written before the snapshot content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    magic: [u8; 8],
+    version: u32,
+    /// Fingerprint of the rules the game was played with
+    rules: u64,
+    world_size: D2Size,
+}
+
+impl SnapshotHeader {
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn rules(&self) -> u64 {
+        self.rules
+    }
+
+    pub fn world_size(&self) -> D2Size {
+        self.world_size
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    frame_i: GameFrame,
+    world_size: D2Size,
+    tasks: Vec<Box<dyn Task>>,
+    cities: Vec2d<Box<City>>,
+    cities_count: usize,
+    units: Vec2d<Vec<Unit>>,
+    units_count: usize,
+    client_states: HashMap<PlayerId, PlayerState>,
+    visibility: Visibility,
+    /// Sequence of the last journal entry included in this snapshot
+    journal_sequence: u64,
+}
+
+#[derive(Debug, Error, Clone)]
+pub enum SnapshotError {
+    #[error("Serialize/Deserialize error: {0}")]
+    Serialize(String),
+    #[error("I/O error: {0}")]
+    Io(io::ErrorKind),
+    #[error("Snapshot format version {0} is unknown (server one is {SNAPSHOT_VERSION})")]
+    UnknownVersion(u32),
+    #[error("Snapshot world size {0:?} differs from world one {1:?}")]
+    WorldSize(D2Size, D2Size),
+}
+
+impl Snapshot {
+    /// Write this snapshot, with its header, played with rules of given fingerprint. It is
+    /// written aside then renamed, so a crash while writing never leaves a partial file.
+    pub fn dump(&self, path: &Path, rules: u64) -> Result<(), SnapshotError> {
+        let data = self.encode(rules)?;
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        let temporary = PathBuf::from(temporary);
+
+        File::create(&temporary)
+            .and_then(|mut file| {
+                file.write_all(&data)?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&temporary, path))
+            .map_err(|e| SnapshotError::Io(e.kind()))
+    }
+
+    pub fn encode(&self, rules: u64) -> Result<Vec<u8>, SnapshotError> {
+        let header = SnapshotHeader {
+            magic: MAGIC,
+            version: SNAPSHOT_VERSION,
+            rules,
+            world_size: self.world_size,
+        };
+        bincode::serialize(&(header, self)).map_err(|e| SnapshotError::Serialize(e.to_string()))
+    }
+
+    /// Read snapshot written by any format version, with its header (if written with one)
+    pub fn load(path: &Path) -> Result<(Option<SnapshotHeader>, Self), SnapshotError> {
+        Self::decode(&fs::read(path).map_err(|e| SnapshotError::Io(e.kind()))?)
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Option<SnapshotHeader>, Self), SnapshotError> {
+        if !data.starts_with(&MAGIC) {
+            return Ok((None, migrate(0, data)?));
+        }
+
+        let mut data = data;
+        let header: SnapshotHeader = bincode::deserialize_from(&mut data)
+            .map_err(|e| SnapshotError::Serialize(e.to_string()))?;
+        let snapshot = migrate(header.version, data)?;
+        Ok((Some(header), snapshot))
+    }
+
+    pub fn frame_i(&self) -> GameFrame {
+        self.frame_i
+    }
+
+    pub fn world_size(&self) -> D2Size {
+        self.world_size
+    }
+
+    pub fn tasks(&self) -> &[Box<dyn Task>] {
+        &self.tasks
+    }
+
+    pub fn cities(&self) -> &Vec2d<Box<City>> {
+        &self.cities
+    }
+
+    pub fn units(&self) -> &Vec2d<Vec<Unit>> {
+        &self.units
+    }
+
+    pub fn client_states(&self) -> &HashMap<PlayerId, PlayerState> {
+        &self.client_states
+    }
+
+    pub fn journal_sequence(&self) -> u64 {
+        self.journal_sequence
+    }
+
+    pub fn set_journal_sequence(&mut self, journal_sequence: u64) {
+        self.journal_sequence = journal_sequence;
+    }
+}
+
+impl From<&State> for Snapshot {
+    fn from(value: &State) -> Self {
+        let tasks = value.tasks().iter().cloned().map(|bx| bx as _).collect();
+        Self {
+            frame_i: *value.frame(),
+            world_size: value.world_size(),
+            tasks,
+            cities: value.cities().clone(),
+            cities_count: value.cities_count(),
+            units: value.units().clone(),
+            units_count: value.units_count(),
+            client_states: value.clients().states().clone(),
+            visibility: value.visibility().clone(),
+            journal_sequence: 0,
+        }
+    }
+}
+
+impl TryFrom<&PathBuf> for Snapshot {
+    type Error = SnapshotError;
+
+    fn try_from(value: &PathBuf) -> Result<Self, Self::Error> {
+        Ok(Self::load(value)?.1)
+    }
+}
+
+impl From<Snapshot> for State {
+    fn from(value: Snapshot) -> Self {
+        let index = Index::from(&value);
+        let tasks: Tasks = value.tasks.iter().map(|bx| bx.boxed()).collect();
+        Self::new(
+            value.frame_i,
+            Clients::new(value.client_states),
+            vec![],
+            index,
+            tasks,
+            value.cities,
+            value.cities_count,
+            value.units,
+            value.units_count,
+            value.visibility,
+            value.world_size,
+            0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::{
+        game::{
+            city::{CityProduct, CityProductionTons},
+            unit::{CityTaskType, SystemTaskType, TaskType, UnitTaskType, UnitType},
+        },
+        geo::WorldPoint,
+    };
+    use rstest::rstest;
+
+    use crate::task::{unit::UnitTaskWrapper, WithContext};
+
+    use super::*;
+
+    /// Snapshots written by each format version, of a 1x1 world at frame 42 with a city, a
+    /// settler settling and a snapshot task
+    const V0: &[u8] = include_bytes!("../../fixtures/snapshot/v0.civ");
+    const V1: &[u8] = include_bytes!("../../fixtures/snapshot/v1.civ");
+
+    #[rstest]
+    #[case(V0, None, 0)]
+    #[case(V1, Some(1), 7)]
+    fn test_decode_fixture(
+        #[case] data: &[u8],
+        #[case] expected_version: Option<u32>,
+        #[case] expected_journal_sequence: u64,
+    ) {
+        // GIVEN/WHEN
+        let (header, snapshot) = Snapshot::decode(data).unwrap();
+
+        // THEN
+        assert_eq!(header.map(|header| header.version()), expected_version);
+        assert_eq!(snapshot.frame_i(), GameFrame(42));
+        assert_eq!(snapshot.world_size(), D2Size::new(1, 1));
+        assert_eq!(snapshot.cities_count, 1);
+        assert_eq!(snapshot.units_count, 1);
+        assert_eq!(snapshot.journal_sequence(), expected_journal_sequence);
+
+        let city = snapshot.cities().get(0).as_ref().unwrap();
+        assert_eq!(city.name(), "CityName");
+        assert_eq!(
+            city.production().current(),
+            &CityProduct::Unit(UnitType::Warriors)
+        );
+        assert_eq!(city.exploitation().tiles(), &[WorldPoint::new(0, 0)]);
+        assert_eq!(city.tasks().growth().context().end(), GameFrame(42));
+
+        let units = snapshot.units().get(0).as_ref().unwrap();
+        assert_eq!(units.len(), 1);
+        assert!(matches!(units[0].task(), Some(UnitTaskWrapper::Settle(_))));
+
+        let types: Vec<TaskType> = snapshot.tasks().iter().map(|task| task.type_()).collect();
+        assert_eq!(
+            types,
+            vec![
+                TaskType::City(CityTaskType::Production(CityProductionTons(1))),
+                TaskType::Unit(UnitTaskType::Settle),
+                TaskType::System(SystemTaskType::Snapshot),
+                TaskType::City(CityTaskType::Growth),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        // GIVEN
+        let snapshot = Snapshot::from(&State::empty(D2Size::new(2, 3)));
+
+        // WHEN
+        let (header, snapshot) = Snapshot::decode(&snapshot.encode(1234).unwrap()).unwrap();
+
+        // THEN
+        let header = header.unwrap();
+        assert_eq!(header.version(), SNAPSHOT_VERSION);
+        assert_eq!(header.rules(), 1234);
+        assert_eq!(header.world_size(), D2Size::new(2, 3));
+        assert_eq!(snapshot.world_size(), D2Size::new(2, 3));
+    }
+
+    #[test]
+    fn test_decode_unknown_version() {
+        // GIVEN
+        let mut data = V1.to_vec();
+        data[8..12].copy_from_slice(&999u32.to_le_bytes());
+
+        // WHEN
+        let result = Snapshot::decode(&data);
+
+        // THEN
+        assert!(matches!(result, Err(SnapshotError::UnknownVersion(999))));
+    }
+}
@@ -0,0 +1,185 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use common::game::GameFrame;
+use log::{info, warn};
+
+use super::{Snapshot, SnapshotError, SnapshotHeader};
+
+/// Snapshots of a game, written next to the configured snapshot path and named after it
+/// with their frame and write time (like `game.civ.000000012000-1760000000`). Only the
+/// `keep` last ones are kept.
+#[derive(Debug, Clone)]
+pub struct Rotation {
+    base: PathBuf,
+    keep: usize,
+}
+
+impl Rotation {
+    pub fn new(base: PathBuf, keep: usize) -> Self {
+        Self {
+            base,
+            keep: keep.max(1),
+        }
+    }
+
+    /// Path of a snapshot of given frame written now
+    pub fn path(&self, frame: GameFrame) -> PathBuf {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        let mut path = self.base.as_os_str().to_owned();
+        path.push(format!(".{:012}-{}", frame.0, timestamp));
+        PathBuf::from(path)
+    }
+
+    /// Existing snapshots, newest first. The configured path itself (written before
+    /// rotation) comes last.
+    pub fn paths(&self) -> Result<Vec<PathBuf>, SnapshotError> {
+        let directory = match self.base.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let Some(prefix) = self.base.file_name().and_then(|name| name.to_str()) else {
+            return Ok(vec![]);
+        };
+        let entries = match fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(error) => return Err(SnapshotError::Io(error.kind())),
+        };
+
+        let mut rotated = vec![];
+        for entry in entries {
+            let entry = entry.map_err(|e| SnapshotError::Io(e.kind()))?;
+            let name = entry.file_name();
+            if let Some(key) = name
+                .to_str()
+                .and_then(|name| name.strip_prefix(prefix))
+                .and_then(|suffix| suffix.strip_prefix('.'))
+                .and_then(rotation_key)
+            {
+                rotated.push((key, entry.path()));
+            }
+        }
+        rotated.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        let mut paths: Vec<PathBuf> = rotated.into_iter().map(|(_, path)| path).collect();
+        if self.base.is_file() {
+            paths.push(self.base.clone());
+        }
+        Ok(paths)
+    }
+
+    /// Write given snapshot (atomically) then remove the oldest ones
+    pub fn save(&self, snapshot: &Snapshot, rules: u64) -> Result<PathBuf, SnapshotError> {
+        let path = self.path(snapshot.frame_i());
+        snapshot.dump(&path, rules)?;
+
+        for old in self.paths()?.into_iter().skip(self.keep) {
+            info!("Remove old snapshot {}", old.display());
+            fs::remove_file(&old).map_err(|e| SnapshotError::Io(e.kind()))?;
+        }
+
+        Ok(path)
+    }
+
+    /// Load the newest snapshot which can be read, falling back to previous ones. Return
+    /// `None` when there is no snapshot at all, and the last error when none can be read.
+    pub fn load(&self) -> Result<Option<(Option<SnapshotHeader>, Snapshot)>, SnapshotError> {
+        let mut last_error = None;
+
+        for path in self.paths()? {
+            match Snapshot::load(&path) {
+                Ok(loaded) => {
+                    info!("Load snapshot {}", path.display());
+                    return Ok(Some(loaded));
+                }
+                Err(error) => {
+                    warn!(
+                        "Snapshot {} can't be loaded ({}), try the previous one",
+                        path.display(),
+                        error
+                    );
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        match last_error {
+            Some(error) => Err(error),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Frame and timestamp of a rotated snapshot file name suffix (`<frame>-<timestamp>`)
+fn rotation_key(suffix: &str) -> Option<(u64, u64)> {
+    let (frame, timestamp) = suffix.split_once('-')?;
+    if !frame
+        .bytes()
+        .chain(timestamp.bytes())
+        .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    Some((frame.parse().ok()?, timestamp.parse().ok()?))
+}
+
+#[cfg(test)]
+mod test {
+    use common::space::D2Size;
+    use rstest::rstest;
+
+    use crate::state::State;
+
+    use super::*;
+
+    fn snapshot(frame: u64) -> Snapshot {
+        let mut snapshot = Snapshot::from(&State::empty(D2Size::new(1, 1)));
+        snapshot.frame_i = GameFrame(frame);
+        snapshot
+    }
+
+    #[rstest]
+    #[case("000000000042-1760000000", Some((42, 1760000000)))]
+    #[case("journal", None)]
+    #[case("000000000042-1760000000.tmp", None)]
+    #[case("-", None)]
+    fn test_rotation_key(#[case] suffix: &str, #[case] expected: Option<(u64, u64)>) {
+        assert_eq!(rotation_key(suffix), expected);
+    }
+
+    #[test]
+    fn test_save_and_load_fallback() {
+        // GIVEN
+        let dir = std::env::temp_dir().join(format!("civ_rotation_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let rotation = Rotation::new(dir.join("game.civ"), 2);
+
+        // WHEN
+        rotation.save(&snapshot(10), 0).unwrap();
+        rotation.save(&snapshot(20), 0).unwrap();
+        let newest = rotation.save(&snapshot(30), 0).unwrap();
+
+        // THEN
+        let paths = rotation.paths().unwrap();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0], newest);
+        let (_, loaded) = rotation.load().unwrap().unwrap();
+        assert_eq!(loaded.frame_i(), GameFrame(30));
+
+        // WHEN
+        fs::write(&newest, b"CIVSNAPS broken").unwrap();
+
+        // THEN
+        let (_, loaded) = rotation.load().unwrap().unwrap();
+        assert_eq!(loaded.frame_i(), GameFrame(20));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use common::game::{GameFrame, PlayerId};
+
+use super::Snapshot;
+
+/// Summary stats of a snapshot, for operators
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    frame: GameFrame,
+    world_size: (usize, usize),
+    journal_sequence: u64,
+    /// Players with the flag they play, by flag name
+    players: Vec<(String, PlayerId)>,
+    /// Cities and units count by flag name
+    flags: BTreeMap<String, (usize, usize)>,
+    cities_count: usize,
+    units_count: usize,
+    /// Tasks count by task type name
+    tasks: BTreeMap<String, usize>,
+}
+
+impl From<&Snapshot> for Summary {
+    fn from(value: &Snapshot) -> Self {
+        let mut players: Vec<(String, PlayerId)> = value
+            .client_states
+            .iter()
+            .map(|(player_id, state)| (state.flag().to_string(), *player_id))
+            .collect();
+        players.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut flags: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+        for city in value.cities.iter().flatten() {
+            flags.entry(city.flag().to_string()).or_default().0 += 1;
+        }
+        for unit in value.units.iter().flatten().flatten() {
+            flags.entry(unit.flag().to_string()).or_default().1 += 1;
+        }
+
+        let mut tasks: BTreeMap<String, usize> = BTreeMap::new();
+        for task in &value.tasks {
+            *tasks.entry(task.type_().to_string()).or_default() += 1;
+        }
+
+        Self {
+            frame: value.frame_i,
+            world_size: (value.world_size.width(), value.world_size.height()),
+            journal_sequence: value.journal_sequence,
+            players,
+            flags,
+            cities_count: value.cities_count,
+            units_count: value.units_count,
+            tasks,
+        }
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Frame: {}", self.frame.0)?;
+        writeln!(f, "World: {}x{}", self.world_size.0, self.world_size.1)?;
+        writeln!(f, "Journal sequence: {}", self.journal_sequence)?;
+        writeln!(f, "Players ({}):", self.players.len())?;
+        for (flag, player_id) in &self.players {
+            writeln!(f, "  {} {}", flag, player_id)?;
+        }
+        writeln!(f, "Flags ({}):", self.flags.len())?;
+        for (flag, (cities, units)) in &self.flags {
+            writeln!(f, "  {}: {} cities, {} units", flag, cities, units)?;
+        }
+        writeln!(f, "Cities: {}", self.cities_count)?;
+        writeln!(f, "Units: {}", self.units_count)?;
+        writeln!(f, "Tasks ({}):", self.tasks.values().sum::<usize>())?;
+        for (type_, count) in &self.tasks {
+            writeln!(f, "  {}: {}", type_, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::{
+        rules::{std1::Std1RuleSet, RuleSetBox},
+        space::D2Size,
+    };
+
+    use crate::{effect, state::State, test::city::build_city};
+
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let mut state = State::empty(D2Size::new(4, 4));
+        state.apply(&vec![effect::new_city(build_city(1))], &rules);
+
+        // WHEN
+        let summary = Summary::from(&Snapshot::from(&state));
+
+        // THEN
+        assert_eq!(summary.cities_count, 1);
+        assert_eq!(summary.flags.get("Abkhazia"), Some(&(1, 0)));
+        assert!(summary.to_string().contains("Abkhazia: 1 cities, 0 units"));
+    }
+}
@@ -1,15 +1,21 @@
 use std::collections::HashMap;
 
 use common::{
-    game::{nation::flag::Flag, PlayerId},
+    game::{nation::flag::Flag, tech::Research, treasury::Treasury, PlayerId},
     geo::GeoContext,
     network::{Client, ClientId},
+    rules::RuleSetBox,
     space::window::Window,
 };
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::effect::{ClientEffect, ClientsEffect};
+use crate::{
+    effect::{ClientEffect, ClientsEffect, PlayerEffect},
+    game::task::research::next_research,
+};
+
+use super::visibility::Visibility;
 
 #[derive(Default)]
 pub struct Clients {
@@ -24,9 +30,28 @@ pub struct Index {
 }
 
 impl Index {
+    pub fn player_client(&self, player_id: &PlayerId) -> Option<&ClientId> {
+        self.player_client.get(player_id)
+    }
+
     fn insert(&mut self, client_id: ClientId, player_id: PlayerId) {
-        self.client_player.insert(client_id, player_id);
-        self.player_client.insert(player_id, client_id);
+        // Player can reconnect with a new client before its previous one is removed
+        if let Some(previous) = self.player_client.insert(player_id, client_id) {
+            if previous != client_id {
+                self.client_player.remove(&previous);
+            }
+        }
+        if let Some(previous) = self.client_player.insert(client_id, player_id) {
+            if previous != player_id {
+                self.player_client.remove(&previous);
+            }
+        }
+    }
+
+    fn remove(&mut self, client_id: &ClientId) {
+        if let Some(player_id) = self.client_player.remove(client_id) {
+            self.player_client.remove(&player_id);
+        }
     }
 }
 
@@ -46,6 +71,11 @@ impl Clients {
         }
     }
 
+    /// Forget connected clients (players states are kept)
+    pub fn forget_clients(&mut self) {
+        self.index = Index::default();
+    }
+
     pub fn clients_count(&self) -> usize {
         self.index.client_player.len()
     }
@@ -59,6 +89,9 @@ impl Clients {
             ClientsEffect::Insert(client_id, player_id) => {
                 self.index.insert(*client_id, *player_id);
             }
+            ClientsEffect::Remove(client_id) => {
+                self.index.remove(client_id);
+            }
         };
 
         Ok(())
@@ -85,6 +118,41 @@ impl Clients {
         Ok(())
     }
 
+    pub fn apply_player(
+        &mut self,
+        rules: &RuleSetBox,
+        player_id: &PlayerId,
+        effect: &PlayerEffect,
+    ) -> Result<(), ClientsError> {
+        let state = self
+            .states
+            .get_mut(player_id)
+            .ok_or(ClientsError::UnknownPlayer(*player_id))?;
+
+        match effect {
+            PlayerEffect::SetResearching(tech) => {
+                // Tech can have been learned during the tick
+                if !state.research().knows(tech) {
+                    state.research_mut().set_current(Some(*tech));
+                }
+            }
+            PlayerEffect::StoreScience(science) => {
+                state.set_research(next_research(rules, state.research(), *science));
+            }
+            PlayerEffect::SetTaxRate(tax_rate) => state.treasury_mut().set_tax_rate(*tax_rate),
+            PlayerEffect::AddGold(gold) => {
+                let gold = state.treasury().gold() + gold;
+                state.treasury_mut().set_gold(gold);
+            }
+            PlayerEffect::RemoveGold(gold) => {
+                let gold = state.treasury().gold().saturating_sub(*gold);
+                state.treasury_mut().set_gold(gold);
+            }
+        };
+
+        Ok(())
+    }
+
     pub fn concerned(&self, geo: &GeoContext) -> Vec<ClientId> {
         self.states
             .iter()
@@ -93,6 +161,16 @@ impl Clients {
             .collect()
     }
 
+    /// Clients concerned by given geo and which player can see it
+    pub fn seeing(&self, geo: &GeoContext, visibility: &Visibility) -> Vec<ClientId> {
+        self.states
+            .iter()
+            .filter(|(_, state)| state.window.contains(geo))
+            .filter(|(_, state)| visibility.is_visible(&state.flag, geo.point()))
+            .filter_map(|(player_id, _)| self.index.player_client.get(player_id).cloned())
+            .collect()
+    }
+
     pub fn player_client_ids(&self) -> Vec<ClientId> {
         // TODO: Can be reference ?
         self.index.player_client.values().copied().collect()
@@ -102,6 +180,22 @@ impl Clients {
         self.states.get(player_id)
     }
 
+    /// Forget given player (and its client, if connected)
+    pub fn remove_player(&mut self, player_id: &PlayerId) -> Option<PlayerState> {
+        if let Some(client_id) = self.index.player_client.get(player_id).copied() {
+            self.index.remove(&client_id);
+        }
+        self.states.remove(player_id)
+    }
+
+    /// Research of the player playing given flag
+    pub fn research(&self, flag: &Flag) -> Option<&Research> {
+        self.states
+            .values()
+            .find(|state| state.flag() == flag)
+            .map(|state| state.research())
+    }
+
     pub fn flags(&self) -> Vec<Flag> {
         self.states.values().map(|s| *s.flag()).collect()
     }
@@ -119,11 +213,20 @@ impl Clients {
 pub struct PlayerState {
     flag: Flag,
     window: Window,
+    #[serde(default)]
+    research: Research,
+    #[serde(default)]
+    treasury: Treasury,
 }
 
 impl PlayerState {
     pub fn new(flag: Flag, window: Window) -> Self {
-        Self { flag, window }
+        Self {
+            flag,
+            window,
+            research: Research::default(),
+            treasury: Treasury::default(),
+        }
     }
 
     pub fn flag(&self) -> &Flag {
@@ -137,4 +240,106 @@ impl PlayerState {
     pub fn set_window(&mut self, window: Window) {
         self.window = window;
     }
+
+    pub fn research(&self) -> &Research {
+        &self.research
+    }
+
+    pub fn research_mut(&mut self) -> &mut Research {
+        &mut self.research
+    }
+
+    pub fn set_research(&mut self, research: Research) {
+        self.research = research;
+    }
+
+    pub fn treasury(&self) -> &Treasury {
+        &self.treasury
+    }
+
+    pub fn treasury_mut(&mut self) -> &mut Treasury {
+        &mut self.treasury
+    }
+
+    pub fn set_treasury(&mut self, treasury: Treasury) {
+        self.treasury = treasury;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::{game::tech::TechType, rules::std1::Std1RuleSet};
+    use rstest::rstest;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn client_id(n: u128) -> ClientId {
+        ClientId(Uuid::from_u128(n))
+    }
+
+    fn player_id(n: u128) -> PlayerId {
+        PlayerId(Uuid::from_u128(n))
+    }
+
+    #[rstest]
+    #[case(vec![ClientsEffect::Insert(client_id(1), player_id(1))], vec![client_id(1)])]
+    #[case(vec![ClientsEffect::Insert(client_id(1), player_id(1)), ClientsEffect::Remove(client_id(1))], vec![])]
+    // Player reconnected with a new client, then its previous one disconnected
+    #[case(vec![ClientsEffect::Insert(client_id(1), player_id(1)), ClientsEffect::Insert(client_id(2), player_id(1)), ClientsEffect::Remove(client_id(1))], vec![client_id(2)])]
+    #[case(vec![ClientsEffect::Insert(client_id(1), player_id(1)), ClientsEffect::Remove(client_id(1)), ClientsEffect::Insert(client_id(1), player_id(1))], vec![client_id(1)])]
+    fn test_apply(#[case] effects: Vec<ClientsEffect>, #[case] expected: Vec<ClientId>) {
+        // GIVEN
+        let mut clients = Clients::default();
+
+        // WHEN
+        for effect in &effects {
+            clients.apply(effect).unwrap();
+        }
+
+        // THEN
+        assert_eq!(clients.player_client_ids(), expected);
+        assert_eq!(clients.clients_count(), expected.len());
+    }
+
+    #[rstest]
+    #[case(vec![PlayerEffect::SetResearching(TechType::Alphabet), PlayerEffect::StoreScience(5)])]
+    #[case(vec![PlayerEffect::StoreScience(5), PlayerEffect::SetResearching(TechType::Alphabet)])]
+    fn test_apply_player_research_choice_and_science(#[case] effects: Vec<PlayerEffect>) {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let state = PlayerState::new(Flag::Abkhazia, Window::default());
+        let mut clients = Clients::new(HashMap::from([(player_id(1), state)]));
+
+        // WHEN
+        for effect in &effects {
+            clients.apply_player(&rules, &player_id(1), effect).unwrap();
+        }
+
+        // THEN
+        let research = clients.player_state(&player_id(1)).unwrap().research();
+        assert_eq!(research.current(), Some(&TechType::Alphabet));
+        assert_eq!(research.science(), 5);
+    }
+
+    #[rstest]
+    #[case(vec![PlayerEffect::SetTaxRate(70), PlayerEffect::AddGold(5)], Treasury::new(15, 70))]
+    #[case(vec![PlayerEffect::RemoveGold(3), PlayerEffect::SetTaxRate(70)], Treasury::new(7, 70))]
+    #[case(vec![PlayerEffect::RemoveGold(30)], Treasury::new(0, 50))]
+    fn test_apply_player_treasury(#[case] effects: Vec<PlayerEffect>, #[case] expected: Treasury) {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let mut state = PlayerState::new(Flag::Abkhazia, Window::default());
+        state.set_treasury(Treasury::new(10, 50));
+        let mut clients = Clients::new(HashMap::from([(player_id(1), state)]));
+
+        // WHEN
+        for effect in &effects {
+            clients.apply_player(&rules, &player_id(1), effect).unwrap();
+        }
+
+        // THEN
+        let treasury = clients.player_state(&player_id(1)).unwrap().treasury();
+        assert_eq!(treasury, &expected);
+    }
 }
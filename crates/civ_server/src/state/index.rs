@@ -51,11 +51,16 @@ impl Index {
     }
 
     pub fn reindex_units_at(&mut self, geos: Vec<GeoContext>, units: &Vec2d<Vec<Unit>>) {
-        for geo in geos {
-            if let Some(units_) = units.get_by_point(*geo.point()) {
+        let indexes = geos.iter().map(|geo| units.index(*geo.point())).collect();
+        self.reindex_units_at_indexes(indexes, units);
+    }
+
+    fn reindex_units_at_indexes(&mut self, indexes: Vec<usize>, units: &Vec2d<Vec<Unit>>) {
+        for index in indexes {
+            if let Some(units_) = units.get(index) {
                 for (i, unit) in units_.iter().enumerate() {
                     self.units_index
-                        .insert(*unit.id(), UnitVec2dIndex(units.index(*geo.point()), i));
+                        .insert(*unit.id(), UnitVec2dIndex(index, i));
                 }
             }
         }
@@ -75,6 +80,7 @@ impl Index {
                     StateEffect::IncrementGameFrame => {}
                     StateEffect::Clients(_) => {}
                     StateEffect::Client(_, _) => {}
+                    StateEffect::Player(_, _) => {}
                     StateEffect::Tasks(effect) => match effect {
                         TasksEffect::Remove(tasks) => {
                             for (task_id, concern) in tasks {
@@ -105,32 +111,59 @@ impl Index {
                         CityEffect::Remove(city) => {
                             self.apply_remove_city(city, cities);
                         }
-                        CityEffect::Replace(_) => {
+                        CityEffect::Replace(city) => {
                             // Tasks already added/removed by TasksEffect
-                            // Nothing else to do
+                            self.apply_replace_city(city);
                         }
+                        // Neither position nor flag change
+                        CityEffect::SetPopulation(_)
+                        | CityEffect::SetProduction(_)
+                        | CityEffect::SetExploitation(_)
+                        | CityEffect::AddBuilding(_)
+                        | CityEffect::RemoveBuilding(_)
+                        | CityEffect::SetProductionTask(_)
+                        | CityEffect::SetGrowthTask(_) => {}
                     },
                     StateEffect::Unit(_, effect) => match effect {
                         UnitEffect::New(unit) => {
                             self.apply_new_unit(unit, units);
                         }
                         UnitEffect::Remove(unit) => {
-                            reindex_units_at.push(*unit.geo()); // Because State Vec2d of units changed
+                            // Unit no longer exist (State ignored this effect)
+                            let Some(previous) = self.units_index.get(unit.id()) else {
+                                continue;
+                            };
+                            // Because State Vec2d of units changed where the unit was
+                            // (which may not be the removed unit position if it moved)
+                            reindex_units_at.push(previous.0);
                             self.apply_remove_unit(unit, units);
                         }
                         UnitEffect::Replace(unit) => {
+                            // Unit no longer exist (State ignored this effect)
+                            let Some(previous) = self.units_index.get(unit.id()) else {
+                                continue;
+                            };
+                            // Unit moved, so State Vec2d of units changed at previous tile
+                            if previous.0 != units.index(*unit.geo().point()) {
+                                reindex_units_at.push(previous.0);
+                            }
+                            // Unit removed later in the tick, see UnitEffect::Remove
+                            if !is_at(unit, units) {
+                                continue;
+                            }
                             self.apply_replace_unit(unit, units);
                         }
+                        UnitEffect::Damage(_) => {}
                     },
                     StateEffect::Testing => {}
                 },
-                Effect::Shines(_) => {}
+                Effect::Shines(_) | Effect::Snapshot(_) => {}
             }
         }
 
         reindex_units_at.sort();
         reindex_units_at.dedup();
-        self.reindex_units_at(reindex_units_at, units);
+        self.reindex_units_at_indexes(reindex_units_at, units);
     }
 
     fn apply_new_task(&mut self, task: &TaskBox) {
@@ -175,6 +208,26 @@ impl Index {
         // self.city_tasks already updated by TaskEffect::Push
     }
 
+    /// Only flag can change (when city is captured)
+    fn apply_replace_city(&mut self, city: &City) {
+        let indexed = self
+            .flag_cities
+            .get(city.flag())
+            .is_some_and(|ids| ids.contains(city.id()));
+        if indexed {
+            return;
+        }
+
+        for ids in self.flag_cities.values_mut() {
+            ids.retain(|id| id != city.id());
+        }
+        self.flag_cities.retain(|_, ids| !ids.is_empty());
+        self.flag_cities
+            .entry(*city.flag())
+            .or_default()
+            .push(*city.id());
+    }
+
     fn apply_remove_city(&mut self, city: &City, _cities: &Vec2d<Box<City>>) {
         self.cities_index.remove(city.id());
         self.flag_cities
@@ -245,6 +298,18 @@ impl Index {
     pub fn flag_units(&self) -> &FxHashMap<Flag, Vec<UnitId>> {
         &self.flag_units
     }
+
+    pub fn flag_cities(&self) -> &FxHashMap<Flag, Vec<CityId>> {
+        &self.flag_cities
+    }
+}
+
+/// Given unit stands at its position in State units
+fn is_at(unit: &Unit, units: &Vec2d<Vec<Unit>>) -> bool {
+    units
+        .get_by_point(*unit.geo().point())
+        .as_ref()
+        .is_some_and(|units| units.iter().any(|u| u.id() == unit.id()))
 }
 
 impl From<&Snapshot> for Index {
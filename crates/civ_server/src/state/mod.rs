@@ -8,7 +8,7 @@ use common::{
         unit::{TaskType, UnitId},
         GameFrame, PlayerId,
     },
-    geo::{Geo, GeoVec},
+    geo::{Geo, GeoContext, GeoVec},
     network::{message::ClientToServerMessage, Client},
     rules::RuleSetBox,
     space::{window::Window, CityVec2dIndex, D2Size, UnitVec2dIndex},
@@ -17,19 +17,23 @@ use common::{
 };
 use derive_more::Constructor;
 use index::Index;
-use log::error;
+use log::warn;
+use tasks::Tasks;
 use thiserror::Error;
+use visibility::{TileVisibility, Visibility};
 
 use crate::{
     effect::{CityEffect, Effect, StateEffect, TaskEffect, TasksEffect, UnitEffect},
     game::{city::City, unit::Unit, IntoClientModel},
     snapshot::Snapshot,
-    task::{Task, TaskBox, TaskId},
+    task::{Task, TaskBox},
 };
 
 pub mod clients;
 pub mod flag;
 pub mod index;
+pub mod tasks;
+pub mod visibility;
 
 #[derive(Constructor)]
 pub struct State {
@@ -37,12 +41,13 @@ pub struct State {
     clients: Clients,
     pending: Vec<(Client, ClientToServerMessage)>,
     index: Index,
-    tasks: Vec<TaskBox>,
+    tasks: Tasks,
     // Don't store a Vec2d<Box<City>> to not allocate useless memory
     cities: Vec2d<Box<City>>,
     cities_count: usize,
     units: Vec2d<Vec<Unit>>,
     units_count: usize,
+    visibility: Visibility,
     world_size: D2Size,
     testing: u64,
 }
@@ -54,11 +59,12 @@ impl State {
             clients: Clients::default(),
             pending: Default::default(),
             index: Index::default(),
-            tasks: vec![],
+            tasks: Tasks::default(),
             cities: Vec2d::from(world_size, Vec::<City>::new()),
             cities_count: 0,
             units: Vec2d::from(world_size, Vec::<GeoVec<Unit>>::new()),
             units_count: 0,
+            visibility: Visibility::default(),
             world_size,
             testing: 0,
         }
@@ -77,7 +83,7 @@ impl State {
         let units_count = units.len();
         let units = Vec2d::from(world_size, units);
         let index = Index::build_from(&cities, &units, tasks);
-        let tasks: Vec<TaskBox> = tasks.iter().map(|bx| bx.boxed()).collect();
+        let tasks: Tasks = tasks.iter().map(|bx| bx.boxed()).collect();
 
         Self::new(
             frame_i,
@@ -89,6 +95,7 @@ impl State {
             cities_count,
             units,
             units_count,
+            Visibility::default(),
             world_size,
             0,
         )
@@ -98,11 +105,11 @@ impl State {
         &self.frame_i
     }
 
-    pub fn tasks(&self) -> &Vec<TaskBox> {
+    pub fn tasks(&self) -> &Tasks {
         &self.tasks
     }
 
-    pub fn tasks_mut(&mut self) -> &mut Vec<TaskBox> {
+    pub fn tasks_mut(&mut self) -> &mut Tasks {
         &mut self.tasks
     }
 
@@ -118,8 +125,9 @@ impl State {
         self.frame_i += GameFrame(1);
     }
 
-    pub fn apply(&mut self, effects: &Vec<Effect>) {
+    pub fn apply(&mut self, effects: &Vec<Effect>, rules: &RuleSetBox) {
         let mut remove_tasks = vec![];
+        let mut damages = vec![];
 
         for effect in effects {
             match effect {
@@ -133,6 +141,9 @@ impl State {
                     StateEffect::Client(client, effect) => {
                         self.clients.apply_client(client, effect).unwrap();
                     }
+                    StateEffect::Player(player_id, effect) => {
+                        self.clients.apply_player(rules, player_id, effect).unwrap();
+                    }
                     StateEffect::Task(uuid, effect) => match effect {
                         TaskEffect::Push(task) => self.tasks.push(task.clone()),
                         TaskEffect::Finished(_) => remove_tasks.push(uuid),
@@ -140,28 +151,97 @@ impl State {
                     },
                     StateEffect::Tasks(effect) => match effect {
                         TasksEffect::Remove(tasks) => {
-                            remove_tasks
-                                .extend(tasks.iter().map(|(i, _)| i).collect::<Vec<&TaskId>>());
+                            remove_tasks.extend(tasks.iter().map(|(i, _)| i));
                         }
                         TasksEffect::Add(tasks) => self.tasks.extend(tasks.clone()),
                     },
-                    StateEffect::City(_, effect) => match effect {
+                    StateEffect::City(city_id, effect) => match effect {
                         CityEffect::New(city) => {
-                            *self.cities.get_by_point_mut(*city.geo().point()) =
-                                Some(Box::new(city.clone()));
+                            *self.cities.get_by_point_mut(*city.geo().point()) = Some(city.clone());
                             self.cities_count += 1;
+                            self.visibility.see(
+                                city.flag(),
+                                city.geo().point(),
+                                rules.city_sight(),
+                                self.world_size,
+                            );
                         }
                         CityEffect::Replace(city) => {
-                            *self.find_city_mut(city.id()).unwrap() = city.clone();
+                            let previous = self.find_city_mut(city.id()).unwrap();
+                            let previous_flag = *previous.flag();
+                            *previous = (**city).clone();
+
+                            // City was captured: its sight changes of player
+                            if &previous_flag != city.flag() {
+                                self.visibility.unsee(
+                                    &previous_flag,
+                                    city.geo().point(),
+                                    rules.city_sight(),
+                                    self.world_size,
+                                );
+                                self.visibility.see(
+                                    city.flag(),
+                                    city.geo().point(),
+                                    rules.city_sight(),
+                                    self.world_size,
+                                );
+                            }
                         }
                         CityEffect::Remove(city) => {
                             *self.cities.get_by_point_mut(*city.geo().point()) = None;
                             self.cities_count -= 1;
+                            self.visibility.unsee(
+                                city.flag(),
+                                city.geo().point(),
+                                rules.city_sight(),
+                                self.world_size,
+                            );
+                        }
+                        CityEffect::SetPopulation(population) => {
+                            if let Some(city) = self.changed_city(city_id) {
+                                city.set_population(*population);
+                            }
+                        }
+                        CityEffect::SetProduction(production) => {
+                            if let Some(city) = self.changed_city(city_id) {
+                                *city.production_mut() = production.clone();
+                            }
+                        }
+                        CityEffect::SetExploitation(exploitation) => {
+                            if let Some(city) = self.changed_city(city_id) {
+                                *city.exploitation_mut() = exploitation.clone();
+                            }
+                        }
+                        CityEffect::AddBuilding(building) => {
+                            if let Some(city) = self.changed_city(city_id) {
+                                city.add_building(*building);
+                            }
+                        }
+                        CityEffect::RemoveBuilding(building) => {
+                            if let Some(city) = self.changed_city(city_id) {
+                                city.remove_building(building);
+                            }
+                        }
+                        CityEffect::SetProductionTask(task) => {
+                            if let Some(city) = self.changed_city(city_id) {
+                                city.tasks_mut().set_production(task.clone());
+                            }
+                        }
+                        CityEffect::SetGrowthTask(task) => {
+                            if let Some(city) = self.changed_city(city_id) {
+                                city.tasks_mut().set_growth(task.clone());
+                            }
                         }
                     },
                     StateEffect::Unit(unit_id, effect) => match effect {
                         UnitEffect::New(unit) => {
                             self.units_count += 1;
+                            self.visibility.see(
+                                unit.flag(),
+                                unit.geo().point(),
+                                rules.unit_sight(unit.type_()),
+                                self.world_size,
+                            );
 
                             if let Some(units) = self.units.get_by_point_mut(*unit.geo().point()) {
                                 units.push(unit.clone());
@@ -171,47 +251,123 @@ impl State {
                             }
                         }
                         UnitEffect::Remove(unit) => {
-                            self.units_count -= 1;
+                            // Several tasks (like attacks) can remove the same unit, which
+                            // may also have moved during the tick
+                            let Ok(point) = self.find_unit(unit_id).map(|u| *u.geo().point())
+                            else {
+                                warn!("Ignore removal of unit {} which no longer exist", unit_id);
+                                continue;
+                            };
 
-                            if let Some(units) = self.units.get_by_point_mut(*unit.geo().point()) {
+                            self.units_count -= 1;
+                            self.visibility.unsee(
+                                unit.flag(),
+                                &point,
+                                rules.unit_sight(unit.type_()),
+                                self.world_size,
+                            );
+
+                            if let Some(units) = self.units.get_by_point_mut(point) {
                                 units.retain(|u| u.id() != unit.id());
                                 if units.is_empty() {
-                                    *self.units.get_by_point_mut(*unit.geo().point()) = None;
+                                    *self.units.get_by_point_mut(point) = None;
                                 }
                             }
+                            // Following effects of the tick find units by the index
+                            self.index
+                                .reindex_units_at(vec![GeoContext::new(point)], &self.units);
                         }
                         UnitEffect::Replace(unit) => {
-                            *self.find_unit_mut(unit_id).unwrap() = unit.clone();
+                            let Ok(previous) = self.find_unit(unit_id).map(|u| *u.geo().point())
+                            else {
+                                warn!("Ignore replace of unit {} which no longer exist", unit_id);
+                                continue;
+                            };
+                            let point = *unit.geo().point();
+
+                            if previous == point {
+                                *self.find_unit_mut(unit_id).unwrap() = unit.clone();
+                            } else {
+                                // Unit moved: take it from its previous tile to the new one
+                                if let Some(units) = self.units.get_by_point_mut(previous) {
+                                    units.retain(|u| u.id() != unit.id());
+                                    if units.is_empty() {
+                                        *self.units.get_by_point_mut(previous) = None;
+                                    }
+                                }
+
+                                if let Some(units) = self.units.get_by_point_mut(point) {
+                                    units.push(unit.clone());
+                                } else {
+                                    *self.units.get_by_point_mut(point) = Some(vec![unit.clone()]);
+                                }
+
+                                let sight = rules.unit_sight(unit.type_());
+                                self.visibility
+                                    .see(unit.flag(), &point, sight, self.world_size);
+                                self.visibility.unsee(
+                                    unit.flag(),
+                                    &previous,
+                                    sight,
+                                    self.world_size,
+                                );
+                                // Following effects of the tick find units by the index
+                                self.index.reindex_units_at(
+                                    vec![GeoContext::new(previous), GeoContext::new(point)],
+                                    &self.units,
+                                );
+                            }
                         }
+                        UnitEffect::Damage(damage) => damages.push((*unit_id, *damage)),
                     },
                     StateEffect::Testing => {
                         self.testing += 1;
                     }
                 },
-                Effect::Shines(_) => {}
+                Effect::Shines(_) | Effect::Snapshot(_) => {}
             }
         }
 
-        if !remove_tasks.is_empty() {
-            // TODO: this is not a good performance way (idea: transport tasks index in tick)
-            self.tasks
-                .retain(|task| !remove_tasks.contains(&task.context().id()));
+        // Replaced units carry the damage they had when the tick started
+        for (unit_id, damage) in damages {
+            match self.find_unit_mut(&unit_id) {
+                Ok(unit) => unit.set_damage(unit.damage() + damage),
+                Err(_) => warn!("Ignore damage of unit {} which no longer exist", unit_id),
+            }
+        }
+
+        for task_id in remove_tasks {
+            self.tasks.remove(task_id);
         }
 
         // Update index must be after because based on &self.cities and &self.units
         self.index.apply(effects, &self.cities, &self.units);
     }
 
+    /// City changed by an effect, which may have been removed during the same tick
+    fn changed_city(&mut self, city_id: &CityId) -> Option<&mut City> {
+        let city = self.find_city_mut(city_id).ok();
+        if city.is_none() {
+            warn!("Ignore change of city {} which no longer exist", city_id);
+        }
+        city
+    }
+
     pub fn cities(&self) -> &Vec2d<Box<City>> {
         &self.cities
     }
 
-    pub fn client_cities_slice(&self, window: &Window) -> Slice<Option<ClientCity>> {
+    /// Cities of window which are visible by given flag
+    pub fn client_cities_slice(&self, flag: &Flag, window: &Window) -> Slice<Option<ClientCity>> {
         let cities = self
             .cities
             .slice(window)
             .into_iter()
-            .map(|c| c.map(|c| c.into_client(self)))
+            .zip(self.visibility.window(flag, window))
+            .map(|(c, visibility)| {
+                c.filter(|_| visibility == TileVisibility::Visible)
+                    .map(|c| c.into_client(self))
+            })
             .collect();
         Slice::new(
             *window.start(),
@@ -314,12 +470,21 @@ impl State {
         &self.units
     }
 
-    pub fn client_units_slice(&self, window: &Window) -> Slice<Option<Vec<ClientUnit>>> {
+    /// Units of window which are visible by given flag
+    pub fn client_units_slice(
+        &self,
+        flag: &Flag,
+        window: &Window,
+    ) -> Slice<Option<Vec<ClientUnit>>> {
         let units = self
             .units
             .slice(window)
             .into_iter()
-            .map(|u| u.map(|u| u.into_iter().map(|u| u.into_client(self)).collect()))
+            .zip(self.visibility.window(flag, window))
+            .map(|(u, visibility)| {
+                u.filter(|_| visibility == TileVisibility::Visible)
+                    .map(|u| u.into_iter().map(|u| u.into_client(self)).collect())
+            })
             .collect();
         Slice::new(
             *window.start(),
@@ -337,6 +502,14 @@ impl State {
         &self.index
     }
 
+    pub fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    pub fn visibility_mut(&mut self) -> &mut Visibility {
+        &mut self.visibility
+    }
+
     pub fn index_mut(&mut self) -> &mut Index {
         &mut self.index
     }
@@ -357,7 +530,7 @@ impl State {
 
     pub fn server_resume(&self, rules: &RuleSetBox) -> ServerResume {
         let flags = self.clients.flags();
-        ServerResume::new(rules.clone().into(), flags)
+        ServerResume::new(rules.file(), flags)
     }
 
     pub fn snapshot(&self) -> Snapshot {
@@ -366,11 +539,31 @@ impl State {
 
     /// Replace found task by given considering its type as differentiators.
     pub fn with_replaced_task_type(mut self, type_: TaskType, task: TaskBox) -> State {
-        self.tasks.retain(|t| t.type_() != type_);
+        self.tasks.remove_type(&type_);
         self.tasks.push(task);
         self
     }
 
+    /// Make each city and unit see around it (snapshots of the first format have no visibility)
+    pub fn see_around(&mut self, rules: &RuleSetBox) {
+        for city in self.cities.iter().flatten() {
+            self.visibility.see(
+                city.flag(),
+                city.geo().point(),
+                rules.city_sight(),
+                self.world_size,
+            );
+        }
+        for unit in self.units.iter().flatten().flatten() {
+            self.visibility.see(
+                unit.flag(),
+                unit.geo().point(),
+                rules.unit_sight(unit.type_()),
+                self.world_size,
+            );
+        }
+    }
+
     pub fn world_size(&self) -> D2Size {
         self.world_size
     }
@@ -423,6 +616,7 @@ mod test {
     use common::{
         game::unit::UnitType,
         geo::{GeoContext, ImaginaryWorldPoint, WorldPoint},
+        rules::std1::Std1RuleSet,
         space::window::DisplayStep,
     };
 
@@ -438,6 +632,115 @@ mod test {
             .build()
     }
 
+    #[test]
+    fn test_unit_replace_with_move() {
+        // Given
+        let size = D2Size::new(3, 3);
+        let geo = GeoContext::new(WorldPoint::new(1, 1));
+        let unit1 = build_unit(geo);
+        let unit2 = build_unit(geo);
+        let units = vec![GeoVec::new(geo, vec![unit1.clone(), unit2.clone()])];
+        let tasks = vec![];
+        let mut state = State::build_from(
+            GameFrame(0),
+            size,
+            Clients::default(),
+            vec![],
+            units,
+            &tasks,
+        );
+
+        // When
+        let mut moved = unit1.clone();
+        moved.geo_mut().set_point(WorldPoint::new(2, 2));
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        state.apply(&vec![crate::effect::replace_unit(moved)], &rules);
+
+        // Then
+        assert_eq!(
+            state.find_unit(unit1.id()).unwrap().geo().point(),
+            &WorldPoint::new(2, 2)
+        );
+        assert_eq!(
+            state.find_unit(unit2.id()).unwrap().geo().point(),
+            &WorldPoint::new(1, 1)
+        );
+        assert_eq!(
+            state
+                .units()
+                .get_by_point(WorldPoint::new(1, 1))
+                .as_ref()
+                .map(|u| u.len()),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_unit_remove_after_move() {
+        // Given
+        let size = D2Size::new(3, 3);
+        let geo = GeoContext::new(WorldPoint::new(1, 1));
+        let unit1 = build_unit(geo);
+        let unit2 = build_unit(geo);
+        let units = vec![GeoVec::new(geo, vec![unit1.clone(), unit2.clone()])];
+        let tasks = vec![];
+        let mut state = State::build_from(
+            GameFrame(0),
+            size,
+            Clients::default(),
+            vec![],
+            units,
+            &tasks,
+        );
+
+        // When (removal carries the unit as it was when the tick started)
+        let mut moved = unit1.clone();
+        moved.geo_mut().set_point(WorldPoint::new(2, 2));
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        state.apply(
+            &vec![
+                crate::effect::replace_unit(moved),
+                crate::effect::remove_unit(unit1.clone()),
+                crate::effect::damage_unit(*unit2.id(), 1),
+            ],
+            &rules,
+        );
+
+        // Then
+        assert!(state.find_unit(unit1.id()).is_err());
+        assert!(state.units().get_by_point(WorldPoint::new(2, 2)).is_none());
+        assert_eq!(state.find_unit(unit2.id()).unwrap().damage(), 1);
+    }
+
+    #[test]
+    fn test_see_around() {
+        // Given
+        let size = D2Size::new(9, 1);
+        let geo = GeoContext::new(WorldPoint::new(0, 0));
+        let unit = build_unit(geo);
+        let units = vec![GeoVec::new(geo, vec![unit.clone()])];
+        let tasks = vec![];
+        let mut state = State::build_from(
+            GameFrame(0),
+            size,
+            Clients::default(),
+            vec![],
+            units,
+            &tasks,
+        );
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let sight = rules.unit_sight(unit.type_());
+
+        // When
+        state.see_around(&rules);
+
+        // Then
+        let visibility = state.visibility();
+        assert!(visibility.is_visible(unit.flag(), &WorldPoint::new(sight, 0)));
+        assert!(!visibility.is_visible(unit.flag(), &WorldPoint::new(sight + 1, 0)));
+        assert!(!visibility.is_visible(&Flag::Albania, &WorldPoint::new(0, 0)));
+    }
+
     #[test]
     fn test_units_slice() {
         // Given
@@ -449,14 +752,17 @@ mod test {
         let unit = build_unit(unit1_geo);
         let units = vec![GeoVec::new(unit1_geo, vec![unit.clone()])];
         let tasks = vec![];
-        let state = State::build_from(frame, size, clients, cities, units, &tasks);
+        let mut state = State::build_from(frame, size, clients, cities, units, &tasks);
+        state
+            .visibility_mut()
+            .see(unit.flag(), unit1_geo.point(), 1, size);
         let unitc: ClientUnit = unit.into_client(&state);
 
         // When/Then
         let window_start = ImaginaryWorldPoint::new(0, 0);
         let window_end = ImaginaryWorldPoint::new(4, 4);
         let window = Window::new(window_start, window_end, DisplayStep::Close);
-        let slice = state.client_units_slice(&window);
+        let slice = state.client_units_slice(&Flag::Abkhazia, &window);
 
         assert_eq!(
             slice.items(),
@@ -498,7 +804,7 @@ mod test {
         let window_start = ImaginaryWorldPoint::new(0, 2);
         let window_end = ImaginaryWorldPoint::new(4, 4);
         let window = Window::new(window_start, window_end, DisplayStep::Close);
-        let slice = state.client_units_slice(&window);
+        let slice = state.client_units_slice(&Flag::Abkhazia, &window);
 
         assert_eq!(
             slice.items(),
@@ -528,7 +834,7 @@ mod test {
         let window_start = ImaginaryWorldPoint::new(2, 2);
         let window_end = ImaginaryWorldPoint::new(4, 4);
         let window = Window::new(window_start, window_end, DisplayStep::Close);
-        let slice = state.client_units_slice(&window);
+        let slice = state.client_units_slice(&Flag::Abkhazia, &window);
 
         assert_eq!(
             slice.items(),
@@ -547,5 +853,10 @@ mod test {
                 None,
             ],
         );
+
+        // When/Then: unit is not visible by other players
+        let slice = state.client_units_slice(&Flag::Afghanistan, &window);
+
+        assert!(slice.items().iter().all(|units| units.is_none()));
     }
 }
@@ -0,0 +1,161 @@
+use std::collections::BTreeSet;
+
+use common::game::{unit::TaskType, GameFrame};
+use rustc_hash::FxHashMap;
+use uuid::Uuid;
+
+use crate::task::{TaskBox, TaskId};
+
+/// Game tasks, indexed by end frame so only the ones finishing (or ticking at each frame)
+/// are dealt with at each tick
+#[derive(Default, Clone)]
+pub struct Tasks {
+    tasks: FxHashMap<TaskId, TaskBox>,
+    ends: BTreeSet<(GameFrame, TaskId)>,
+    /// Tasks which must tick at each frame, see [`crate::task::Task::ticks`]
+    ticking: BTreeSet<TaskId>,
+}
+
+impl Tasks {
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    pub fn get(&self, id: &TaskId) -> Option<&TaskBox> {
+        self.tasks.get(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TaskBox> {
+        self.tasks.values()
+    }
+
+    pub fn push(&mut self, task: TaskBox) {
+        let id = *task.context().id();
+        if let Some(previous) = self.tasks.remove(&id) {
+            self.unindex(&previous);
+        }
+
+        self.ends.insert((task.context().end(), id));
+        if task.ticks() {
+            self.ticking.insert(id);
+        }
+        self.tasks.insert(id, task);
+    }
+
+    pub fn remove(&mut self, id: &TaskId) -> Option<TaskBox> {
+        let task = self.tasks.remove(id)?;
+        self.unindex(&task);
+        Some(task)
+    }
+
+    pub fn remove_type(&mut self, type_: &TaskType) {
+        let ids = self
+            .tasks
+            .values()
+            .filter(|task| &task.type_() == type_)
+            .map(|task| *task.context().id())
+            .collect::<Vec<_>>();
+        for id in ids {
+            self.remove(&id);
+        }
+    }
+
+    /// Tasks to deal with at given frame: the ticking ones and the finished ones, in a
+    /// stable order (workers share them out by position)
+    pub fn due(&self, frame: GameFrame) -> Vec<&TaskBox> {
+        let finished = self
+            .ends
+            .range(..=(frame, TaskId(Uuid::from_u128(u128::MAX))))
+            .map(|(_, id)| id)
+            .filter(|id| !self.ticking.contains(id));
+
+        self.ticking
+            .iter()
+            .chain(finished)
+            .filter_map(|id| self.tasks.get(id))
+            .collect()
+    }
+
+    fn unindex(&mut self, task: &TaskBox) {
+        let id = *task.context().id();
+        self.ends.remove(&(task.context().end(), id));
+        self.ticking.remove(&id);
+    }
+}
+
+impl FromIterator<TaskBox> for Tasks {
+    fn from_iter<I: IntoIterator<Item = TaskBox>>(iter: I) -> Self {
+        let mut tasks = Self::default();
+        for task in iter {
+            tasks.push(task);
+        }
+        tasks
+    }
+}
+
+impl Extend<TaskBox> for Tasks {
+    fn extend<I: IntoIterator<Item = TaskBox>>(&mut self, iter: I) {
+        for task in iter {
+            self.push(task);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        task::TaskContext,
+        test::task::{FibonacciTask, IdleTask},
+    };
+
+    use super::*;
+
+    fn idle(id: u128, end: u64) -> TaskBox {
+        Box::new(IdleTask::new(context(id, end)))
+    }
+
+    fn fibonacci(id: u128, end: u64) -> TaskBox {
+        Box::new(FibonacciTask::new(context(id, end), 1))
+    }
+
+    fn context(id: u128, end: u64) -> TaskContext {
+        TaskContext::builder()
+            .id(TaskId(Uuid::from_u128(id)))
+            .start(GameFrame(0))
+            .end(GameFrame(end))
+            .build()
+    }
+
+    fn ids(tasks: Vec<&TaskBox>) -> Vec<u128> {
+        tasks
+            .into_iter()
+            .map(|task| task.context().id().0.as_u128())
+            .collect()
+    }
+
+    #[test]
+    fn test_due() {
+        // GIVEN
+        let mut tasks: Tasks = vec![idle(1, 10), idle(2, 5), fibonacci(3, 20), idle(4, 20)]
+            .into_iter()
+            .collect();
+
+        // WHEN/THEN
+        assert_eq!(ids(tasks.due(GameFrame(4))), vec![3]);
+        assert_eq!(ids(tasks.due(GameFrame(5))), vec![3, 2]);
+        assert_eq!(ids(tasks.due(GameFrame(15))), vec![3, 2, 1]);
+        assert_eq!(ids(tasks.due(GameFrame(20))), vec![3, 2, 1, 4]);
+
+        // WHEN
+        tasks.remove(&TaskId(Uuid::from_u128(2)));
+        tasks.remove(&TaskId(Uuid::from_u128(3)));
+
+        // THEN
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(ids(tasks.due(GameFrame(20))), vec![1, 4]);
+    }
+}
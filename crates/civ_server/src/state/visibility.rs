@@ -0,0 +1,210 @@
+//! Per player knowledge of the world. A tile is explored once seen by one of the player
+//! units or cities, and visible while at least one of them sees it.
+use std::collections::{HashMap, HashSet};
+
+use common::{game::nation::flag::Flag, geo::WorldPoint, space::window::Window, space::D2Size};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileVisibility {
+    /// Never seen
+    Unknown,
+    /// Seen before, but not currently
+    Explored,
+    Visible,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FlagVisibility {
+    explored: HashSet<WorldPoint>,
+    /// Count of units and cities currently seeing each tile
+    #[serde(with = "pairs")]
+    visible: HashMap<WorldPoint, u32>,
+}
+
+/// Map serialized as a sequence of pairs, so snapshots can be exported to formats which
+/// only accept string keys (like JSON). Bincode encodes both the same way.
+mod pairs {
+    use std::{collections::HashMap, hash::Hash};
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<K: Serialize, V: Serialize, S: Serializer>(
+        map: &HashMap<K, V>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<(K, V)>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Visibility {
+    flags: HashMap<Flag, FlagVisibility>,
+    /// Tiles which visibility changed since last [`Visibility::take_changed`], by flag
+    #[serde(skip)]
+    changed: HashMap<Flag, HashSet<WorldPoint>>,
+}
+
+impl Visibility {
+    /// Make tiles around `center` visible (and explored) for given flag
+    pub fn see(&mut self, flag: &Flag, center: &WorldPoint, radius: u64, world_size: D2Size) {
+        let visibility = self.flags.entry(*flag).or_default();
+
+        for point in area(center, radius, world_size) {
+            visibility.explored.insert(point);
+            let count = visibility.visible.entry(point).or_default();
+            if *count == 0 {
+                self.changed.entry(*flag).or_default().insert(point);
+            }
+            *count += 1;
+        }
+    }
+
+    /// Forget everything given flag saw (when its player is removed)
+    pub fn forget(&mut self, flag: &Flag) {
+        if let Some(visibility) = self.flags.remove(flag) {
+            self.changed
+                .entry(*flag)
+                .or_default()
+                .extend(visibility.explored);
+        }
+    }
+
+    /// Stop seeing tiles around `center` for given flag (they stay explored)
+    pub fn unsee(&mut self, flag: &Flag, center: &WorldPoint, radius: u64, world_size: D2Size) {
+        let Some(visibility) = self.flags.get_mut(flag) else {
+            return;
+        };
+
+        for point in area(center, radius, world_size) {
+            if let Some(count) = visibility.visible.get_mut(&point) {
+                *count = count.saturating_sub(1);
+                if *count == 0 {
+                    visibility.visible.remove(&point);
+                    self.changed.entry(*flag).or_default().insert(point);
+                }
+            }
+        }
+    }
+
+    pub fn tile(&self, flag: &Flag, point: &WorldPoint) -> TileVisibility {
+        let Some(visibility) = self.flags.get(flag) else {
+            return TileVisibility::Unknown;
+        };
+
+        if visibility.visible.contains_key(point) {
+            TileVisibility::Visible
+        } else if visibility.explored.contains(point) {
+            TileVisibility::Explored
+        } else {
+            TileVisibility::Unknown
+        }
+    }
+
+    pub fn is_visible(&self, flag: &Flag, point: &WorldPoint) -> bool {
+        self.tile(flag, point) == TileVisibility::Visible
+    }
+
+    /// Visibility of each window tile, row ordered (like window slices)
+    pub fn window(&self, flag: &Flag, window: &Window) -> Vec<TileVisibility> {
+        let mut tiles = Vec::with_capacity(window.shape() as usize);
+
+        for y in window.start().y..=window.end().y {
+            for x in window.start().x..=window.end().x {
+                if x < 0 || y < 0 {
+                    tiles.push(TileVisibility::Unknown);
+                } else {
+                    tiles.push(self.tile(flag, &WorldPoint::new(x as u64, y as u64)));
+                }
+            }
+        }
+
+        tiles
+    }
+
+    /// Tiles which visibility changed since previous call, by flag
+    pub fn take_changed(&mut self) -> HashMap<Flag, HashSet<WorldPoint>> {
+        std::mem::take(&mut self.changed)
+    }
+}
+
+/// Tiles at given radius (or less) around `center`, clipped to the world
+fn area(center: &WorldPoint, radius: u64, world_size: D2Size) -> impl Iterator<Item = WorldPoint> {
+    center.spiral(radius).into_iter().filter(move |point| {
+        point.x < world_size.width() as u64 && point.y < world_size.height() as u64
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_see_and_unsee() {
+        // Given
+        let size = D2Size::new(10, 10);
+        let flag = Flag::Abkhazia;
+        let mut visibility = Visibility::default();
+
+        // When
+        visibility.see(&flag, &WorldPoint::new(0, 0), 1, size);
+        visibility.see(&flag, &WorldPoint::new(2, 0), 1, size);
+
+        // Then
+        assert!(visibility.is_visible(&flag, &WorldPoint::new(1, 1)));
+        assert!(visibility.is_visible(&flag, &WorldPoint::new(3, 0)));
+        assert_eq!(
+            visibility.tile(&flag, &WorldPoint::new(4, 0)),
+            TileVisibility::Unknown
+        );
+        assert_eq!(
+            visibility.tile(&Flag::Afghanistan, &WorldPoint::new(0, 0)),
+            TileVisibility::Unknown
+        );
+        let changed = visibility.take_changed();
+        assert!(changed[&flag].contains(&WorldPoint::new(0, 0)));
+        assert!(changed[&flag].contains(&WorldPoint::new(3, 0)));
+        assert!(visibility.take_changed().is_empty());
+
+        // When
+        visibility.unsee(&flag, &WorldPoint::new(0, 0), 1, size);
+
+        // Then
+        assert_eq!(
+            visibility.tile(&flag, &WorldPoint::new(0, 0)),
+            TileVisibility::Explored
+        );
+        // Still seen from 2.0
+        assert!(visibility.is_visible(&flag, &WorldPoint::new(1, 1)));
+        let changed = visibility.take_changed();
+        assert!(changed[&flag].contains(&WorldPoint::new(0, 0)));
+        assert!(!changed[&flag].contains(&WorldPoint::new(1, 1)));
+    }
+
+    #[test]
+    fn test_see_again_does_not_change() {
+        // Given
+        let size = D2Size::new(10, 10);
+        let flag = Flag::Abkhazia;
+        let mut visibility = Visibility::default();
+        visibility.see(&flag, &WorldPoint::new(5, 5), 2, size);
+        visibility.take_changed();
+
+        // When
+        visibility.see(&flag, &WorldPoint::new(5, 5), 1, size);
+
+        // Then
+        assert!(visibility.take_changed().is_empty());
+    }
+}
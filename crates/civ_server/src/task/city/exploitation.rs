@@ -0,0 +1,279 @@
+use std::{cmp::Reverse, collections::HashSet};
+
+use common::{
+    game::city::{BuildingType, CityExploitation},
+    geo::WorldPoint,
+    rules::{RuleSetBox, TileYield},
+    task::CantExploitReason,
+    world::Resource,
+};
+
+use crate::{state::State, world::reader::WorldReader};
+
+/// Exploitation of city at `center` when its citizens work given tiles (city tile is
+/// always worked), with its buildings bonuses
+pub fn exploitation(
+    rules: &RuleSetBox,
+    world: &WorldReader,
+    center: &WorldPoint,
+    buildings: &[BuildingType],
+    tiles: Vec<WorldPoint>,
+) -> CityExploitation {
+    let tiles = std::iter::once(*center)
+        .chain(tiles.into_iter().filter(|point| point != center))
+        .collect::<Vec<_>>();
+    let yield_ = tiles
+        .iter()
+        .map(|point| tile_yield(rules, world, point))
+        .fold(TileYield::default(), |total, yield_| total + yield_);
+
+    CityExploitation::new(tiles, rules.city_yield(yield_, buildings))
+}
+
+/// Tiles worked by the cities around `center` (the one there excluded), which can't be
+/// worked by the city at `center`
+pub fn taken(rules: &RuleSetBox, state: &State, center: &WorldPoint) -> HashSet<WorldPoint> {
+    let size = state.world_size();
+    center
+        .spiral(rules.city_work_radius() * 2)
+        .into_iter()
+        .filter(|point| {
+            point != center
+                && (point.x as usize) < size.width()
+                && (point.y as usize) < size.height()
+        })
+        .filter_map(|point| state.cities().get_by_point(point).as_ref())
+        .flat_map(|city| city.exploitation().tiles().iter().copied())
+        .collect()
+}
+
+/// Exploitation for `size` citizens: currently worked tiles are kept (the worst are
+/// released when city shrinks) and new citizens work the best free tiles, `taken` ones
+/// excluded.
+pub fn resize(
+    rules: &RuleSetBox,
+    world: &WorldReader,
+    center: &WorldPoint,
+    buildings: &[BuildingType],
+    current: &CityExploitation,
+    taken: &HashSet<WorldPoint>,
+    size: u64,
+) -> CityExploitation {
+    let mut tiles = current
+        .tiles()
+        .iter()
+        .filter(|point| *point != center && !taken.contains(point))
+        .copied()
+        .collect::<Vec<_>>();
+    tiles.sort_by_key(|point| Reverse(rank(&tile_yield(rules, world, point))));
+    tiles.truncate(size as usize);
+
+    for candidate in candidates(rules, world, center) {
+        if tiles.len() >= size as usize {
+            break;
+        }
+        if !tiles.contains(&candidate) && !taken.contains(&candidate) {
+            tiles.push(candidate);
+        }
+    }
+
+    exploitation(rules, world, center, buildings, tiles)
+}
+
+/// Ensure tiles asked by a client can be worked by city at `center` (not `taken` ones)
+pub fn check(
+    rules: &RuleSetBox,
+    world: &WorldReader,
+    center: &WorldPoint,
+    size: u64,
+    taken: &HashSet<WorldPoint>,
+    tiles: &[WorldPoint],
+) -> Result<(), CantExploitReason> {
+    if tiles.len() as u64 > size {
+        return Err(CantExploitReason::NotEnoughCitizens(size));
+    }
+
+    let mut worked = HashSet::new();
+    for point in tiles {
+        let distance = center.distance(point);
+        if distance == 0 || distance > rules.city_work_radius() {
+            return Err(CantExploitReason::OutOfRadius(*point));
+        }
+        if world.tile(point.x, point.y).is_none() {
+            return Err(CantExploitReason::OutsideWorld(*point));
+        }
+        if !worked.insert(*point) {
+            return Err(CantExploitReason::AlreadyWorked(*point));
+        }
+        if taken.contains(point) {
+            return Err(CantExploitReason::WorkedByOtherCity(*point));
+        }
+    }
+
+    Ok(())
+}
+
+/// Resources of the tiles in work radius around `center` (city tile included), which can
+/// be required by city products
+pub fn resources(
+    rules: &RuleSetBox,
+    world: &WorldReader,
+    center: &WorldPoint,
+) -> HashSet<Resource> {
+    center
+        .spiral(rules.city_work_radius())
+        .into_iter()
+        .filter_map(|point| world.tile(point.x, point.y))
+        .filter_map(|tile| tile.resource())
+        .collect()
+}
+
+/// Tiles in work radius around `center` (city tile excluded), best first
+fn candidates(rules: &RuleSetBox, world: &WorldReader, center: &WorldPoint) -> Vec<WorldPoint> {
+    let mut candidates = center
+        .spiral(rules.city_work_radius())
+        .into_iter()
+        .filter(|point| point != center && world.tile(point.x, point.y).is_some())
+        .collect::<Vec<_>>();
+    // Stable sort: at equal yield, closest tiles first
+    candidates.sort_by_key(|point| Reverse(rank(&tile_yield(rules, world, point))));
+    candidates
+}
+
+fn tile_yield(rules: &RuleSetBox, world: &WorldReader, point: &WorldPoint) -> TileYield {
+    world
+        .tile(point.x, point.y)
+        .map(|tile| rules.tile_yield(tile))
+        .unwrap_or_default()
+}
+
+/// Food first, as citizens must be fed
+fn rank(yield_: &TileYield) -> (u64, u64, u64) {
+    (yield_.food, yield_.production, yield_.trade)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use common::{
+        game::GameFrame,
+        rules::std1::Std1RuleSet,
+        space::D2Size,
+        world::{TerrainType, Tile},
+    };
+    use rstest::rstest;
+
+    use crate::{state::clients::Clients, test::city::build_city};
+
+    use super::*;
+
+    /// 5x5 desert world with a grassland at (3, 2) and a plain at (1, 2)
+    fn world() -> WorldReader {
+        let mut tiles = vec![Tile::new(TerrainType::Desert); 25];
+        tiles[2 * 5 + 3] = Tile::new(TerrainType::GrassLand);
+        tiles[2 * 5 + 1] = Tile::new(TerrainType::Plain);
+        WorldReader::new(PathBuf::new(), 5, 5, tiles)
+    }
+
+    #[test]
+    fn test_resize() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let world = world();
+        let center = WorldPoint::new(2, 2);
+
+        // WHEN
+        let one = resize(
+            &rules,
+            &world,
+            &center,
+            &[],
+            &CityExploitation::default(),
+            &HashSet::new(),
+            1,
+        );
+        let two = resize(&rules, &world, &center, &[], &one, &HashSet::new(), 2);
+        let back = resize(&rules, &world, &center, &[], &two, &HashSet::new(), 1);
+
+        // THEN
+        assert_eq!(one.tiles(), &[center, WorldPoint::new(3, 2)]);
+        assert_eq!(one.yield_(), &TileYield::new(2, 1, 0));
+        assert_eq!(
+            two.tiles(),
+            &[center, WorldPoint::new(3, 2), WorldPoint::new(1, 2)]
+        );
+        assert_eq!(two.production_tons().0, 2);
+        assert_eq!(back, one);
+    }
+
+    #[rstest]
+    #[case(vec![WorldPoint::new(1, 2)], Ok(()))]
+    #[case(vec![WorldPoint::new(2, 3)], Ok(()))]
+    #[case(vec![WorldPoint::new(2, 2)], Err(CantExploitReason::OutOfRadius(WorldPoint::new(2, 2))))]
+    #[case(vec![WorldPoint::new(4, 4)], Err(CantExploitReason::OutOfRadius(WorldPoint::new(4, 4))))]
+    #[case(vec![WorldPoint::new(1, 2), WorldPoint::new(3, 2), WorldPoint::new(2, 1)], Err(CantExploitReason::NotEnoughCitizens(2)))]
+    #[case(vec![WorldPoint::new(1, 2), WorldPoint::new(1, 2)], Err(CantExploitReason::AlreadyWorked(WorldPoint::new(1, 2))))]
+    #[case(vec![WorldPoint::new(2, 0)], Err(CantExploitReason::WorkedByOtherCity(WorldPoint::new(2, 0))))]
+    fn test_check(#[case] tiles: Vec<WorldPoint>, #[case] expected: Result<(), CantExploitReason>) {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let world = world();
+
+        // WHEN
+        let result = check(
+            &rules,
+            &world,
+            &WorldPoint::new(2, 2),
+            2,
+            &HashSet::from([WorldPoint::new(2, 0)]),
+            &tiles,
+        );
+
+        // THEN
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_resize_skips_taken() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let world = world();
+        let center = WorldPoint::new(2, 2);
+        let current =
+            CityExploitation::new(vec![center, WorldPoint::new(3, 2)], TileYield::default());
+        let taken = HashSet::from([WorldPoint::new(3, 2)]);
+
+        // WHEN
+        let exploitation = resize(&rules, &world, &center, &[], &current, &taken, 1);
+
+        // THEN
+        assert_eq!(exploitation.tiles(), &[center, WorldPoint::new(1, 2)]);
+    }
+
+    #[test]
+    fn test_taken() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let mut city = build_city(0);
+        *city.exploitation_mut() = CityExploitation::new(
+            vec![WorldPoint::new(0, 0), WorldPoint::new(1, 1)],
+            TileYield::default(),
+        );
+        let state = State::build_from(
+            GameFrame(0),
+            D2Size::new(5, 5),
+            Clients::default(),
+            vec![city],
+            vec![],
+            &vec![],
+        );
+
+        // WHEN/THEN
+        assert_eq!(
+            taken(&rules, &state, &WorldPoint::new(2, 2)),
+            HashSet::from([WorldPoint::new(0, 0), WorldPoint::new(1, 1)])
+        );
+        assert!(taken(&rules, &state, &WorldPoint::new(0, 0)).is_empty());
+    }
+}
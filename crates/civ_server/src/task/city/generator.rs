@@ -1,7 +1,7 @@
 use bon::Builder;
 use common::{
     game::{
-        city::{CityExploitation, CityId, CityProduction, CityProductionTons},
+        city::{BuildingType, CityExploitation, CityId, CityPopulation, CityProduction},
         nation::flag::Flag,
         GameFrame,
     },
@@ -9,9 +9,19 @@ use common::{
 };
 
 use crate::{
-    game::city::City,
+    game::{
+        city::City,
+        task::growth::{growth_task, CityGrowthTask},
+    },
     runner::RunnerContext,
-    task::city::{production::production_task, CityTasks},
+    task::{
+        city::{
+            exploitation::{resize, taken},
+            production::{production_task, stored_tons},
+            CityTasks,
+        },
+        TaskContext, TaskId, WithContext,
+    },
 };
 
 use super::TaskError;
@@ -62,41 +72,106 @@ impl BuildCityFrom<'_> {
         }
     }
 
+    pub fn population(&self) -> Option<&CityPopulation> {
+        match self {
+            BuildCityFrom::Scratch(_, _, _) => None,
+            BuildCityFrom::Change(city, _) => Some(city.population()),
+        }
+    }
+
     pub fn production(&self) -> Option<&CityProduction> {
         match self {
             BuildCityFrom::Scratch(_, _, _) => None,
+            BuildCityFrom::Change(_, BuildCityFromChange::Production(production)) => {
+                Some(production)
+            }
             BuildCityFrom::Change(city, _) => Some(city.production()),
         }
     }
+
+    pub fn buildings(&self) -> &[BuildingType] {
+        match self {
+            BuildCityFrom::Scratch(_, _, _) => &[],
+            BuildCityFrom::Change(city, _) => city.buildings(),
+        }
+    }
 }
 
 impl CityGenerator<'_> {
     pub fn generate(&self) -> Result<City, TaskError> {
         let default_production = self.context.default_production();
         let city_id = self.from.id().copied().unwrap_or(CityId::default());
-        let tasks = CityTasks::new(production_task(
-            self.context.context.rules(),
-            self.game_frame,
-            &self.from,
-            &city_id,
-            default_production.current(),
-        ));
-        // TODO: tons according to exploitation (according to geo ...)
-        let exploitation = CityExploitation::new(CityProductionTons(1));
+        let rules = self.context.context.rules();
+        let growth = match &self.from {
+            BuildCityFrom::Scratch(_, _, _) => growth_task(rules, self.game_frame, &city_id),
+            // Changes don't interrupt the current growth cycle (but replace its task)
+            BuildCityFrom::Change(city, _) => {
+                let current = city.tasks().growth().context();
+                CityGrowthTask::builder()
+                    .context(
+                        TaskContext::builder()
+                            .id(TaskId::default())
+                            .start(current.start())
+                            .end(current.end())
+                            .build(),
+                    )
+                    .city(city_id)
+                    .build()
+            }
+        };
+        let population = self.from.population().copied().unwrap_or_default();
+        let exploitation = match &self.from {
+            BuildCityFrom::Scratch(_, _, geo) => {
+                let world = self
+                    .context
+                    .world
+                    .read()
+                    .expect("Consider world as always readable");
+                resize(
+                    rules,
+                    &world,
+                    geo.point(),
+                    self.from.buildings(),
+                    &CityExploitation::default(),
+                    &taken(rules, &self.context.state(), geo.point()),
+                    population.size(),
+                )
+            }
+            BuildCityFrom::Change(_, BuildCityFromChange::Exploitation(exploitation)) => {
+                exploitation.clone()
+            }
+            BuildCityFrom::Change(city, _) => city.exploitation().clone(),
+        };
+        let tasks = CityTasks::new(
+            production_task(
+                rules,
+                self.game_frame,
+                &self.from,
+                &city_id,
+                &exploitation,
+                default_production.current(),
+            ),
+            growth,
+        );
+        let mut production = self
+            .from
+            .production()
+            .unwrap_or(&default_production)
+            .clone();
+        if let BuildCityFrom::Change(city, _) = &self.from {
+            production.set_tons(stored_tons(city, self.game_frame));
+        }
 
         Ok(City::builder()
             .id(city_id)
             .name(self.from.name().to_string())
             .flag(*self.from.flag())
             .geo(*self.from.geo())
-            .production(
-                self.from
-                    .production()
-                    .unwrap_or(&self.context.default_production())
-                    .clone(),
-            )
+            .production(production)
             .tasks(tasks)
             .exploitation(exploitation)
+            .population(population)
+            .buildings(self.from.buildings().to_vec())
             .build())
     }
 }
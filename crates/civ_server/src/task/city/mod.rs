@@ -2,27 +2,46 @@ use bon::Builder;
 use common::game::slice::ClientCityTasks;
 use serde::{Deserialize, Serialize};
 
-use crate::game::task::production::CityProductionTask;
+use crate::game::task::{growth::CityGrowthTask, production::CityProductionTask};
 
 use super::{TaskBox, TaskContext, TaskError, TaskId};
 
+pub mod exploitation;
 pub mod generator;
 pub mod production;
+pub mod refresh;
 
 #[derive(Debug, Builder, Clone, Serialize, Deserialize)]
 pub struct CityTasks {
     production: CityProductionTask,
+    growth: CityGrowthTask,
 }
 
 impl CityTasks {
-    pub fn new(production: CityProductionTask) -> Self {
-        Self { production }
+    pub fn new(production: CityProductionTask, growth: CityGrowthTask) -> Self {
+        Self { production, growth }
+    }
+
+    pub fn production(&self) -> &CityProductionTask {
+        &self.production
+    }
+
+    pub fn set_production(&mut self, production: CityProductionTask) {
+        self.production = production;
+    }
+
+    pub fn growth(&self) -> &CityGrowthTask {
+        &self.growth
+    }
+
+    pub fn set_growth(&mut self, growth: CityGrowthTask) {
+        self.growth = growth;
     }
 }
 
 impl From<CityTasks> for Vec<TaskBox> {
     fn from(value: CityTasks) -> Self {
-        vec![Box::new(value.production)]
+        vec![Box::new(value.production), Box::new(value.growth)]
     }
 }
 
@@ -36,12 +55,14 @@ impl From<CityTasks> for ClientCityTasks {
 mod test {
     use common::{
         game::{
-            city::CityProduct,
+            city::{BuildingType, CityProduct},
+            tech::{Tech, TechType},
             unit::{TaskType, UnitType},
             PRODUCTION_FRAMES_PER_TONS,
         },
         geo::{GeoContext, WorldPoint},
-        rules::{RuleSet, RuleSetType},
+        rules::{file::FileRuleSet, RuleSet, RuleSetType, TileYield},
+        world::{Resource, Tile},
     };
     use common::{
         game::{
@@ -60,7 +81,7 @@ mod test {
     use crate::task::{
         city::{
             generator::{BuildCityFrom, BuildCityFromChange},
-            production::production_task,
+            production::{production_task, stored_tons},
         },
         Concern, Task,
     };
@@ -75,7 +96,7 @@ mod test {
             unreachable!()
         }
 
-        fn unit_can(&self, _: &UnitType) -> Vec<TaskType> {
+        fn unit_can(&self, _: &UnitType, _: &[TechType]) -> Vec<TaskType> {
             unreachable!()
         }
 
@@ -87,12 +108,65 @@ mod test {
             unreachable!()
         }
 
+        fn can_move(&self, _: &UnitType) -> bool {
+            unreachable!()
+        }
+
+        fn can_enter(&self, _: &UnitType, _: &Tile) -> bool {
+            unreachable!()
+        }
+
+        fn move_duration(&self, _: &UnitType, _: &Tile) -> GameFrame {
+            unreachable!()
+        }
+
+        fn river_move_duration(&self, _: &UnitType) -> GameFrame {
+            unreachable!()
+        }
+
+        fn tile_yield(&self, _: &Tile) -> TileYield {
+            unreachable!()
+        }
+
+        fn resource_yield(&self, _: &Resource) -> TileYield {
+            unreachable!()
+        }
+
+        fn default_product(&self) -> CityProduct {
+            unreachable!()
+        }
+
+        fn buildings(&self) -> Vec<BuildingType> {
+            unreachable!()
+        }
+
+        fn city_yield(&self, _yield_: TileYield, _: &[BuildingType]) -> TileYield {
+            unreachable!()
+        }
+
+        fn growth_kept_food(&self, _: u64, _: &[BuildingType]) -> u64 {
+            unreachable!()
+        }
+
+        fn city_defence(&self, _: &[BuildingType]) -> u64 {
+            unreachable!()
+        }
+
+        fn veteran_units(&self, _: &[BuildingType]) -> bool {
+            unreachable!()
+        }
+
+        fn required_resources(&self, _: &CityProduct) -> Vec<Resource> {
+            unreachable!()
+        }
+
         fn required_tons(&self, product: &CityProduct) -> CityProductionTons {
             match product {
                 CityProduct::Unit(unit_type) => match unit_type {
                     UnitType::Settlers => CityProductionTons(40),
                     UnitType::Warriors => CityProductionTons(8),
                 },
+                CityProduct::Building(_) => unreachable!(),
             }
         }
 
@@ -100,9 +174,102 @@ mod test {
             RuleSetType::Testing
         }
 
+        fn file(&self) -> FileRuleSet {
+            unreachable!()
+        }
+
         fn can_be_startup(&self, _tile: &common::world::Tile) -> bool {
             true
         }
+
+        fn growth_duration(&self) -> GameFrame {
+            unreachable!()
+        }
+
+        fn citizen_food(&self) -> u64 {
+            unreachable!()
+        }
+
+        fn growth_food(&self, _: u64) -> u64 {
+            unreachable!()
+        }
+
+        fn unit_sight(&self, _: &UnitType) -> u64 {
+            unreachable!()
+        }
+
+        fn city_sight(&self) -> u64 {
+            unreachable!()
+        }
+
+        fn city_work_radius(&self) -> u64 {
+            unreachable!()
+        }
+
+        fn unit_attack(&self, _: &UnitType) -> u64 {
+            unreachable!()
+        }
+
+        fn unit_defence(&self, _: &UnitType) -> u64 {
+            unreachable!()
+        }
+
+        fn unit_hit_points(&self, _: &UnitType) -> u64 {
+            unreachable!()
+        }
+
+        fn combat_round_duration(&self) -> GameFrame {
+            unreachable!()
+        }
+
+        fn techs(&self) -> Vec<Tech> {
+            unreachable!()
+        }
+
+        fn research_duration(&self) -> GameFrame {
+            unreachable!()
+        }
+
+        fn city_trade(&self, _: &TileYield) -> u64 {
+            unreachable!()
+        }
+
+        fn upkeep_duration(&self) -> GameFrame {
+            unreachable!()
+        }
+
+        fn unit_upkeep(&self, _: &UnitType) -> u64 {
+            unreachable!()
+        }
+
+        fn building_upkeep(&self, _: &BuildingType) -> u64 {
+            unreachable!()
+        }
+
+        fn building_sale(&self, _: &BuildingType) -> u64 {
+            unreachable!()
+        }
+
+        fn city_free_upkeep(&self) -> u64 {
+            unreachable!()
+        }
+    }
+
+    fn producing(tons: u64) -> CityExploitation {
+        CityExploitation::new(vec![], TileYield::new(0, tons, 0))
+    }
+
+    fn growth_task(start: &GameFrame, city_id: &CityId) -> CityGrowthTask {
+        CityGrowthTask::builder()
+            .context(
+                TaskContext::builder()
+                    .id(TaskId::default())
+                    .start(*start)
+                    .end(*start + 600)
+                    .build(),
+            )
+            .city(*city_id)
+            .build()
     }
 
     #[test]
@@ -120,6 +287,7 @@ mod test {
             &game_frame,
             &BuildCityFrom::Scratch("CityName".to_string(), Flag::Abkhazia, city_geo),
             &city_id,
+            &producing(1),
             &CityProduct::Unit(UnitType::Settlers),
         );
 
@@ -156,8 +324,9 @@ mod test {
                     .city(city_id)
                     .tons(CityProductionTons(1))
                     .build(),
+                growth_task(&GameFrame(0), &city_id),
             ))
-            .exploitation(CityExploitation::new(CityProductionTons(1)))
+            .exploitation(producing(1))
             .build();
 
         let new_city_production = CityProduction::new(vec![CityProduct::Unit(UnitType::Warriors)]);
@@ -170,6 +339,7 @@ mod test {
             &game_frame,
             &BuildCityFrom::Change(&city, BuildCityFromChange::Production(new_city_production)),
             &city_id,
+            &producing(1),
             &CityProduct::Unit(UnitType::Warriors),
         );
 
@@ -209,8 +379,9 @@ mod test {
                     .city(city_id)
                     .tons(was_producing_tons)
                     .build(),
+                growth_task(&GameFrame(0), &city_id),
             ))
-            .exploitation(CityExploitation::new(was_producing_tons))
+            .exploitation(producing(was_producing_tons.0))
             .build();
         // 20 tons / 2 because 120_000 is half of 240_000 (total required frames), 20 is half of 40, and tons is now 2.
         let expected_end = 120_000 + PRODUCTION_FRAMES_PER_TONS * (20 / 2);
@@ -221,9 +392,10 @@ mod test {
             &game_frame,
             &BuildCityFrom::Change(
                 &city,
-                BuildCityFromChange::Exploitation(CityExploitation::new(now_producing_tons)),
+                BuildCityFromChange::Exploitation(producing(now_producing_tons.0)),
             ),
             &city_id,
+            &producing(now_producing_tons.0),
             &CityProduct::Unit(now_producing),
         );
 
@@ -233,5 +405,54 @@ mod test {
         assert_eq!(task.context().end(), GameFrame(expected_end));
     }
 
-    // TODO: test with change to unit (will produce excess)
+    #[test]
+    fn test_production_task_change_to_cheaper_product() {
+        // GIVEN
+        let game_frame = GameFrame(120_000);
+        let rule_set: RuleSetBox = Box::new(TestRuleSet);
+        let city_geo = GeoContext::builder().point(WorldPoint::new(0, 0)).build();
+        let city_id = CityId::default();
+        let city = City::builder()
+            .geo(city_geo)
+            .id(city_id)
+            .name("CityName".to_string())
+            .flag(Flag::Abkhazia)
+            .production(CityProduction::new(vec![CityProduct::Unit(
+                UnitType::Settlers,
+            )]))
+            .tasks(CityTasks::new(
+                CityProductionTask::builder()
+                    .context(
+                        TaskContext::builder()
+                            .id(TaskId::default())
+                            .start(GameFrame(0))
+                            .end(GameFrame(240_000))
+                            .build(),
+                    )
+                    .city(city_id)
+                    .tons(CityProductionTons(1))
+                    .build(),
+                growth_task(&GameFrame(0), &city_id),
+            ))
+            .exploitation(producing(1))
+            .build();
+        let new_city_production = CityProduction::new(vec![CityProduct::Unit(UnitType::Warriors)]);
+
+        // WHEN
+        let task = production_task(
+            &rule_set,
+            &game_frame,
+            &BuildCityFrom::Change(&city, BuildCityFromChange::Production(new_city_production)),
+            &city_id,
+            &producing(1),
+            &CityProduct::Unit(UnitType::Warriors),
+        );
+
+        // THEN
+        // 20 tons are already stored, 8 are required: product is done right now, and
+        // excess tons will be kept for next product
+        assert_eq!(stored_tons(&city, &game_frame), CityProductionTons(20));
+        assert_eq!(task.context().start(), GameFrame(120_000));
+        assert_eq!(task.context().end(), GameFrame(120_000));
+    }
 }
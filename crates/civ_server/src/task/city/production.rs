@@ -1,13 +1,13 @@
 use common::{
     game::{
-        city::{CityId, CityProduct, CityProductionTons},
-        GameFrame, FRAME_PRODUCTION_TONS_RATIO, PRODUCTION_FRAMES_PER_TONS,
+        city::{CityExploitation, CityId, CityProduct, CityProductionTons},
+        GameFrame, PRODUCTION_FRAMES_PER_TONS,
     },
     rules::RuleSetBox,
 };
 
 use crate::{
-    game::task::production::CityProductionTask,
+    game::{city::City, task::production::CityProductionTask},
     task::{
         city::generator::{BuildCityFrom, BuildCityFromChange},
         WithContext,
@@ -16,60 +16,67 @@ use crate::{
 
 use super::{TaskContext, TaskId};
 
-type FromProduction<'a> = (
-    Option<(GameFrame, &'a CityProductionTons, &'a CityProduct)>,
-    (&'a CityProductionTons, &'a CityProduct),
-);
-
+/// Production task of city which will have given `exploitation`. Tons stored so far are
+/// kept for the (maybe new) current product.
 pub fn production_task(
     rules: &RuleSetBox,
     game_frame: &GameFrame,
     from: &BuildCityFrom,
     city_id: &CityId,
+    exploitation: &CityExploitation,
     default_product: &CityProduct,
 ) -> CityProductionTask {
-    let (previous, current): FromProduction = match from {
-        BuildCityFrom::Scratch(_, _, _) => (
-            None,
-            // TODO: for "current" tons, need to determine with "new" city exploitation
-            (&CityProductionTons(1), default_product),
-        ),
-        BuildCityFrom::Change(city, BuildCityFromChange::Production(production)) => (
-            Some((
-                *game_frame - city.tasks().production.context().start(),
-                city.exploitation().production_tons(),
-                city.production().current(),
-            )),
-            (city.exploitation().production_tons(), production.current()),
-        ),
-        BuildCityFrom::Change(city, BuildCityFromChange::Exploitation(exploitation)) => (
-            Some((
-                *game_frame - city.tasks().production.context().start(),
-                city.exploitation().production_tons(),
-                city.production().current(),
-            )),
-            (exploitation.production_tons(), city.production().current()),
-        ),
+    let (stored, product) = match from {
+        BuildCityFrom::Scratch(_, _, _) => (CityProductionTons(0), default_product),
+        BuildCityFrom::Change(city, BuildCityFromChange::Production(production)) => {
+            (stored_tons(city, game_frame), production.current())
+        }
+        BuildCityFrom::Change(city, BuildCityFromChange::Exploitation(_)) => {
+            (stored_tons(city, game_frame), city.production().current())
+        }
     };
 
-    let previously_produced_tons = previous
-        .map(|(f, t, _)| {
-            CityProductionTons((t.0 as f64 * f.0 as f64 * FRAME_PRODUCTION_TONS_RATIO) as u64)
-        })
-        .unwrap_or(CityProductionTons(0));
-    let current_product_left = rules.required_tons(current.1) - previously_produced_tons;
-    let required_frames = (PRODUCTION_FRAMES_PER_TONS as f32
-        * (current_product_left.0 as f32 / current.0 .0 as f32)) as u64;
+    product_task(
+        rules,
+        game_frame,
+        city_id,
+        product,
+        stored,
+        exploitation.production_tons(),
+    )
+}
+
+/// Task producing `product` from `start`, with already `stored` tons
+pub fn product_task(
+    rules: &RuleSetBox,
+    start: &GameFrame,
+    city_id: &CityId,
+    product: &CityProduct,
+    stored: CityProductionTons,
+    tons: CityProductionTons,
+) -> CityProductionTask {
+    let left = rules.required_tons(product).0.saturating_sub(stored.0);
+    let required_frames = (left * PRODUCTION_FRAMES_PER_TONS).div_ceil(tons.0);
 
     CityProductionTask::builder()
         .context(
             TaskContext::builder()
                 .id(TaskId::default())
-                .start(*game_frame)
-                .end(*game_frame + required_frames)
+                .start(*start)
+                .end(*start + required_frames)
                 .build(),
         )
         .city(*city_id)
-        .tons(*current.0)
+        .tons(tons)
         .build()
 }
+
+/// Tons stored by city at given frame: tons stored when its production task started,
+/// plus tons produced since
+pub fn stored_tons(city: &City, frame: &GameFrame) -> CityProductionTons {
+    let task = city.tasks().production();
+    let frames = frame.0.saturating_sub(task.context().start().0);
+    let produced = task.tons().0 * frames / PRODUCTION_FRAMES_PER_TONS;
+
+    city.production().tons() + produced
+}
@@ -0,0 +1,183 @@
+use common::{game::city::CityId, geo::Geo};
+
+use crate::{
+    effect::{self, CityEffect, Effect, StateEffect},
+    runner::RunnerContext,
+    state::StateError,
+    task::{
+        city::{
+            exploitation::{resize, taken},
+            generator::{BuildCityFrom, BuildCityFromChange},
+            production::{production_task, stored_tons},
+        },
+        TaskBox,
+    },
+};
+
+/// Effects making cities changed by given (applied) effects work tiles matching their new
+/// size and buildings. As several tasks can change a city during a tick, this is done once
+/// they are all applied.
+pub fn refresh_cities(context: &RunnerContext, effects: &[Effect]) -> Vec<Effect> {
+    let mut city_ids = vec![];
+    for effect in effects {
+        if let Effect::State(StateEffect::City(
+            city_id,
+            CityEffect::SetPopulation(_)
+            | CityEffect::AddBuilding(_)
+            | CityEffect::RemoveBuilding(_),
+        )) = effect
+        {
+            if !city_ids.contains(city_id) {
+                city_ids.push(*city_id);
+            }
+        }
+    }
+
+    city_ids
+        .iter()
+        // City can have been removed during the tick
+        .filter_map(|city_id| refresh_city(context, city_id).ok())
+        .flatten()
+        .collect()
+}
+
+/// Effects making given city work tiles matching its size and buildings. Its production
+/// task is replaced when its yield changes (tons stored so far are kept).
+pub fn refresh_city(context: &RunnerContext, city_id: &CityId) -> Result<Vec<Effect>, StateError> {
+    let rules = context.context.rules();
+    let state = context.state();
+    let world = context
+        .world
+        .read()
+        .expect("Consider world as always readable");
+    let city = state.find_city(city_id)?;
+
+    let exploitation = resize(
+        rules,
+        &world,
+        city.geo().point(),
+        city.buildings(),
+        city.exploitation(),
+        &taken(rules, &state, city.geo().point()),
+        city.population().size(),
+    );
+    if &exploitation == city.exploitation() {
+        return Ok(vec![]);
+    }
+
+    let task = production_task(
+        rules,
+        state.frame(),
+        &BuildCityFrom::Change(
+            city,
+            BuildCityFromChange::Exploitation(exploitation.clone()),
+        ),
+        city_id,
+        &exploitation,
+        city.production().current(),
+    );
+    let mut production = city.production().clone();
+    production.set_tons(stored_tons(city, state.frame()));
+    let previous: TaskBox = Box::new(city.tasks().production().clone());
+
+    Ok(vec![
+        effect::remove_task(previous),
+        effect::add_task(Box::new(task.clone())),
+        effect::set_city_production(*city_id, production),
+        effect::set_city_production_task(*city_id, task),
+        effect::set_city_exploitation(*city_id, exploitation),
+    ])
+}
+
+#[cfg(test)]
+mod test {
+    use common::{
+        game::{
+            city::{
+                BuildingType, CityExploitation, CityPopulation, CityProduct, CityProduction,
+                CityProductionTons,
+            },
+            unit::UnitType,
+            GameFrame, PRODUCTION_FRAMES_PER_TONS,
+        },
+        geo::WorldPoint,
+        rules::{std1::Std1RuleSet, RuleSetBox, TileYield},
+        space::D2Size,
+    };
+
+    use crate::{state::State, test::context::build_context};
+
+    use super::*;
+
+    #[test]
+    fn test_refresh_city_keeps_stored_tons() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let frame = GameFrame(PRODUCTION_FRAMES_PER_TONS * 3);
+        let mut city = crate::test::city::build_city(1);
+        city.set_population(CityPopulation::new(2, 0));
+        *city.exploitation_mut() =
+            CityExploitation::new(vec![WorldPoint::new(1, 1)], TileYield::new(2, 1, 0));
+        *city.production_mut() = CityProduction::new(vec![CityProduct::Unit(UnitType::Settlers)]);
+        city.production_mut().set_tons(CityProductionTons(2));
+        let stored = stored_tons(&city, &frame);
+        let state = State::build_from(
+            frame,
+            D2Size::new(3, 3),
+            Default::default(),
+            vec![city.clone()],
+            vec![],
+            &vec![],
+        );
+        let context = build_context(rules.clone(), state);
+
+        // WHEN
+        let effects = refresh_city(&context, city.id()).unwrap();
+        context.state.write().unwrap().apply(&effects, &rules);
+
+        // THEN
+        let state = context.state();
+        let city = state.find_city(city.id()).unwrap();
+        assert_eq!(city.exploitation().tiles().len(), 3);
+        assert_eq!(stored_tons(city, &frame), stored);
+    }
+
+    #[test]
+    fn test_refresh_cities_after_sold_building() {
+        // GIVEN
+        let rules: RuleSetBox = Box::new(Std1RuleSet);
+        let frame = GameFrame(PRODUCTION_FRAMES_PER_TONS * 3);
+        let mut city = crate::test::city::build_city(1);
+        city.add_building(BuildingType::Library);
+        *city.exploitation_mut() =
+            CityExploitation::new(vec![WorldPoint::new(1, 1)], TileYield::new(2, 1, 4));
+        *city.production_mut() = CityProduction::new(vec![CityProduct::Unit(UnitType::Settlers)]);
+        city.production_mut().set_tons(CityProductionTons(2));
+        let stored = stored_tons(&city, &frame);
+        let state = State::build_from(
+            frame,
+            D2Size::new(3, 3),
+            Default::default(),
+            vec![city.clone()],
+            vec![],
+            &vec![],
+        );
+        let context = build_context(rules.clone(), state);
+        let effects = vec![effect::remove_city_building(
+            *city.id(),
+            BuildingType::Library,
+        )];
+        context.state.write().unwrap().apply(&effects, &rules);
+
+        // WHEN
+        let effects = refresh_cities(&context, &effects);
+        context.state.write().unwrap().apply(&effects, &rules);
+
+        // THEN
+        let state = context.state();
+        let city = state.find_city(city.id()).unwrap();
+        assert!(!effects.is_empty());
+        assert!(city.buildings().is_empty());
+        assert_eq!(stored_tons(city, &frame), stored);
+    }
+}
@@ -4,7 +4,7 @@ use common::{
     game::{
         city::CityId,
         unit::{TaskType, UnitId},
-        GameFrame,
+        GameFrame, PlayerId,
     },
     geo::Geo,
 };
@@ -18,7 +18,9 @@ use crate::{
     effect::{self, Effect},
     game::{
         city::City,
-        task::{production::CityProductionTask, settle::Settle},
+        task::{
+            growth::CityGrowthTask, move_::Move, production::CityProductionTask, settle::Settle,
+        },
         unit::Unit,
     },
     runner::RunnerContext,
@@ -29,7 +31,7 @@ use crate::{
 pub mod city;
 pub mod unit;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct TaskId(pub Uuid);
 
 impl Default for TaskId {
@@ -44,6 +46,41 @@ pub trait Boxed {
     fn boxed(&self) -> TaskBox;
 }
 
+/// Serde of [`TaskBox`]: typetag only deserializes `Box<dyn Task>`, which is boxed again
+pub mod task_box_serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Task, TaskBox};
+
+    pub fn serialize<S: Serializer>(task: &TaskBox, serializer: S) -> Result<S::Ok, S::Error> {
+        task.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TaskBox, D::Error> {
+        Ok(Box::<dyn Task>::deserialize(deserializer)?.boxed())
+    }
+}
+
+/// Serde of [`TaskBox`] vectors, see [`task_box_serde`]
+pub mod task_boxes_serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Task, TaskBox};
+
+    pub fn serialize<S: Serializer>(tasks: &[TaskBox], serializer: S) -> Result<S::Ok, S::Error> {
+        tasks.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<TaskBox>, D::Error> {
+        Ok(Vec::<Box<dyn Task>>::deserialize(deserializer)?
+            .iter()
+            .map(|task| task.boxed())
+            .collect())
+    }
+}
+
 #[macro_export]
 macro_rules! impl_boxed {
     ($type:ty) => {
@@ -59,13 +96,22 @@ macro_rules! impl_boxed {
 pub trait Task: DynClone + Then + Boxed + WithContext {
     fn type_(&self) -> TaskType;
     fn concern(&self) -> Concern;
+    /// Player the task works for, when it is a player task
+    fn player(&self) -> Option<PlayerId> {
+        None
+    }
+    /// Must be true when [`Task::tick`] is implemented, else it is only called when the
+    /// task finishes
+    fn ticks(&self) -> bool {
+        false
+    }
     fn tick(&self, _frame: GameFrame) -> Vec<Effect> {
         vec![]
     }
 }
 dyn_clone::clone_trait_object!(Task);
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum Concern {
     Nothing,
     Unit(UnitId),
@@ -219,8 +265,10 @@ pub enum TaskContainer {
 }
 pub enum UnitTaskContainer {
     Settle(Settle),
+    Move(Move),
 }
 
 pub enum CityTaskContainer {
     Production(CityProductionTask),
+    Growth(CityGrowthTask),
 }
@@ -4,7 +4,6 @@ use super::{Concern, Task, TaskBox, TaskContext, TaskError, TaskId, Then};
 use crate::{effect::Effect, impl_boxed, impl_with_context, runner::RunnerContext};
 use bon::Builder;
 use common::game::unit::{SystemTaskType, TaskType};
-use log::info;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Builder, Clone, Serialize, Deserialize)]
@@ -41,12 +40,10 @@ impl Then for SnapshotTask {
         let state = context.state();
         let frame = state.frame();
 
-        info!("Snapshot to {}", self.snapshot_to.display());
-        state.snapshot().dump(&self.snapshot_to).unwrap();
-
+        // Snapshot is written by runner once this tick effects applied
         let each = self.context.end() - self.context.start();
         Ok((
-            vec![],
+            vec![Effect::Snapshot(self.snapshot_to.clone())],
             vec![Box::new(Self::new(
                 TaskContext::builder()
                     .id(TaskId::default())
@@ -1,19 +1,28 @@
 use common::game::tasks::client::ClientTask;
 use serde::{Deserialize, Serialize};
 
-use crate::{game::task::settle::Settle as SettleTask, task::WithContext};
+use crate::{
+    game::task::{
+        attack::Attack as AttackTask, move_::Move as MoveTask, settle::Settle as SettleTask,
+    },
+    task::WithContext,
+};
 
 use super::{TaskBox, TaskContext};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum UnitTaskWrapper {
     Settle(SettleTask),
+    Move(MoveTask),
+    Attack(AttackTask),
 }
 
 impl UnitTaskWrapper {
     pub fn context(&self) -> &TaskContext {
         match self {
             UnitTaskWrapper::Settle(settle) => settle.context(),
+            UnitTaskWrapper::Move(move_) => move_.context(),
+            UnitTaskWrapper::Attack(attack) => attack.context(),
         }
     }
 }
@@ -25,15 +34,25 @@ impl From<UnitTaskWrapper> for ClientTask {
                 let context = task.context().clone();
                 ClientTask::new(task.into(), context.start(), context.end())
             }
+            UnitTaskWrapper::Move(task) => {
+                let context = task.context().clone();
+                ClientTask::new(task.into(), context.start(), context.end())
+            }
+            UnitTaskWrapper::Attack(task) => {
+                let context = task.context().clone();
+                ClientTask::new(task.into(), context.start(), context.end())
+            }
         }
     }
 }
 
 impl From<UnitTaskWrapper> for TaskBox {
     fn from(value: UnitTaskWrapper) -> Self {
-        Box::new(match value {
-            UnitTaskWrapper::Settle(task) => task,
-        })
+        match value {
+            UnitTaskWrapper::Settle(task) => Box::new(task),
+            UnitTaskWrapper::Move(task) => Box::new(task),
+            UnitTaskWrapper::Attack(task) => Box::new(task),
+        }
     }
 }
 
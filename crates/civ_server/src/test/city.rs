@@ -1,5 +1,8 @@
 use crate::{
-    game::{city::City, task::production::CityProductionTask},
+    game::{
+        city::City,
+        task::{growth::CityGrowthTask, production::CityProductionTask},
+    },
     task::{city::CityTasks, TaskContext, TaskId},
 };
 use common::{
@@ -22,7 +25,7 @@ pub fn build_city(i: usize) -> City {
                 .build(),
         )
         .production(CityProduction::new(vec![]))
-        .exploitation(CityExploitation::new(CityProductionTons(1)))
+        .exploitation(CityExploitation::default())
         .tasks(
             CityTasks::builder()
                 .production(
@@ -38,6 +41,18 @@ pub fn build_city(i: usize) -> City {
                         .tons(CityProductionTons(1))
                         .build(),
                 )
+                .growth(
+                    CityGrowthTask::builder()
+                        .city(city_uuid)
+                        .context(
+                            TaskContext::builder()
+                                .id(TaskId::default())
+                                .start(GameFrame(0))
+                                .end(GameFrame(1))
+                                .build(),
+                        )
+                        .build(),
+                )
                 .build(),
         )
         .flag(Flag::Abkhazia)
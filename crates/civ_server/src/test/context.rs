@@ -0,0 +1,39 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+use async_std::channel::unbounded;
+use common::{
+    game::GameFrame,
+    rules::RuleSetBox,
+    world::{TerrainType, Tile},
+};
+
+use crate::{
+    config::ServerConfig, context::Context, game::placer::RandomPlacer, runner::RunnerContext,
+    state::State, world::reader::WorldReader,
+};
+
+/// Runner context of given state, on a grassland world of the state size
+pub fn build_context(rules: RuleSetBox, state: State) -> RunnerContext {
+    let size = state.world_size();
+    let world = WorldReader::new(
+        PathBuf::new(),
+        size.width() as u64,
+        size.height() as u64,
+        vec![Tile::new(TerrainType::GrassLand); size.width() * size.height()],
+    );
+    let config = ServerConfig::new(None, GameFrame(0), 1, "".to_string(), "".to_string());
+    let (_, from_clients_receiver) = unbounded();
+    let (to_clients_sender, _) = unbounded();
+
+    RunnerContext::new(
+        Context::new(rules, config),
+        Arc::new(RwLock::new(state)),
+        Arc::new(RwLock::new(world)),
+        from_clients_receiver,
+        to_clients_sender,
+        Box::new(RandomPlacer),
+    )
+}
@@ -1,4 +1,5 @@
 pub mod city;
+pub mod context;
 pub mod task;
 pub mod unit;
 
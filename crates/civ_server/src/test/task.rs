@@ -53,6 +53,10 @@ impl Task for FibonacciTask {
         Concern::Nothing
     }
 
+    fn ticks(&self) -> bool {
+        true
+    }
+
     fn tick(&self, _frame: GameFrame) -> Vec<Effect> {
         fibonacci(self.complexity);
         vec![Effect::State(StateEffect::Testing)]
@@ -65,6 +69,38 @@ impl Then for FibonacciTask {
     }
 }
 
+/// Task doing nothing until it finishes, as most game tasks (production, settle, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdleTask {
+    context: TaskContext,
+}
+
+impl IdleTask {
+    pub fn new(context: TaskContext) -> Self {
+        Self { context }
+    }
+}
+
+impl_boxed!(IdleTask);
+impl_with_context!(IdleTask);
+
+#[typetag::serde]
+impl Task for IdleTask {
+    fn type_(&self) -> TaskType {
+        TaskType::Testing
+    }
+
+    fn concern(&self) -> Concern {
+        Concern::Nothing
+    }
+}
+
+impl Then for IdleTask {
+    fn then(&self, _context: &RunnerContext) -> Result<(Vec<Effect>, Vec<TaskBox>), TaskError> {
+        Ok((vec![], vec![]))
+    }
+}
+
 pub fn build_task() -> Box<dyn Task> {
     Box::new(FibonacciTask::new(
         TaskContext::builder()
@@ -34,6 +34,8 @@ pub struct WorldReader {
     source: PathBuf,
     width: u64,
     height: u64,
+    /// Seed used to generate the world
+    seed: u64,
     tiles: Vec<Tile>,
 }
 
@@ -43,6 +45,7 @@ impl WorldReader {
             source,
             width,
             height,
+            seed: 0,
             tiles,
         }
     }
@@ -55,6 +58,7 @@ impl WorldReader {
             source,
             width: 0,
             height: 0,
+            seed: 0,
             tiles: vec![],
         };
 
@@ -70,11 +74,15 @@ impl WorldReader {
         self_.tiles.clear();
         self_.width = world.width;
         self_.height = world.height;
+        self_.seed = world.seed;
         let mut done = 0;
         let expected = world.width * world.height;
 
-        for chunk_x in 0..chunked_width {
-            for chunk_y in 0..chunked_height {
+        for chunk_y in 0..chunked_height {
+            // Chunks tiles are row ordered, so a whole chunks line is required to
+            // write world tiles rows
+            let mut chunks = Vec::with_capacity(chunked_width as usize);
+            for chunk_x in 0..chunked_width {
                 let file_name = format!("{}_{}.ct", chunk_x, chunk_y);
                 let chunk: Chunk =
                     bincode::deserialize(&fs::read(self_.source.join(file_name)).map_err(|e| {
@@ -85,15 +93,22 @@ impl WorldReader {
                             e.to_string(),
                         ))
                     })?;
+                chunks.push(chunk);
+            }
 
-                done += chunk.tiles.len();
-                self_.tiles.extend(chunk.tiles);
-
-                let progress_ = done as f32 / expected as f32;
-                progress
-                    .as_ref()
-                    .map(|s| s.send_blocking(Progress::InProgress(progress_)));
+            for row in 0..world.chunk_size as usize {
+                for chunk in &chunks {
+                    let start = row * world.chunk_size as usize;
+                    let end = start + world.chunk_size as usize;
+                    self_.tiles.extend_from_slice(&chunk.tiles[start..end]);
+                }
             }
+
+            done += chunks.iter().map(|c| c.tiles.len()).sum::<usize>();
+            let progress_ = done as f32 / expected as f32;
+            progress
+                .as_ref()
+                .map(|s| s.send_blocking(Progress::InProgress(progress_)));
         }
 
         progress
@@ -103,6 +118,10 @@ impl WorldReader {
     }
 
     pub fn tile(&self, x: u64, y: u64) -> Option<&Tile> {
+        if x >= self.width {
+            return None;
+        }
+
         let index = y * self.width + x;
         self.tiles.get(index as usize)
     }
@@ -135,4 +154,8 @@ impl WorldReader {
     pub fn height(&self) -> u64 {
         self.height
     }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
 }
@@ -30,7 +30,30 @@ pub fn city(context: CommandContext, id: &CityId, follow: bool) -> Result<(), Co
             println!("id: {}", city.id());
             println!("name: {}", city.name());
             println!("xy: {:?}", city.geo().point());
+            println!("size: {}", city.size());
+            println!("food: {}", city.population().food());
+            println!("yield: {:?}", city.exploitation().yield_());
+            println!("worked: {:?}", city.exploitation().tiles());
             println!("production: {}", city.production_str(&frame));
+            println!("stored: {}", city.stored_tons(&frame).0);
+            println!(
+                "queue: {}",
+                city.production()
+                    .stack()
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            println!("repeat: {}", city.production().repeat());
+            println!(
+                "buildings: {}",
+                city.buildings()
+                    .iter()
+                    .map(|b| b.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
         }
         follow_ = follow;
 
@@ -11,16 +11,28 @@ use common::{
 use super::{CommandContext, CommandError, InvalidInputError};
 
 pub fn place(context: CommandContext, input: &str) -> Result<(), CommandError> {
-    let flag = Flag::from_str(input).map_err(|_| {
-        CommandError::InvalidInput(InvalidInputError::InvalidFlag(input.to_string()))
-    })?;
+    let flag = Flag::from_str(input)
+        .map_err(|_| CommandError::InvalidInput(InvalidInputError::Flag(input.to_string())))?;
+    // Server checks we play with the rules it sent
+    let fingerprint = context
+        .state
+        .read()
+        .expect("Assume state is always accessible")
+        .server()
+        .ok_or(CommandError::GameStateNotReady)?
+        .rules()
+        .fingerprint();
 
     context
         .to_server_sender
         .send(ClientToServerMessage::Game(
             ClientToServerGameMessage::Establishment(
                 // TODO: this is not a correct resolution
-                ClientToServerEstablishmentMessage::TakePlace(flag, Resolution::new(1, 1)),
+                ClientToServerEstablishmentMessage::TakePlace(
+                    flag,
+                    Resolution::new(1, 1),
+                    fingerprint,
+                ),
             ),
         ))
         .unwrap();
@@ -15,7 +15,10 @@ use uuid::Uuid;
 
 pub mod city;
 pub mod errors;
+pub mod research;
 pub mod status;
+pub mod tile;
+pub mod treasury;
 pub mod unit;
 pub mod window;
 
@@ -55,7 +58,19 @@ pub enum SubCommand {
         #[clap(long, short, action)]
         follow: bool,
     },
+    Tile {
+        x: u64,
+        y: u64,
+    },
     Units,
+    /// Show research, or choose the tech to research
+    Research {
+        tech: Option<String>,
+    },
+    /// Show treasury, or choose the part of trade (in percent) given to gold
+    Treasury {
+        tax_rate: Option<u8>,
+    },
     Unit {
         id: Uuid,
         #[clap(subcommand)]
@@ -72,6 +87,16 @@ pub enum UnitSubCommand {
     Settle {
         city_name: String,
     },
+    MoveTo {
+        x: u64,
+        y: u64,
+    },
+    AttackUnit {
+        target: Uuid,
+    },
+    AttackCity {
+        target: Uuid,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -122,7 +147,11 @@ pub enum CommandError {
 #[derive(Error, Debug)]
 pub enum InvalidInputError {
     #[error("Invalid flag: {0}")]
-    InvalidFlag(String),
+    Flag(String),
+    #[error("Invalid tech: {0}")]
+    Tech(String),
+    #[error("Invalid tax rate: {0} (expected 0 to 100)")]
+    TaxRate(u8),
 }
 
 impl From<StateError> for CommandError {
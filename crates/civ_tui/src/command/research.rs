@@ -0,0 +1,55 @@
+use common::network::message::{
+    ClientToServerGameMessage, ClientToServerInGameMessage, ClientToServerMessage,
+};
+
+use super::{CommandContext, CommandError, InvalidInputError};
+
+pub fn research(context: CommandContext) -> Result<(), CommandError> {
+    let state = context
+        .state
+        .read()
+        .expect("Consider state always accessible");
+    let research = state.research();
+    let known = research
+        .known()
+        .iter()
+        .map(|tech| tech.to_string())
+        .collect::<Vec<_>>();
+
+    println!("known: {}", known.join(", "));
+    match research.current() {
+        Some(current) => {
+            let cost = context
+                .context
+                .rule_set()
+                .ok_or(CommandError::GameStateNotReady)?
+                .tech(current)
+                .map(|tech| tech.cost())
+                .unwrap_or_default();
+            println!("current: {} ({}/{})", current, research.science(), cost);
+        }
+        None => println!("current: None ({} stored)", research.science()),
+    }
+
+    Ok(())
+}
+
+pub fn set(context: CommandContext, input: &str) -> Result<(), CommandError> {
+    let tech = context
+        .context
+        .rule_set()
+        .ok_or(CommandError::GameStateNotReady)?
+        .techs()
+        .into_iter()
+        .map(|tech| *tech.type_())
+        .find(|tech| format!("{:?}", tech).eq_ignore_ascii_case(input))
+        .ok_or(CommandError::InvalidInput(InvalidInputError::Tech(
+            input.to_string(),
+        )))?;
+
+    context.to_server_sender.send(ClientToServerMessage::Game(
+        ClientToServerGameMessage::InGame(ClientToServerInGameMessage::SetResearch(tech)),
+    ))?;
+
+    Ok(())
+}
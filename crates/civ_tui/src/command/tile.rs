@@ -0,0 +1,44 @@
+use common::{geo::WorldPoint, world::CtxTile};
+
+use super::{CommandContext, CommandError};
+
+pub fn tile(context: CommandContext, point: &WorldPoint) -> Result<(), CommandError> {
+    let state = context
+        .state
+        .read()
+        .expect("Consider state always accessible");
+
+    let tiles = state.tiles().ok_or(CommandError::GameStateNotReady)?;
+    let Some(CtxTile::Visible(tile) | CtxTile::Explored(tile)) = tiles.get(point) else {
+        println!("Tile not explored");
+        return Ok(());
+    };
+    let yield_ = context
+        .context
+        .rule_set()
+        .ok_or(CommandError::GameStateNotReady)?
+        .tile_yield(tile);
+
+    println!("xy: {:?}", point);
+    println!("terrain: {:?}", tile.type_());
+    println!("elevation: {:?}", tile.elevation());
+    println!(
+        "vegetation: {}",
+        tile.vegetation()
+            .map(|vegetation| format!("{:?}", vegetation))
+            .unwrap_or("n/a".to_string())
+    );
+    println!("river: {}", tile.has_river());
+    println!(
+        "resource: {}",
+        tile.resource()
+            .map(|resource| resource.to_string())
+            .unwrap_or("n/a".to_string())
+    );
+    println!(
+        "yield: food={} production={} trade={}",
+        yield_.food, yield_.production, yield_.trade
+    );
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use common::network::message::{
+    ClientToServerGameMessage, ClientToServerInGameMessage, ClientToServerMessage,
+};
+
+use super::{CommandContext, CommandError, InvalidInputError};
+
+pub fn treasury(context: CommandContext) -> Result<(), CommandError> {
+    let state = context
+        .state
+        .read()
+        .expect("Consider state always accessible");
+    let treasury = state.treasury();
+
+    println!("gold: {}", treasury.gold());
+    println!(
+        "tax rate: {}% (science: {}%)",
+        treasury.tax_rate(),
+        100 - treasury.tax_rate()
+    );
+
+    Ok(())
+}
+
+pub fn set_tax_rate(context: CommandContext, tax_rate: u8) -> Result<(), CommandError> {
+    if tax_rate > 100 {
+        return Err(CommandError::InvalidInput(InvalidInputError::TaxRate(
+            tax_rate,
+        )));
+    }
+
+    context.to_server_sender.send(ClientToServerMessage::Game(
+        ClientToServerGameMessage::InGame(ClientToServerInGameMessage::SetTaxRate(tax_rate)),
+    ))?;
+
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use std::thread;
 
 use common::{
-    game::unit::{TaskType, UnitId, UnitTaskType},
+    game::unit::{AttackTarget, TaskType, UnitId, UnitTaskType},
+    geo::WorldPoint,
     network::message::{
         ClientToServerGameMessage, ClientToServerInGameMessage, ClientToServerMessage,
         ClientToServerUnitMessage,
@@ -41,6 +42,7 @@ pub fn detail(context: CommandContext, id: &UnitId, follow: bool) -> Result<(),
             println!("id: {}", unit.id());
             println!("xy: {:?}", unit.geo().point());
             println!("type: {:?}", unit.type_().to_string());
+            println!("damage: {}", unit.damage());
             println!("task: {}", task_str);
         }
         follow_ = follow;
@@ -71,7 +73,8 @@ pub fn settle(
     if !context
         .context
         .rule_set()
-        .unit_can(unit.type_())
+        .ok_or(CommandError::GameStateNotReady)?
+        .unit_can(unit.type_(), state.research().known())
         .contains(&TaskType::Unit(UnitTaskType::Settle))
     {
         println!("Action not available for this unit type");
@@ -87,3 +90,75 @@ pub fn settle(
 
     Ok(())
 }
+
+pub fn move_to(
+    context: CommandContext,
+    unit_id: &UnitId,
+    destination: WorldPoint,
+) -> Result<(), CommandError> {
+    let state = context
+        .state
+        .read()
+        .expect("Assume state always accessible");
+
+    let unit = state
+        .units()?
+        .iter()
+        .find(|c| c.id() == unit_id)
+        .ok_or(CommandError::UnitNoMoreAvailable)?;
+    if !context
+        .context
+        .rule_set()
+        .ok_or(CommandError::GameStateNotReady)?
+        .unit_can(unit.type_(), state.research().known())
+        .contains(&TaskType::Unit(UnitTaskType::Move))
+    {
+        println!("Action not available for this unit type");
+        return Ok(());
+    }
+
+    context.to_server_sender.send(ClientToServerMessage::Game(
+        ClientToServerGameMessage::InGame(ClientToServerInGameMessage::Unit(
+            *unit.id(),
+            ClientToServerUnitMessage::MoveTo(destination),
+        )),
+    ))?;
+
+    Ok(())
+}
+
+pub fn attack(
+    context: CommandContext,
+    unit_id: &UnitId,
+    target: AttackTarget,
+) -> Result<(), CommandError> {
+    let state = context
+        .state
+        .read()
+        .expect("Assume state always accessible");
+
+    let unit = state
+        .units()?
+        .iter()
+        .find(|c| c.id() == unit_id)
+        .ok_or(CommandError::UnitNoMoreAvailable)?;
+    if !context
+        .context
+        .rule_set()
+        .ok_or(CommandError::GameStateNotReady)?
+        .unit_can(unit.type_(), state.research().known())
+        .contains(&TaskType::Unit(UnitTaskType::Attack))
+    {
+        println!("Action not available for this unit type");
+        return Ok(());
+    }
+
+    context.to_server_sender.send(ClientToServerMessage::Game(
+        ClientToServerGameMessage::InGame(ClientToServerInGameMessage::Unit(
+            *unit.id(),
+            ClientToServerUnitMessage::Attack(target),
+        )),
+    ))?;
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use std::sync::{
     atomic::{AtomicBool, Ordering},
-    Arc,
+    Arc, RwLock,
 };
 
 use common::rules::RuleSetBox;
@@ -8,14 +8,15 @@ use common::rules::RuleSetBox;
 #[derive(Clone)]
 pub struct Context {
     stop: Arc<AtomicBool>,
-    rule_set: RuleSetBox,
+    /// Rules received from the server
+    rule_set: Arc<RwLock<Option<RuleSetBox>>>,
 }
 
 impl Context {
-    pub fn new(rule_set: RuleSetBox) -> Self {
+    pub fn new() -> Self {
         Self {
             stop: Arc::new(AtomicBool::new(false)),
-            rule_set,
+            rule_set: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -27,7 +28,18 @@ impl Context {
         self.stop.swap(true, Ordering::Relaxed);
     }
 
-    pub fn rule_set(&self) -> &RuleSetBox {
-        &self.rule_set
+    /// Rules of the server, once received
+    pub fn rule_set(&self) -> Option<RuleSetBox> {
+        self.rule_set
+            .read()
+            .expect("Consider rule set always accessible")
+            .clone()
+    }
+
+    pub fn set_rule_set(&self, rule_set: RuleSetBox) {
+        *self
+            .rule_set
+            .write()
+            .expect("Consider rule set always accessible") = Some(rule_set);
     }
 }
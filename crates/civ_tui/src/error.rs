@@ -1,10 +1,12 @@
-use common::network::message::TakePlaceRefusedReason;
+use common::network::message::{HelloRefusedReason, TakePlaceRefusedReason};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum PublicError {
     #[error("Not connected to server")]
     NotConnected,
+    #[error("Server refused connection because: {0}")]
+    ConnectionRefused(HelloRefusedReason),
     #[error("Cant take place because: {0}")]
     CantTakePlace(TakePlaceRefusedReason),
     #[error("{0}")]
@@ -11,7 +11,6 @@ use common::{
         message::{ClientToServerMessage, ServerToClientMessage},
         ClientId,
     },
-    rules::std1::Std1RuleSet,
 };
 use context::Context;
 use crossbeam::channel::{unbounded, Receiver, Sender};
@@ -55,7 +54,7 @@ fn main() -> Result<(), Error> {
     let client_id = ClientId::default();
     let player_id = PlayerId::from_str(&args.player.unwrap_or(PlayerId::default().to_string()))
         .map_err(Error::PlayerId)?;
-    let context = Context::new(Box::new(Std1RuleSet));
+    let context = Context::new();
     let state = Arc::new(RwLock::new(State::new(client_id)));
     let (to_server_sender, to_server_receiver): (
         Sender<ClientToServerMessage>,
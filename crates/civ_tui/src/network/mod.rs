@@ -8,11 +8,12 @@ use common::{
     game::PlayerId,
     network::{
         message::{ClientToServerMessage, ClientToServerNetworkMessage, ServerToClientMessage},
-        Client, ClientId,
+        Client, ClientId, PROTOCOL_VERSION,
     },
     space::window::Resolution,
 };
 use crossbeam::channel::{Receiver, Sender};
+use log::error;
 use message_io::{
     network::{Endpoint, NetEvent, Transport},
     node::{self, NodeHandler, NodeListener},
@@ -92,6 +93,7 @@ impl NetworkClient {
                     // Inform server about our uuid
                     let message = bincode::serialize(&ClientToServerMessage::Network(
                         ClientToServerNetworkMessage::Hello(
+                            PROTOCOL_VERSION,
                             Client::new(self.client_id, self.player_id),
                             Resolution::new(1, 1),
                         ),
@@ -101,7 +103,20 @@ impl NetworkClient {
                 }
                 NetEvent::Accepted(_, _) => {}
                 NetEvent::Message(_endpoint, input_data) => {
-                    let message: ServerToClientMessage = bincode::deserialize(input_data).unwrap();
+                    let message: ServerToClientMessage = match bincode::deserialize(input_data) {
+                        Ok(message) => message,
+                        Err(error) => {
+                            // Probably a server of another protocol version
+                            error!("Unreadable message from server: {}", error);
+                            let mut state = self
+                                .state
+                                .write()
+                                .expect("Assume state is always accessible");
+                            state.set_connected(false);
+                            self.handler.stop();
+                            return;
+                        }
+                    };
                     self.from_server_sender.send(message).unwrap();
                 }
                 NetEvent::Disconnected(_) => {
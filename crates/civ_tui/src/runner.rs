@@ -7,7 +7,11 @@ use std::{
 use bon::Builder;
 use clap::Parser;
 use common::{
-    game::{city::CityId, unit::UnitId},
+    game::{
+        city::CityId,
+        unit::{AttackTarget, UnitId},
+    },
+    geo::WorldPoint,
     network::message::{
         ClientToServerMessage, NotificationLevel, ServerToClientEstablishmentMessage,
         ServerToClientInGameMessage, ServerToClientMessage,
@@ -36,18 +40,23 @@ impl Runner {
     pub fn run(&mut self) {
         let from_server_receiver = self.from_server_receiver.clone();
         let state = Arc::clone(&self.state);
+        let context = self.context.clone();
         thread::spawn(move || {
             while let Ok(message) = from_server_receiver.recv() {
                 let mut state = state.write().expect("Assume state is always accessible");
                 match message {
                     ServerToClientMessage::Establishment(message) => match message {
                         ServerToClientEstablishmentMessage::ServerResume(server_resume, flag) => {
-                            state.set_server(Some(server_resume));
+                            context.set_rule_set(Box::new(server_resume.rules().clone()));
+                            state.set_server(Some(*server_resume));
                             state.set_flag(flag);
                         }
                         ServerToClientEstablishmentMessage::TakePlaceRefused(reason) => {
                             state.push_error(PublicError::CantTakePlace(reason))
                         }
+                        ServerToClientEstablishmentMessage::HelloRefused(reason) => {
+                            state.push_error(PublicError::ConnectionRefused(reason))
+                        }
                     },
                     ServerToClientMessage::InGame(ServerToClientInGameMessage::State(message)) => {
                         state.apply(message);
@@ -57,10 +66,9 @@ impl Runner {
                         message,
                     )) => {
                         match level {
-                            NotificationLevel::Error => {
+                            NotificationLevel::Error | NotificationLevel::Warning => {
                                 state.push_error(PublicError::ServerNotification(message));
                             }
-                            NotificationLevel::Warning => {}
                             NotificationLevel::Info => {}
                         };
                     }
@@ -126,7 +134,18 @@ impl Runner {
                     SubCommand::City { id, follow } => {
                         command::city::city(self.into(), &CityId::new(id), follow)?
                     }
+                    SubCommand::Tile { x, y } => {
+                        command::tile::tile(self.into(), &WorldPoint::new(x, y))?
+                    }
                     SubCommand::Units => command::unit::units(self.into())?,
+                    SubCommand::Research { tech } => match tech {
+                        Some(tech) => command::research::set(self.into(), &tech)?,
+                        None => command::research::research(self.into())?,
+                    },
+                    SubCommand::Treasury { tax_rate } => match tax_rate {
+                        Some(tax_rate) => command::treasury::set_tax_rate(self.into(), tax_rate)?,
+                        None => command::treasury::treasury(self.into())?,
+                    },
                     SubCommand::Unit { id, subcommand } => {
                         match subcommand {
                             Some(command) => match command {
@@ -140,6 +159,27 @@ impl Runner {
                                         &city_name,
                                     )?;
                                 }
+                                UnitSubCommand::MoveTo { x, y } => {
+                                    command::unit::move_to(
+                                        self.into(),
+                                        &UnitId::new(id),
+                                        WorldPoint::new(x, y),
+                                    )?;
+                                }
+                                UnitSubCommand::AttackUnit { target } => {
+                                    command::unit::attack(
+                                        self.into(),
+                                        &UnitId::new(id),
+                                        AttackTarget::Unit(UnitId::new(target)),
+                                    )?;
+                                }
+                                UnitSubCommand::AttackCity { target } => {
+                                    command::unit::attack(
+                                        self.into(),
+                                        &UnitId::new(id),
+                                        AttackTarget::City(CityId::new(target)),
+                                    )?;
+                                }
                             },
                             None => command::unit::detail(self.into(), &UnitId::new(id), false)?,
                         };
@@ -3,6 +3,8 @@ use common::{
         nation::flag::Flag,
         server::ServerResume,
         slice::{ClientCity, ClientUnit},
+        tech::Research,
+        treasury::Treasury,
         GameFrame,
     },
     network::{message::ClientStateMessage, ClientId},
@@ -24,6 +26,8 @@ pub struct State {
     tiles: Option<Slice<CtxTile<Tile>>>,
     cities: Option<Vec<ClientCity>>,
     units: Option<Vec<ClientUnit>>,
+    research: Research,
+    treasury: Treasury,
 }
 
 impl State {
@@ -39,6 +43,8 @@ impl State {
             tiles: None,
             cities: None,
             units: None,
+            research: Research::default(),
+            treasury: Treasury::default(),
         }
     }
 
@@ -82,11 +88,35 @@ impl State {
             ClientStateMessage::SetWindow(window) => {
                 self.set_window(Some(window));
             }
-            ClientStateMessage::SetGameSlice(_slice) => {
-                todo!();
-                // self.tiles = Some(slice.tiles().clone());
-                // self.cities = Some(slice.cities().into());
-                // self.units = Some(slice.units().into());
+            ClientStateMessage::SetGameSlice(slice) => {
+                self.tiles = Some(slice.tiles().clone());
+                self.cities = Some(slice.cities().items().iter().flatten().cloned().collect());
+                self.units = Some(
+                    slice
+                        .units()
+                        .items()
+                        .iter()
+                        .flatten()
+                        .flatten()
+                        .cloned()
+                        .collect(),
+                );
+            }
+            ClientStateMessage::SetTiles(tiles_) => {
+                for tile in tiles_ {
+                    let point = tile.point();
+                    if let Some(tiles) = &mut self.tiles {
+                        tiles.set(point, tile.tile().clone());
+                    }
+                    if let Some(cities) = &mut self.cities {
+                        cities.retain(|c| c.geo().point() != point);
+                        cities.extend(tile.city().cloned());
+                    }
+                    if let Some(units) = &mut self.units {
+                        units.retain(|u| u.geo().point() != point);
+                        units.extend(tile.units().iter().cloned());
+                    }
+                }
             }
             ClientStateMessage::SetCity(city) => {
                 if let Some(cities) = &mut self.cities {
@@ -112,6 +142,12 @@ impl State {
                     units.retain(|u| u.id() != &unit_id)
                 }
             }
+            ClientStateMessage::SetResearch(research) => {
+                self.research = research;
+            }
+            ClientStateMessage::SetTreasury(treasury) => {
+                self.treasury = treasury;
+            }
         }
     }
 
@@ -123,6 +159,14 @@ impl State {
         self.units.as_ref().ok_or(StateError::NotReady)
     }
 
+    pub fn research(&self) -> &Research {
+        &self.research
+    }
+
+    pub fn treasury(&self) -> &Treasury {
+        &self.treasury
+    }
+
     pub fn frame(&self) -> Result<GameFrame, StateError> {
         self.frame.ok_or(StateError::NotReady)
     }
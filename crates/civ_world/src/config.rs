@@ -4,7 +4,7 @@ use bon::Builder;
 use common::world::World;
 use derive_more::Constructor;
 
-use crate::Args;
+use crate::{generator::resource::DEFAULT_RESOURCES_DENSITY, Args, GeneratorType};
 
 // TODO: For now, contain same than Args, but will contains climatic info, etc
 #[derive(Debug, Builder, Clone, Constructor)]
@@ -14,6 +14,8 @@ pub struct WorldConfig {
     pub height: usize,
     #[builder(default = 5000)]
     pub chunk_size: usize,
+    /// Random if not given
+    pub seed: Option<u64>,
 }
 
 impl From<WorldConfig> for Args {
@@ -23,12 +25,16 @@ impl From<WorldConfig> for Args {
             width,
             height,
             chunk_size,
+            seed,
         } = value;
         Self {
             target,
             width,
             height,
             chunk_size,
+            generator: GeneratorType::default(),
+            seed,
+            resources_density: DEFAULT_RESOURCES_DENSITY,
         }
     }
 }
@@ -39,6 +45,7 @@ impl From<WorldConfig> for World {
             .width(value.width as u64)
             .height(value.height as u64)
             .chunk_size(value.chunk_size as u64)
+            .seed(value.seed.unwrap_or_else(rand::random))
             .build()
     }
 }
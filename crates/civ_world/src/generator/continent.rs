@@ -0,0 +1,237 @@
+use bon::Builder;
+use common::{
+    geo::WorldPoint,
+    world::{Chunk, Elevation, TerrainType, Tile, Vegetation, World},
+};
+
+use crate::WorldGeneratorError;
+
+use super::{
+    hydrology::Hydrology,
+    noise,
+    resource::{resource, DEFAULT_RESOURCES_DENSITY},
+    Generator,
+};
+
+const RIDGE_SEED: u64 = 1;
+const TEMPERATURE_SEED: u64 = 2;
+const MOISTURE_SEED: u64 = 3;
+/// Elevation band (under sea level) considered as shallow water
+const COAST_DEPTH: f64 = 0.03;
+/// Minimum altitude (above sea level) of river sources
+const RIVER_SOURCE_ALTITUDE: f64 = 0.15;
+
+/// Generate continents from noise heightmap. Climate depends on latitude, altitude and
+/// moisture noise. Rivers and lakes are then traced by [`Hydrology`]. Each tile only
+/// depends on world seed and its coordinates.
+#[derive(Debug, Clone, Builder)]
+pub struct ContinentGenerator {
+    /// Noise elevation (in [0, 1]) under which tiles are water
+    #[builder(default = 0.5)]
+    sea_level: f64,
+    /// Approximate count of continents along the biggest world side
+    #[builder(default = 3.0)]
+    continents: f64,
+    /// Part of tiles having a resource
+    #[builder(default = DEFAULT_RESOURCES_DENSITY)]
+    resources_density: f64,
+}
+
+impl ContinentGenerator {
+    fn scale(&self, world: &World) -> f64 {
+        world.width.max(world.height) as f64 / self.continents
+    }
+
+    /// Noise elevation, in [0, 1]
+    fn elevation(&self, world: &World, point: &WorldPoint) -> f64 {
+        noise::fractal(
+            world.seed,
+            point.x as f64,
+            point.y as f64,
+            self.scale(world),
+            6,
+        )
+    }
+
+    /// Tile terrain, without rivers and lakes
+    pub fn tile(&self, world: &World, x: u64, y: u64) -> Tile {
+        let scale = self.scale(world);
+        let (fx, fy) = (x as f64, y as f64);
+        let elevation = self.elevation(world, &WorldPoint::new(x, y));
+
+        if elevation < self.sea_level {
+            let type_ = if elevation > self.sea_level - COAST_DEPTH {
+                TerrainType::Coast
+            } else {
+                TerrainType::Ocean
+            };
+            return Tile::new(type_);
+        }
+
+        // Altitude above sea level, in [0, 1]
+        let altitude = (elevation - self.sea_level) / (1.0 - self.sea_level);
+        // Close to 1.0 along noise "ridges", to draw mountain ranges
+        let ridge = 1.0
+            - (noise::fractal(world.seed.wrapping_add(RIDGE_SEED), fx, fy, scale / 4.0, 4) * 2.0
+                - 1.0)
+                .abs();
+        let relief = if altitude > 0.4 || (altitude > 0.1 && ridge > 0.95) {
+            Elevation::Mountains
+        } else if altitude > 0.25 || (altitude > 0.05 && ridge > 0.88) {
+            Elevation::Hills
+        } else {
+            Elevation::Flat
+        };
+
+        // Latitude is 0.0 at equator and 1.0 at poles
+        let latitude = (fy / world.height as f64 - 0.5).abs() * 2.0;
+        let temperature = 1.0 - latitude - altitude * 0.3
+            + (noise::fractal(
+                world.seed.wrapping_add(TEMPERATURE_SEED),
+                fx,
+                fy,
+                scale / 2.0,
+                3,
+            ) - 0.5)
+                * 0.2;
+        let moisture = noise::fractal(
+            world.seed.wrapping_add(MOISTURE_SEED),
+            fx,
+            fy,
+            scale / 2.0,
+            4,
+        );
+
+        let type_ = if temperature < 0.2 {
+            TerrainType::Tundra
+        } else if temperature > 0.65 && moisture < 0.4 {
+            TerrainType::Desert
+        } else if moisture > 0.5 {
+            TerrainType::GrassLand
+        } else {
+            TerrainType::Plain
+        };
+
+        let vegetation = match type_ {
+            _ if relief == Elevation::Mountains => None,
+            TerrainType::Desert => None,
+            TerrainType::Tundra => (moisture > 0.65).then_some(Vegetation::Forest),
+            _ if temperature > 0.65 && moisture > 0.62 => Some(Vegetation::Jungle),
+            _ if altitude < 0.03 && moisture > 0.6 => Some(Vegetation::Swamp),
+            _ if moisture > 0.58 => Some(Vegetation::Forest),
+            _ => None,
+        };
+
+        Tile::builder()
+            .type_(type_)
+            .elevation(relief)
+            .maybe_vegetation(vegetation)
+            .build()
+    }
+}
+
+impl Generator for ContinentGenerator {
+    fn generate_chunk(
+        &self,
+        world: &World,
+        chunk_x: u64,
+        chunk_y: u64,
+    ) -> Result<Chunk, WorldGeneratorError> {
+        let mut tiles = Vec::with_capacity((world.chunk_size * world.chunk_size) as usize);
+        let start = WorldPoint::new(chunk_x * world.chunk_size, chunk_y * world.chunk_size);
+        let end = WorldPoint::new(start.x + world.chunk_size, start.y + world.chunk_size);
+        let water = Hydrology::new(
+            world,
+            self.sea_level,
+            self.sea_level + (1.0 - self.sea_level) * RIVER_SOURCE_ALTITUDE,
+            |point: &WorldPoint| self.elevation(world, point),
+        )
+        .water(&start, &end);
+
+        for local_y in 0..world.chunk_size {
+            for local_x in 0..world.chunk_size {
+                let point = WorldPoint::new(start.x + local_x, start.y + local_y);
+                let tile = self.tile(world, point.x, point.y);
+
+                let tile = if tile.is_water() {
+                    tile
+                } else if water.is_lake(&point) {
+                    Tile::new(TerrainType::Lake)
+                } else {
+                    tile.with_rivers(water.rivers(&point))
+                };
+                let resource = resource(world, &point, &tile, self.resources_density);
+
+                tiles.push(tile.with_resource(resource));
+            }
+        }
+
+        Ok(Chunk {
+            x: chunk_x,
+            y: chunk_y,
+            tiles,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn world() -> World {
+        World::builder()
+            .chunk_size(8)
+            .width(32)
+            .height(16)
+            .seed(42)
+            .build()
+    }
+
+    #[test]
+    fn test_same_seed_same_chunk() {
+        let world = world();
+        let generator = ContinentGenerator::builder().build();
+
+        let chunk1 = generator.generate_chunk(&world, 2, 1).unwrap();
+        let _ = generator.generate_chunk(&world, 0, 0).unwrap();
+        let chunk2 = generator.generate_chunk(&world, 2, 1).unwrap();
+
+        assert_eq!(chunk1.tiles, chunk2.tiles);
+    }
+
+    #[test]
+    fn test_chunk_tiles_are_world_tiles() {
+        let world = world();
+        let big_chunk_world = World::builder()
+            .chunk_size(16)
+            .width(32)
+            .height(16)
+            .seed(42)
+            .build();
+        let generator = ContinentGenerator::builder().build();
+
+        let small_chunk = generator.generate_chunk(&world, 1, 1).unwrap();
+        let big_chunk = generator.generate_chunk(&big_chunk_world, 0, 0).unwrap();
+
+        // x=9, y=9 in world, with rivers traced across chunks identically
+        assert_eq!(small_chunk.tiles[8 + 1], big_chunk.tiles[9 * 16 + 9]);
+    }
+
+    #[test]
+    fn test_different_seed_different_world() {
+        let world = |seed| {
+            World::builder()
+                .chunk_size(64)
+                .width(64)
+                .height(64)
+                .seed(seed)
+                .build()
+        };
+        let generator = ContinentGenerator::builder().build();
+
+        assert_ne!(
+            generator.generate_chunk(&world(1), 0, 0).unwrap().tiles,
+            generator.generate_chunk(&world(2), 0, 0).unwrap().tiles
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Rivers and lakes. Rivers start from sources (at most one per cell of the world grid),
+//! follow the steepest slope down to the sea, or to a basin which is filled as a lake.
+//! Elevation only depends on coordinates, so a chunk traces every river able to reach it
+//! and stays consistent with its neighbors without sharing any state.
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use common::{
+    geo::{Direction, WorldPoint},
+    world::{RiverEdges, World},
+};
+use derive_more::Constructor;
+use strum::IntoEnumIterator;
+
+use super::noise;
+
+/// Size (in tiles) of grid cells containing at most one river source
+const CELL_SIZE: u64 = 16;
+/// Chance for a cell to contain a river source
+const SOURCE_PROBABILITY: f64 = 0.6;
+/// Maximum river length (in tiles)
+pub const MAX_RIVER_LENGTH: u64 = 64;
+/// Maximum lake size (in tiles)
+pub const MAX_LAKE_SIZE: usize = 8;
+/// Elevation above basin bottom still filled by a lake
+const LAKE_DEPTH: f64 = 0.01;
+const SOURCE_SEED: u64 = 4;
+
+#[derive(Debug, Default)]
+pub struct Water {
+    rivers: HashMap<WorldPoint, RiverEdges>,
+    lakes: HashSet<WorldPoint>,
+}
+
+impl Water {
+    pub fn rivers(&self, point: &WorldPoint) -> RiverEdges {
+        self.rivers.get(point).copied().unwrap_or_default()
+    }
+
+    pub fn is_lake(&self, point: &WorldPoint) -> bool {
+        self.lakes.contains(point)
+    }
+
+    fn connect(&mut self, from: WorldPoint, to: WorldPoint, direction: Direction) {
+        let from_edges = self.rivers.entry(from).or_default();
+        *from_edges = from_edges.with(direction);
+        let to_edges = self.rivers.entry(to).or_default();
+        *to_edges = to_edges.with(direction.opposite());
+    }
+}
+
+#[derive(Constructor)]
+pub struct Hydrology<'a, F: Fn(&WorldPoint) -> f64> {
+    world: &'a World,
+    sea_level: f64,
+    /// Minimum elevation of river sources
+    source_level: f64,
+    elevation: F,
+}
+
+impl<F: Fn(&WorldPoint) -> f64> Hydrology<'_, F> {
+    /// Rivers and lakes able to reach the area from `start` to `end` (excluded)
+    pub fn water(&self, start: &WorldPoint, end: &WorldPoint) -> Water {
+        let mut water = Water::default();
+        let margin = MAX_RIVER_LENGTH + MAX_LAKE_SIZE as u64;
+        let cells_start_x = start.x.saturating_sub(margin) / CELL_SIZE;
+        let cells_start_y = start.y.saturating_sub(margin) / CELL_SIZE;
+        let cells_end_x = (end.x + margin).min(self.world.width).div_ceil(CELL_SIZE);
+        let cells_end_y = (end.y + margin).min(self.world.height).div_ceil(CELL_SIZE);
+
+        for cell_y in cells_start_y..cells_end_y {
+            for cell_x in cells_start_x..cells_end_x {
+                if let Some(source) = self.source(cell_x, cell_y) {
+                    self.trace(source, &mut water);
+                }
+            }
+        }
+
+        water
+    }
+
+    fn source(&self, cell_x: u64, cell_y: u64) -> Option<WorldPoint> {
+        let seed = self.world.seed.wrapping_add(SOURCE_SEED);
+        if noise::hash(seed, cell_x as i64, cell_y as i64) >= SOURCE_PROBABILITY {
+            return None;
+        }
+
+        let offset = noise::mix(seed.wrapping_add(1), cell_x as i64, cell_y as i64);
+        let x = cell_x * CELL_SIZE + offset % CELL_SIZE;
+        let y = cell_y * CELL_SIZE + (offset / CELL_SIZE) % CELL_SIZE;
+        let point = WorldPoint::new(x, y);
+
+        (x < self.world.width
+            && y < self.world.height
+            && (self.elevation)(&point) >= self.source_level)
+            .then_some(point)
+    }
+
+    fn trace(&self, source: WorldPoint, water: &mut Water) {
+        let mut current = source;
+        let mut current_elevation = (self.elevation)(&current);
+
+        for _ in 0..MAX_RIVER_LENGTH {
+            let Some((direction, next, next_elevation)) = self
+                .neighbors(&current)
+                .into_iter()
+                .map(|(direction, point)| (direction, point, (self.elevation)(&point)))
+                .min_by(|a, b| a.2.total_cmp(&b.2))
+            else {
+                return;
+            };
+
+            if next_elevation >= current_elevation {
+                self.fill_lake(current, current_elevation, water);
+                return;
+            }
+
+            water.connect(current, next, direction);
+            if next_elevation < self.sea_level {
+                return;
+            }
+
+            current = next;
+            current_elevation = next_elevation;
+        }
+    }
+
+    fn fill_lake(&self, bottom: WorldPoint, level: f64, water: &mut Water) {
+        let mut queue = VecDeque::from([bottom]);
+        let mut visited = HashSet::from([bottom]);
+        let mut size = 0;
+
+        while let Some(point) = queue.pop_front() {
+            if size >= MAX_LAKE_SIZE {
+                break;
+            }
+            water.lakes.insert(point);
+            size += 1;
+
+            for (_, neighbor) in self.neighbors(&point) {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+
+                let elevation = (self.elevation)(&neighbor);
+                if elevation >= self.sea_level && elevation <= level + LAKE_DEPTH {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+
+    fn neighbors(&self, point: &WorldPoint) -> Vec<(Direction, WorldPoint)> {
+        Direction::iter()
+            .filter_map(|direction| {
+                let (dx, dy) = direction.delta();
+                let x = point.x as i64 + dx;
+                let y = point.y as i64 + dy;
+
+                (x >= 0
+                    && y >= 0
+                    && (x as u64) < self.world.width
+                    && (y as u64) < self.world.height)
+                    .then(|| (direction, WorldPoint::new(x as u64, y as u64)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn world() -> World {
+        World::builder().chunk_size(16).width(16).height(16).build()
+    }
+
+    #[test]
+    fn test_river_flows_to_sea() {
+        // GIVEN
+        let world = world();
+        // Slope going down to the west, valley on y=3
+        let elevation =
+            |point: &WorldPoint| point.x as f64 / 32.0 + (point.y as f64 - 3.0).abs() / 1000.0;
+        let hydrology = Hydrology::new(&world, 0.1, 0.2, elevation);
+        let mut water = Water::default();
+
+        // WHEN
+        hydrology.trace(WorldPoint::new(10, 3), &mut water);
+
+        // THEN
+        assert_eq!(
+            water.rivers(&WorldPoint::new(10, 3)).directions(),
+            vec![Direction::West]
+        );
+        assert_eq!(
+            water.rivers(&WorldPoint::new(5, 3)).directions(),
+            vec![Direction::East, Direction::West]
+        );
+        // x=3 is the first sea tile
+        assert_eq!(
+            water.rivers(&WorldPoint::new(3, 3)).directions(),
+            vec![Direction::East]
+        );
+        assert!(water.rivers(&WorldPoint::new(2, 3)).is_empty());
+        assert!(water.lakes.is_empty());
+    }
+
+    #[test]
+    fn test_river_fills_basin() {
+        // GIVEN
+        let world = world();
+        // Bowl centered on 8.8
+        let elevation = |point: &WorldPoint| {
+            0.5 + ((point.x as f64 - 8.0).powi(2) + (point.y as f64 - 8.0).powi(2)) / 1000.0
+        };
+        let hydrology = Hydrology::new(&world, 0.1, 0.2, elevation);
+        let mut water = Water::default();
+
+        // WHEN
+        hydrology.trace(WorldPoint::new(12, 8), &mut water);
+
+        // THEN
+        assert!(water.is_lake(&WorldPoint::new(8, 8)));
+        assert!(water.lakes.len() <= MAX_LAKE_SIZE);
+        assert!(water
+            .rivers(&WorldPoint::new(9, 8))
+            .contains(Direction::West));
+    }
+}
@@ -3,12 +3,23 @@ use common::{
     utils::Progress,
     world::{Chunk, World},
 };
+use rand::{rngs::StdRng, SeedableRng};
 use std::fs;
 use std::path::PathBuf;
 
 use crate::{writer::Writer, WorldGeneratorError};
 
+pub mod continent;
+pub mod hydrology;
+pub mod noise;
 pub mod random;
+pub mod resource;
+
+/// Random generator of given chunk. Derived from world seed, so a chunk can be
+/// regenerated alone with identical result.
+pub fn chunk_rng(world: &World, chunk_x: u64, chunk_y: u64) -> StdRng {
+    StdRng::seed_from_u64(noise::mix(world.seed, chunk_x as i64, chunk_y as i64))
+}
 
 pub trait Generator {
     #[allow(unused)]
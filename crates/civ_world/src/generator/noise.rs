@@ -0,0 +1,56 @@
+//! Deterministic value noise. Values only depend on seed and world coordinates, so any
+//! chunk can be generated alone and still match its neighbors.
+
+/// Pseudo random number for given seed and lattice point (splitmix64 mixing)
+pub fn mix(seed: u64, x: i64, y: i64) -> u64 {
+    let mut value = seed
+        ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+}
+
+/// Pseudo random value in [0, 1) for given seed and lattice point
+pub fn hash(seed: u64, x: i64, y: i64) -> f64 {
+    (mix(seed, x, y) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+fn smooth(t: f64) -> f64 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+/// Smoothly interpolated value noise in [0, 1)
+pub fn value(seed: u64, x: f64, y: f64) -> f64 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (tx, ty) = (smooth(x - x0), smooth(y - y0));
+    let (x0, y0) = (x0 as i64, y0 as i64);
+
+    let top = lerp(hash(seed, x0, y0), hash(seed, x0 + 1, y0), tx);
+    let bottom = lerp(hash(seed, x0, y0 + 1), hash(seed, x0 + 1, y0 + 1), tx);
+
+    lerp(top, bottom, ty)
+}
+
+/// Sum of `octaves` value noises (each one with double frequency and half amplitude),
+/// normalized in [0, 1). `scale` is the size (in tiles) of the biggest features.
+pub fn fractal(seed: u64, x: f64, y: f64, scale: f64, octaves: u32) -> f64 {
+    let mut total = 0.0;
+    let mut amplitude = 1.0;
+    let mut max = 0.0;
+    let mut frequency = 1.0 / scale;
+
+    for octave in 0..octaves {
+        let octave_seed = seed.wrapping_add(octave as u64);
+        total += value(octave_seed, x * frequency, y * frequency) * amplitude;
+        max += amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+
+    total / max
+}
@@ -1,9 +1,9 @@
 use common::world::{Chunk, TerrainType, Tile, World};
-use weighted_rand::builder::{NewBuilder, WalkerTableBuilder};
+use rand::distr::{weighted::WeightedIndex, Distribution};
 
 use crate::WorldGeneratorError;
 
-use super::Generator;
+use super::{chunk_rng, Generator};
 
 pub struct RandomGenerator;
 
@@ -19,12 +19,12 @@ impl Generator for RandomGenerator {
         let terrains = [TerrainType::GrassLand, TerrainType::Plain];
         let index_weights = [25, 100];
 
-        let builder = WalkerTableBuilder::new(&index_weights);
-        let wa_table = builder.build();
+        let weights = WeightedIndex::new(index_weights).expect("Static weights are valid");
+        let mut rng = chunk_rng(world, chunk_x, chunk_y);
 
         for _ in 0..world.chunk_size {
             for _ in 0..world.chunk_size {
-                tiles.push(Tile::new(terrains[wa_table.next()]));
+                tiles.push(Tile::new(terrains[weights.sample(&mut rng)]));
             }
         }
 
@@ -35,3 +35,25 @@ impl Generator for RandomGenerator {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_chunk_regeneration_is_identical() {
+        let world = World::builder()
+            .chunk_size(16)
+            .width(32)
+            .height(32)
+            .seed(42)
+            .build();
+
+        let chunk1 = RandomGenerator.generate_chunk(&world, 1, 0).unwrap();
+        let chunk2 = RandomGenerator.generate_chunk(&world, 1, 0).unwrap();
+        let other = RandomGenerator.generate_chunk(&world, 0, 1).unwrap();
+
+        assert_eq!(chunk1.tiles, chunk2.tiles);
+        assert_ne!(chunk1.tiles, other.tiles);
+    }
+}
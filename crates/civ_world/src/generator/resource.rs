@@ -0,0 +1,78 @@
+use common::{
+    geo::WorldPoint,
+    world::{Elevation, Resource, TerrainType, Tile, Vegetation, World},
+};
+
+use super::noise;
+
+/// Default part of tiles having a resource
+pub const DEFAULT_RESOURCES_DENSITY: f64 = 0.04;
+const RESOURCE_SEED: u64 = 6;
+
+/// Resources which can be found on given tile
+fn candidates(tile: &Tile) -> &'static [Resource] {
+    match (tile.type_(), tile.vegetation(), tile.elevation()) {
+        (TerrainType::Coast | TerrainType::Ocean | TerrainType::Lake, _, _) => &[Resource::Fish],
+        (_, Some(Vegetation::Jungle), _) => &[Resource::Fruit],
+        (_, Some(Vegetation::Swamp), _) => &[Resource::Oil],
+        (_, Some(Vegetation::Forest), _) => &[],
+        (_, None, Elevation::Mountains) => &[Resource::Gold, Resource::Iron],
+        (_, None, Elevation::Hills) => &[Resource::Coal, Resource::Iron],
+        (TerrainType::GrassLand, None, Elevation::Flat) => &[Resource::Horses],
+        (TerrainType::Plain, None, Elevation::Flat) => &[Resource::Wheat, Resource::Horses],
+        (TerrainType::Desert | TerrainType::Tundra, None, Elevation::Flat) => &[Resource::Oil],
+    }
+}
+
+/// Resource of tile at given point. Only depends on world seed and point, so chunks can be
+/// generated independently. `density` is the part of tiles having a resource.
+pub fn resource(world: &World, point: &WorldPoint, tile: &Tile, density: f64) -> Option<Resource> {
+    let seed = world.seed.wrapping_add(RESOURCE_SEED);
+    let (x, y) = (point.x as i64, point.y as i64);
+    if noise::hash(seed, x, y) >= density {
+        return None;
+    }
+
+    let candidates = candidates(tile);
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let index = noise::mix(seed.wrapping_add(1), x, y) % candidates.len() as u64;
+    Some(candidates[index as usize])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_resource_density() {
+        let world = World::builder()
+            .chunk_size(100)
+            .width(100)
+            .height(100)
+            .seed(42)
+            .build();
+        let tile = Tile::new(TerrainType::Plain);
+        let count = |density| {
+            (0..100)
+                .flat_map(|x| (0..100).map(move |y| WorldPoint::new(x, y)))
+                .filter_map(|point| resource(&world, &point, &tile, density))
+                .count()
+        };
+
+        assert_eq!(count(0.0), 0);
+        assert_eq!(count(1.0), 10_000);
+        assert!((300..500).contains(&count(DEFAULT_RESOURCES_DENSITY)));
+        assert_eq!(
+            resource(
+                &world,
+                &WorldPoint::new(0, 0),
+                &Tile::new(TerrainType::Ocean),
+                1.0
+            ),
+            Some(Resource::Fish)
+        );
+    }
+}
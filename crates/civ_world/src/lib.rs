@@ -2,9 +2,9 @@ use std::{io, path::PathBuf};
 
 use async_std::channel::Sender;
 use bon::{builder, Builder};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use common::{utils::Progress, world::World};
-use generator::Generator;
+use generator::{resource::DEFAULT_RESOURCES_DENSITY, Generator};
 use thiserror::Error;
 use writer::Writer;
 
@@ -21,6 +21,25 @@ pub struct Args {
     #[builder(default = 5000)]
     #[arg(short, long, default_value_t = 5000)]
     pub chunk_size: usize,
+    #[builder(default)]
+    #[arg(short, long, value_enum, default_value_t)]
+    pub generator: GeneratorType,
+    /// Generation seed (random if not given). Same seed and size produce same world
+    #[arg(short, long)]
+    pub seed: Option<u64>,
+    /// Part of tiles having a resource (continent generator)
+    #[builder(default = DEFAULT_RESOURCES_DENSITY)]
+    #[arg(short, long, default_value_t = DEFAULT_RESOURCES_DENSITY)]
+    pub resources_density: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum GeneratorType {
+    /// Random flat terrains
+    Random,
+    /// Noise based continents, oceans, mountain ranges and climate bands
+    #[default]
+    Continent,
 }
 
 #[builder]
@@ -60,6 +79,7 @@ pub fn run_<T: Generator>(
         .chunk_size(world.chunk_size)
         .width(world.width)
         .height(world.height)
+        .seed(world.seed)
         .build();
     generator.generate(&world, target, writer, progress)?;
 
@@ -86,6 +106,7 @@ impl From<Args> for World {
             .width(value.width as u64)
             .height(value.height as u64)
             .chunk_size(value.chunk_size as u64)
+            .seed(value.seed.unwrap_or_else(rand::random))
             .build()
     }
 }
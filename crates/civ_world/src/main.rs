@@ -1,15 +1,13 @@
 use std::thread;
 
 use async_std::channel::unbounded;
+use civ_world::generator::continent::ContinentGenerator;
 use civ_world::generator::random::RandomGenerator;
 use civ_world::writer::FilesWriter;
-use civ_world::{run, Args, WorldGeneratorError};
+use civ_world::{run, Args, GeneratorType, WorldGeneratorError};
 use clap::Parser;
 use common::utils::Progress;
 
-mod generator;
-mod writer;
-
 fn main() -> Result<(), WorldGeneratorError> {
     let args = Args::parse();
     let (progress_sender, progress_receiver) = unbounded();
@@ -17,15 +15,29 @@ fn main() -> Result<(), WorldGeneratorError> {
     thread::spawn(move || {
         let target = args.target.clone();
         let writer = FilesWriter::new(target.clone());
+        let generator = args.generator;
+        let resources_density = args.resources_density;
         let world = args.into();
-        let _ = run()
-            // TODO: Choose generator type by arg
-            .generator(RandomGenerator)
-            .target(&target)
-            .world(&world)
-            .writer(&writer)
-            .progress(progress_sender)
-            .call();
+        let _ = match generator {
+            GeneratorType::Random => run()
+                .generator(RandomGenerator)
+                .target(&target)
+                .world(&world)
+                .writer(&writer)
+                .progress(progress_sender)
+                .call(),
+            GeneratorType::Continent => run()
+                .generator(
+                    ContinentGenerator::builder()
+                        .resources_density(resources_density)
+                        .build(),
+                )
+                .target(&target)
+                .world(&world)
+                .writer(&writer)
+                .progress(progress_sender)
+                .call(),
+        };
     });
 
     while let Ok(progress) = progress_receiver.recv_blocking() {